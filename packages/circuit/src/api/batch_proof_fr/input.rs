@@ -1,5 +1,6 @@
+use std::io::{Read, Write};
+use std::path::Path;
 use std::str::FromStr;
-use std::{fs::read_to_string, path::Path};
 
 use franklin_crypto::bellman::plonk::better_better_cs::cs::{
     Circuit, ProvingAssembly, SetupAssembly, Width4MainGateWithDNext,
@@ -7,7 +8,7 @@ use franklin_crypto::bellman::plonk::better_better_cs::cs::{
 use franklin_crypto::bellman::plonk::better_better_cs::proof::Proof;
 use franklin_crypto::bellman::plonk::better_better_cs::setup::VerificationKey;
 use franklin_crypto::bellman::plonk::commitments::transcript::keccak_transcript::RollingKeccakTranscript;
-use franklin_crypto::bellman::{ScalarEngine, SynthesisError};
+use franklin_crypto::bellman::ScalarEngine;
 use franklin_crypto::plonk::circuit::Width4WithCustomGates;
 use franklin_crypto::{
     bellman::kate_commitment::{Crs, CrsForMonomialForm},
@@ -19,13 +20,27 @@ use franklin_crypto::{
     bellman::pairing::bn256::{Bn256, Fr, G1Affine},
     plonk::circuit::verifier_circuit::affine_point_wrapper::aux_data::BN256AuxData,
 };
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use verkle_tree::ipa_fr::config::IpaConfig;
 use verkle_tree::ipa_fr::proof::IpaProof;
 use verkle_tree::ipa_fr::rns::BaseRnsParameters;
 
-use crate::circuit::batch_proof_fr::BatchProofCircuit;
+use crate::api::format::{
+    invalid_input, num_ipa_rounds, write_g1_into, CircuitKind, Curve, FormatError, FrameReader,
+    FrameWriter, Header,
+};
+use crate::api::ipa_fr::input::SerializableIpaProof;
+use crate::api::point::PointEncoding;
+use crate::api::utils::{check_crs_size, g1_from_hex_pair, g1_to_hex_pair};
+use crate::circuit::batch_proof_fr::UnlabeledBatchProofCircuit;
+use crate::circuit::debug::check_satisfied;
 use crate::circuit::ipa_fr::proof::OptionIpaProof;
+use crate::circuit::utils::{
+    field_element_from_hex, field_element_to_hex, write_field_element_le_into,
+};
+use crate::error::CircuitError;
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BatchProofCircuitInput {
     pub proof: IpaProof<G1Affine>,
     pub d: G1Affine,
@@ -36,7 +51,8 @@ pub struct BatchProofCircuitInput {
 
 #[cfg(test)]
 mod batch_proof_api_tests {
-    use std::fs::{File, OpenOptions};
+    use std::fs::{read_to_string, File, OpenOptions};
+    use std::io::Write;
     use std::path::Path;
 
     use franklin_crypto::bellman::kate_commitment::{Crs, CrsForMonomialForm};
@@ -48,6 +64,8 @@ mod batch_proof_api_tests {
     use verkle_tree::ipa_fr::transcript::{PoseidonBn256Transcript, Bn256Transcript};
     use verkle_tree::ipa_fr::utils::test_poly;
 
+    use crate::api::format::FormatError;
+
     use super::{BatchProofCircuitInput, VkAndProof};
 
     const CIRCUIT_NAME: &str = "batch_proof_fr";
@@ -99,6 +117,100 @@ mod batch_proof_api_tests {
         crs
     }
 
+    fn make_round_trip_input() -> anyhow::Result<BatchProofCircuitInput> {
+        let domain_size = 4;
+        let ipa_conf = IpaConfig::<G1Affine>::new(domain_size);
+        let rns_params = &BaseRnsParameters::<Bn256>::new_for_field(68, 110, 4);
+
+        let polys = vec![vec![12, 97, 37, 0], vec![1, 208, 132, 3], vec![5, 4, 3, 2]];
+        let fs = polys
+            .iter()
+            .map(|poly| test_poly::<Fr>(poly, domain_size))
+            .collect::<Vec<_>>();
+        let zs = vec![1, 0, 3];
+        let prover_transcript = PoseidonBn256Transcript::with_bytes(b"multi_proof");
+
+        make_test_input(
+            fs,
+            zs,
+            prover_transcript.into_params(),
+            rns_params,
+            &ipa_conf,
+        )
+    }
+
+    #[test]
+    fn test_batch_proof_circuit_input_read_write() -> Result<(), Box<dyn std::error::Error>> {
+        let circuit_input = make_round_trip_input()?;
+
+        let file_path = Path::new("./test_cases")
+            .join(CIRCUIT_NAME)
+            .join("public_inputs");
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&file_path)?;
+        circuit_input.write_into(&mut file)?;
+        println!("write circuit_input into {:?}", file_path);
+
+        let circuit_input2 = BatchProofCircuitInput::from_path(&file_path)?;
+        println!("read circuit_input2 from {:?}", file_path);
+        assert_eq!(circuit_input, circuit_input2);
+
+        let circuit_input3 = BatchProofCircuitInput::from_bytes(&circuit_input.to_bytes())?;
+        assert_eq!(circuit_input, circuit_input3);
+
+        // A truncated input is rejected.
+        let bytes = circuit_input.to_bytes();
+        assert!(matches!(
+            BatchProofCircuitInput::from_bytes(&bytes[..(bytes.len() - 1)]),
+            Err(FormatError::Truncated)
+        ));
+
+        // An evaluation point out of the domain is rejected.
+        let mut invalid_input = circuit_input.clone();
+        invalid_input.zs[0] = 4;
+        assert!(matches!(
+            BatchProofCircuitInput::from_bytes(&invalid_input.to_bytes()),
+            Err(FormatError::InvalidValue {
+                section: "zs",
+                value: 4
+            })
+        ));
+
+        // An input whose lengths are inconsistent is not written.
+        let mut invalid_input = circuit_input.clone();
+        invalid_input.ys.pop();
+        assert!(invalid_input.write_into(&mut Vec::<u8>::new()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_proof_circuit_input_serde_json() -> Result<(), Box<dyn std::error::Error>> {
+        let circuit_input = make_round_trip_input()?;
+
+        let file_path = Path::new("./test_cases")
+            .join(CIRCUIT_NAME)
+            .join("public_inputs.json");
+        let j = serde_json::to_string(&circuit_input)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&file_path)?;
+        write!(file, "{}", j)?;
+        println!("write circuit_input into {:?}", file_path);
+
+        let raw = read_to_string(&file_path)?;
+        let circuit_input2: BatchProofCircuitInput = serde_json::from_str(&raw)?;
+        println!("read circuit_input2 from {:?}", file_path);
+        assert_eq!(circuit_input, circuit_input2);
+
+        Ok(())
+    }
+
     #[test]
     fn test_batch_proof_circuit_case1() -> Result<(), Box<dyn std::error::Error>> {
         let crs = open_crs_for_log2_of_size(23);
@@ -160,8 +272,8 @@ mod batch_proof_api_tests {
 }
 
 pub struct VkAndProof<'a, WP: WrappedAffinePoint<'a, Bn256>, AD: AuxData<Bn256>>(
-    pub VerificationKey<Bn256, BatchProofCircuit<'a, Bn256, WP, AD>>,
-    pub Proof<Bn256, BatchProofCircuit<'a, Bn256, WP, AD>>,
+    pub VerificationKey<Bn256, UnlabeledBatchProofCircuit<'a, Bn256, WP, AD>>,
+    pub Proof<Bn256, UnlabeledBatchProofCircuit<'a, Bn256, WP, AD>>,
 );
 
 impl BatchProofCircuitInput {
@@ -171,7 +283,8 @@ impl BatchProofCircuitInput {
         ipa_conf: IpaConfig<G1Affine>,
         rns_params: &'a BaseRnsParameters<Bn256>,
         crs: Crs<Bn256, CrsForMonomialForm>,
-    ) -> Result<VkAndProof<'a, WP, BN256AuxData>, SynthesisError> {
+    ) -> Result<VkAndProof<'a, WP, BN256AuxData>, CircuitError> {
+        let _span = tracing::info_span!("create_plonk_proof", circuit = "batch-proof-fr").entered();
         // let dummy_circuit = PoseidonCircuit::<Bn256> {
         //   inputs: inputs.iter().map(|&_| None).collect::<Vec<_>>(),
         //   output: None,
@@ -179,7 +292,7 @@ impl BatchProofCircuitInput {
 
         let aux_data = BN256AuxData::new();
         let wrapped_proof = OptionIpaProof::from(self.proof.clone());
-        let circuit = BatchProofCircuit::<'a, Bn256, WP, BN256AuxData> {
+        let circuit = UnlabeledBatchProofCircuit::<'a, Bn256, WP, BN256AuxData> {
             transcript_params: Some(transcript_params),
             proof: wrapped_proof,
             d: Some(self.d),
@@ -194,13 +307,13 @@ impl BatchProofCircuitInput {
             rns_params,
             aux_data,
             _wp: std::marker::PhantomData,
+            _transcript: std::marker::PhantomData,
         };
 
+        let span = tracing::info_span!("setup").entered();
         let mut dummy_assembly =
             SetupAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        circuit
-            .synthesize(&mut dummy_assembly)
-            .expect("must synthesize");
+        circuit.synthesize(&mut dummy_assembly)?;
         dummy_assembly.finalize();
 
         // println!("Checking if satisfied");
@@ -209,24 +322,29 @@ impl BatchProofCircuitInput {
 
         let worker = franklin_crypto::bellman::worker::Worker::new();
         let setup = dummy_assembly
-            .create_setup::<BatchProofCircuit<'a, Bn256, WP, BN256AuxData>>(&worker)?;
+            .create_setup::<UnlabeledBatchProofCircuit<'a, Bn256, WP, BN256AuxData>>(&worker)?;
+        check_crs_size(&crs, setup.n)?;
 
         let vk =
-            VerificationKey::<Bn256, BatchProofCircuit<'a, Bn256, WP, BN256AuxData>>::from_setup(
+            VerificationKey::<Bn256, UnlabeledBatchProofCircuit<'a, Bn256, WP, BN256AuxData>>::from_setup(
                 &setup, &worker, &crs,
             )?;
 
+        drop(span);
+
+        let span = tracing::info_span!("prove").entered();
+        check_satisfied(&circuit)?;
+
         let mut assembly =
             ProvingAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        circuit.synthesize(&mut assembly).expect("must synthesize");
+        circuit.synthesize(&mut assembly)?;
         assembly.finalize();
 
-        println!("prove");
-
         let proof = assembly
-            .create_proof::<BatchProofCircuit<'a, Bn256, WP, BN256AuxData>, RollingKeccakTranscript<<Bn256 as ScalarEngine>::Fr>>(
+            .create_proof::<UnlabeledBatchProofCircuit<'a, Bn256, WP, BN256AuxData>, RollingKeccakTranscript<<Bn256 as ScalarEngine>::Fr>>(
                 &worker, &setup, &crs, None,
             )?;
+        drop(span);
 
         // assert_eq!(
         //     proof.inputs,
@@ -239,21 +357,237 @@ impl BatchProofCircuitInput {
         Ok(result)
     }
 
-    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
-        let json_str = read_to_string(path)?;
+    pub fn from_path(path: &Path) -> Result<Self, FormatError> {
+        let bytes = std::fs::read(path)?;
+
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+        Self::read_from(&mut std::io::Cursor::new(bytes))
+    }
+
+    /// Read an input framed as
+    /// `header(BatchProofFr, Bn256, domain_size) | [L] | [R] | [a] | [D] | [commitments] | [ys] | [zs]`,
+    /// where each of `zs` is a byte.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, FormatError> {
+        let mut frame = FrameReader::new(reader, CircuitKind::BatchProofFr, Curve::Bn256, 7)?;
+        let domain_size = frame.header().domain_size;
+        let num_ipa_rounds = num_ipa_rounds(domain_size)?;
+
+        let mut proof_points = vec![];
+        for name in ["L", "R"] {
+            let mut section = frame.read_section(name)?;
+            let points = section.read_g1s()?;
+            section.finish()?;
+            if points.len() != num_ipa_rounds {
+                return Err(FormatError::LengthMismatch {
+                    section: name,
+                    expected: num_ipa_rounds,
+                    found: points.len(),
+                });
+            }
+            proof_points.push(points);
+        }
+        let proof_r = proof_points.pop().unwrap();
+        let proof_l = proof_points.pop().unwrap();
+
+        let mut section = frame.read_section("a")?;
+        let proof_a = section.read_field_element()?;
+        section.finish()?;
+        let proof = IpaProof {
+            l: proof_l,
+            r: proof_r,
+            a: proof_a,
+        };
+
+        let mut section = frame.read_section("D")?;
+        let d = section.read_g1()?;
+        section.finish()?;
+
+        let mut section = frame.read_section("commitments")?;
+        let commitments = section.read_g1s()?;
+        section.finish()?;
+        let num_commitments = commitments.len();
+
+        let mut section = frame.read_section("ys")?;
+        let ys = section.read_field_elements()?;
+        section.finish()?;
+        if ys.len() != num_commitments {
+            return Err(FormatError::LengthMismatch {
+                section: "ys",
+                expected: num_commitments,
+                found: ys.len(),
+            });
+        }
+
+        let mut section = frame.read_section("zs")?;
+        let mut zs = vec![];
+        for _ in 0..section.count(1)? {
+            let z = section.read_u8()?;
+            if z as u64 >= domain_size {
+                return Err(FormatError::InvalidValue {
+                    section: "zs",
+                    value: z as u64,
+                });
+            }
+            zs.push(z);
+        }
+        section.finish()?;
+        if zs.len() != num_commitments {
+            return Err(FormatError::LengthMismatch {
+                section: "zs",
+                expected: num_commitments,
+                found: zs.len(),
+            });
+        }
+        frame.finish()?;
+
+        let input = Self {
+            proof,
+            d,
+            commitments,
+            ys,
+            zs,
+        };
+
+        Ok(input)
+    }
+
+    /// Write the input framed as
+    /// `header(BatchProofFr, Bn256, domain_size) | [L] | [R] | [a] | [D] | [commitments] | [ys] | [zs]`.
+    pub fn write_into<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let num_ipa_rounds = self.proof.l.len();
+        if self.proof.r.len() != num_ipa_rounds || num_ipa_rounds >= 64 {
+            return Err(invalid_input(format!(
+                "invalid numbers of IPA rounds: {} and {}",
+                num_ipa_rounds,
+                self.proof.r.len()
+            )));
+        }
+        if self.ys.len() != self.commitments.len() || self.zs.len() != self.commitments.len() {
+            return Err(invalid_input(format!(
+                "invalid lengths of commitments, ys and zs: {}, {} and {}",
+                self.commitments.len(),
+                self.ys.len(),
+                self.zs.len()
+            )));
+        }
+
+        let header = Header {
+            kind: CircuitKind::BatchProofFr,
+            curve: Curve::Bn256,
+            point_encoding: PointEncoding::Uncompressed,
+            domain_size: 1 << num_ipa_rounds,
+        };
+        let mut frame = FrameWriter::new(writer, header, 7)?;
+
+        for points in [&self.proof.l, &self.proof.r] {
+            let mut section = vec![];
+            for point in points.iter() {
+                write_g1_into(point, &mut section)?;
+            }
+            frame.write_section(&section)?;
+        }
+
+        let mut section = vec![];
+        write_field_element_le_into(self.proof.a, &mut section)?;
+        frame.write_section(&section)?;
+
+        let mut section = vec![];
+        write_g1_into(&self.d, &mut section)?;
+        frame.write_section(&section)?;
+
+        let mut section = vec![];
+        for commitment in self.commitments.iter() {
+            write_g1_into(commitment, &mut section)?;
+        }
+        frame.write_section(&section)?;
+
+        let mut section = vec![];
+        for &y in self.ys.iter() {
+            write_field_element_le_into(y, &mut section)?;
+        }
+        frame.write_section(&section)?;
+
+        frame.write_section(&self.zs)?;
 
-        Self::from_str(&json_str)
+        Ok(())
     }
 
-    pub fn from_bytes(_bytes: &[u8]) -> anyhow::Result<Self> {
-        todo!()
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_into(&mut bytes).unwrap();
+
+        bytes
     }
 }
 
 impl FromStr for BatchProofCircuitInput {
-    type Err = anyhow::Error;
+    type Err = FormatError;
 
-    fn from_str(s: &str) -> anyhow::Result<Self> {
+    fn from_str(s: &str) -> Result<Self, FormatError> {
         Self::from_bytes(s.as_bytes())
     }
 }
+
+/// `SerializableBatchProofCircuitInput` is needed in the process of serializing `BatchProofCircuitInput`.
+/// `BatchProofCircuitInput` is serialized by treating `Fr` and the coordinates of `G1Affine` as hex strings.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct SerializableBatchProofCircuitInput {
+    proof: SerializableIpaProof,
+    d: (String, String),
+    commitments: Vec<(String, String)>,
+    ys: Vec<String>,
+    zs: Vec<u8>,
+}
+
+impl Serialize for BatchProofCircuitInput {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let new_self = SerializableBatchProofCircuitInput {
+            proof: SerializableIpaProof::from_proof(&self.proof),
+            d: g1_to_hex_pair(&self.d),
+            commitments: self.commitments.iter().map(g1_to_hex_pair).collect(),
+            ys: self.ys.iter().map(|&y| field_element_to_hex(y)).collect(),
+            zs: self.zs.clone(),
+        };
+
+        new_self.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BatchProofCircuitInput {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = SerializableBatchProofCircuitInput::deserialize(deserializer)?;
+        let parse = || -> anyhow::Result<Self> {
+            anyhow::ensure!(
+                raw.commitments.len() == raw.ys.len() && raw.commitments.len() == raw.zs.len(),
+                "the numbers of commitments, ys and zs are different"
+            );
+
+            Ok(Self {
+                proof: raw.proof.to_proof()?,
+                d: g1_from_hex_pair(&raw.d)?,
+                commitments: raw
+                    .commitments
+                    .iter()
+                    .map(g1_from_hex_pair)
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+                ys: raw
+                    .ys
+                    .iter()
+                    .map(|y| field_element_from_hex(y))
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+                zs: raw.zs.clone(),
+            })
+        };
+
+        parse().map_err(serde::de::Error::custom)
+    }
+}
@@ -1,8 +1,10 @@
-use std::{fs::read_to_string, path::Path, str::FromStr};
+use std::{
+    io::{Read, Write},
+    path::Path,
+    str::FromStr,
+};
 
-use byteorder::{LittleEndian, ReadBytesExt};
 use franklin_crypto::{
-    babyjubjub::{edwards, JubjubBn256, JubjubEngine, Unknown},
     bellman::{
         kate_commitment::{Crs, CrsForMonomialForm},
         pairing::bn256::{Bn256, Fr},
@@ -14,7 +16,6 @@ use franklin_crypto::{
             },
             commitments::transcript::keccak_transcript::RollingKeccakTranscript,
         },
-        PrimeField, PrimeFieldRepr, ScalarEngine, SynthesisError,
     },
     plonk::circuit::{
         bigint::field::RnsParameters, verifier_circuit::affine_point_wrapper::WrappedAffinePoint,
@@ -27,14 +28,22 @@ use verkle_tree::{
 };
 // use serde::{Deserialize, Serialize};
 
+use crate::api::format::{
+    invalid_input, num_ipa_rounds, CircuitKind, FormatError, FrameReader, FrameWriter, Header,
+    JubjubCurve,
+};
+use crate::api::point::{write_jubjub_point_into, PointEncoding};
+use crate::api::utils::check_crs_size;
 use crate::circuit::{
-    batch_proof_fs::BatchProofCircuit, ipa_fs::proof::OptionIpaProof,
-    utils::read_field_element_le_from,
+    batch_proof_fs::UnlabeledBatchProofCircuit, debug::check_satisfied,
+    ipa_fs::proof::OptionIpaProof, num::jubjub::JubjubEngine, utils::write_field_element_le_into,
 };
+use crate::error::CircuitError;
 
+/// verkle-tree makes batch proofs only over BN256, so this input is not generic over `JubjubCurve`.
 #[derive(Clone)]
 pub struct BatchProofCircuitInput {
-    pub commitments: Vec<edwards::Point<Bn256, Unknown>>,
+    pub commitments: Vec<<Bn256 as JubjubEngine>::Point>,
     pub proof: BatchProof<Bn256>,
     pub ys: Vec<<Bn256 as JubjubEngine>::Fs>,
     pub zs: Vec<usize>,
@@ -258,79 +267,101 @@ mod batch_proof_fs_api_tests {
 }
 
 impl FromStr for BatchProofCircuitInput {
-    type Err = anyhow::Error;
+    type Err = FormatError;
 
-    fn from_str(s: &str) -> anyhow::Result<Self> {
+    fn from_str(s: &str) -> Result<Self, FormatError> {
         Self::from_bytes(s.as_bytes())
     }
 }
 
 impl BatchProofCircuitInput {
-    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
-        let json_str = read_to_string(path)?;
+    pub fn from_path(path: &Path) -> Result<Self, FormatError> {
+        let bytes = std::fs::read(path)?;
 
-        Self::from_str(&json_str)
+        Self::from_bytes(&bytes)
     }
 
-    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
-        let jubjub_params = &JubjubBn256::new();
-        let reader = &mut std::io::Cursor::new(bytes.to_vec());
-        let num_rounds = reader.read_u64::<LittleEndian>()?;
-
-        let mut proof_l = vec![];
-        for _ in 0..num_rounds {
-            let lix: Fr = read_field_element_le_from(reader)?;
-            let liy: Fr = read_field_element_le_from(reader)?;
-            let li =
-                edwards::Point::get_for_y(liy, lix.into_repr().is_odd(), jubjub_params).unwrap();
-            proof_l.push(li);
-        }
-        let mut proof_r = vec![];
-        for _ in 0..num_rounds {
-            let rix: Fr = read_field_element_le_from(reader)?;
-            let riy: Fr = read_field_element_le_from(reader)?;
-            let ri =
-                edwards::Point::get_for_y(riy, rix.into_repr().is_odd(), jubjub_params).unwrap();
-            proof_r.push(ri);
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+        Self::read_from(&mut std::io::Cursor::new(bytes))
+    }
+
+    /// Read an input framed as
+    /// `header(BatchProofFs, JubjubBn256, domain_size) | [L] | [R] | [a] | [D] | [commitments] | [ys] | [zs]`,
+    /// where each of `zs` is a byte.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, FormatError> {
+        let jubjub_params = &Bn256::jubjub_params();
+        let mut frame = FrameReader::new(reader, CircuitKind::BatchProofFs, Bn256::CURVE, 7)?;
+        let domain_size = frame.header().domain_size;
+        let num_ipa_rounds = num_ipa_rounds(domain_size)?;
+
+        let mut proof_points = vec![];
+        for name in ["L", "R"] {
+            let mut section = frame.read_section(name)?;
+            let points = section.read_jubjub_points::<Bn256>(jubjub_params)?;
+            section.finish()?;
+            if points.len() != num_ipa_rounds {
+                return Err(FormatError::LengthMismatch {
+                    section: name,
+                    expected: num_ipa_rounds,
+                    found: points.len(),
+                });
+            }
+            proof_points.push(points);
         }
-        let proof_a: <Bn256 as JubjubEngine>::Fs = read_field_element_le_from(reader)?;
+        let proof_r = proof_points.pop().unwrap();
+        let proof_l = proof_points.pop().unwrap();
+
+        let mut section = frame.read_section("a")?;
+        let proof_a = section.read_field_element::<<Bn256 as JubjubEngine>::Fs>()?;
+        section.finish()?;
         let ipa_proof = IpaProof {
             l: proof_l,
             r: proof_r,
             a: proof_a,
         };
-        let dx: Fr = read_field_element_le_from(reader)?;
-        let dy: Fr = read_field_element_le_from(reader)?;
-        let d = edwards::Point::get_for_y(dy, dx.into_repr().is_odd(), jubjub_params).unwrap();
-        let proof = BatchProof { ipa: ipa_proof, d };
 
-        let num_commitments = reader.read_u64::<LittleEndian>()?;
-        let mut commitments = vec![];
-        for _ in 0..num_commitments {
-            let commitment_x: Fr = read_field_element_le_from(reader)?;
-            let commitment_y: Fr = read_field_element_le_from(reader)?;
-            let commitment = edwards::Point::get_for_y(
-                commitment_y,
-                commitment_x.into_repr().is_odd(),
-                jubjub_params,
-            )
-            .unwrap();
-            commitments.push(commitment);
-        }
+        let mut section = frame.read_section("D")?;
+        let d = section.read_jubjub_point::<Bn256>(jubjub_params)?;
+        section.finish()?;
+        let proof = BatchProof { ipa: ipa_proof, d };
 
-        let mut ys = vec![];
-        for _ in 0..num_commitments {
-            let eval_point = read_field_element_le_from(reader)?;
-            ys.push(eval_point);
+        let mut section = frame.read_section("commitments")?;
+        let commitments = section.read_jubjub_points::<Bn256>(jubjub_params)?;
+        section.finish()?;
+        let num_commitments = commitments.len();
+
+        let mut section = frame.read_section("ys")?;
+        let ys = section.read_field_elements()?;
+        section.finish()?;
+        if ys.len() != num_commitments {
+            return Err(FormatError::LengthMismatch {
+                section: "ys",
+                expected: num_commitments,
+                found: ys.len(),
+            });
         }
 
-        let z_size_bytes = reader.read_u8()?;
-        assert_eq!(z_size_bytes, 1);
+        let mut section = frame.read_section("zs")?;
         let mut zs = vec![];
-        for _ in 0..num_commitments {
-            let inner_prod = reader.read_u8()?;
-            zs.push(inner_prod as usize);
+        for _ in 0..section.count(1)? {
+            let z = section.read_u8()? as usize;
+            if z as u64 >= domain_size {
+                return Err(FormatError::InvalidValue {
+                    section: "zs",
+                    value: z as u64,
+                });
+            }
+            zs.push(z);
+        }
+        section.finish()?;
+        if zs.len() != num_commitments {
+            return Err(FormatError::LengthMismatch {
+                section: "zs",
+                expected: num_commitments,
+                found: zs.len(),
+            });
         }
+        frame.finish()?;
 
         let input = Self {
             commitments,
@@ -341,11 +372,96 @@ impl BatchProofCircuitInput {
 
         Ok(input)
     }
+
+    /// Write the input framed as
+    /// `header(BatchProofFs, JubjubBn256, domain_size) | [L] | [R] | [a] | [D] | [commitments] | [ys] | [zs]`.
+    /// The points are written uncompressed.
+    pub fn write_into<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_into_with_encoding(writer, PointEncoding::Uncompressed)
+    }
+
+    /// Write the input framed in the same way as `write_into`, where the points are written with `encoding`.
+    pub fn write_into_with_encoding<W: Write>(
+        &self,
+        writer: &mut W,
+        encoding: PointEncoding,
+    ) -> std::io::Result<()> {
+        let ipa_proof = &self.proof.ipa;
+        let num_ipa_rounds = ipa_proof.l.len();
+        if ipa_proof.r.len() != num_ipa_rounds || num_ipa_rounds >= 64 {
+            return Err(invalid_input(format!(
+                "invalid numbers of IPA rounds: {} and {}",
+                num_ipa_rounds,
+                ipa_proof.r.len()
+            )));
+        }
+        if self.ys.len() != self.commitments.len() || self.zs.len() != self.commitments.len() {
+            return Err(invalid_input(format!(
+                "invalid lengths of commitments, ys and zs: {}, {} and {}",
+                self.commitments.len(),
+                self.ys.len(),
+                self.zs.len()
+            )));
+        }
+
+        let header = Header {
+            kind: CircuitKind::BatchProofFs,
+            curve: Bn256::CURVE,
+            point_encoding: encoding,
+            domain_size: 1 << num_ipa_rounds,
+        };
+        let mut frame = FrameWriter::new(writer, header, 7)?;
+
+        for points in [&ipa_proof.l, &ipa_proof.r] {
+            let mut section = vec![];
+            for point in points.iter() {
+                write_jubjub_point_into(point, encoding, &mut section)?;
+            }
+            frame.write_section(&section)?;
+        }
+
+        let mut section = vec![];
+        write_field_element_le_into(ipa_proof.a, &mut section)?;
+        frame.write_section(&section)?;
+
+        let mut section = vec![];
+        write_jubjub_point_into(&self.proof.d, encoding, &mut section)?;
+        frame.write_section(&section)?;
+
+        let mut section = vec![];
+        for commitment in self.commitments.iter() {
+            write_jubjub_point_into(commitment, encoding, &mut section)?;
+        }
+        frame.write_section(&section)?;
+
+        let mut section = vec![];
+        for &y in self.ys.iter() {
+            write_field_element_le_into(y, &mut section)?;
+        }
+        frame.write_section(&section)?;
+
+        let mut section = vec![];
+        for &z in self.zs.iter() {
+            let z = u8::try_from(z)
+                .map_err(|_| invalid_input(format!("too large evaluation point: {}", z)))?;
+            section.push(z);
+        }
+        frame.write_section(&section)?;
+
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_into(&mut bytes).unwrap();
+
+        bytes
+    }
 }
 
 impl BatchProofCircuitInput {
     pub fn new(
-        commitments: Vec<edwards::Point<Bn256, Unknown>>,
+        commitments: Vec<<Bn256 as JubjubEngine>::Point>,
         proof: BatchProof<Bn256>,
         ys: Vec<<Bn256 as JubjubEngine>::Fs>,
         zs: Vec<usize>,
@@ -363,11 +479,11 @@ impl BatchProofCircuitInput {
         transcript_params: Fr,
         ipa_conf: &'c IpaConfig<'b, Bn256>,
         rns_params: &'a RnsParameters<Bn256, <Bn256 as JubjubEngine>::Fs>,
-    ) -> BatchProofCircuit<'a, 'b, 'c, Bn256>
+    ) -> UnlabeledBatchProofCircuit<'a, 'b, 'c, Bn256>
     where
         'c: 'b,
     {
-        BatchProofCircuit::<Bn256> {
+        UnlabeledBatchProofCircuit::<Bn256> {
             transcript_params: Some(transcript_params),
             commitments: self
                 .commitments
@@ -380,6 +496,7 @@ impl BatchProofCircuitInput {
             zs: self.zs.iter().map(|&zi| Some(zi)).collect::<Vec<_>>(),
             ipa_conf,
             rns_params,
+            _transcript: std::marker::PhantomData,
         }
     }
 
@@ -468,19 +585,19 @@ impl BatchProofCircuitInput {
         crs: Crs<Bn256, CrsForMonomialForm>,
     ) -> Result<
         (
-            VerificationKey<Bn256, BatchProofCircuit<Bn256>>,
-            Proof<Bn256, BatchProofCircuit<Bn256>>,
+            VerificationKey<Bn256, UnlabeledBatchProofCircuit<Bn256>>,
+            Proof<Bn256, UnlabeledBatchProofCircuit<Bn256>>,
         ),
-        SynthesisError,
+        CircuitError,
     > {
+        let _span = tracing::info_span!("create_plonk_proof", circuit = "batch-proof-fs").entered();
         let circuit = self.make_circuit_for_proving(transcript_params, ipa_conf, rns_params);
-        let _dummy_circuit = BatchProofCircuit::<Bn256>::initialize(ipa_conf, rns_params);
+        let _dummy_circuit = UnlabeledBatchProofCircuit::<Bn256>::initialize(ipa_conf, rns_params);
 
+        let span = tracing::info_span!("setup").entered();
         let mut dummy_assembly =
             SetupAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        circuit
-            .synthesize(&mut dummy_assembly)
-            .expect("must synthesize dummy circuit"); // TODO: use `dummy_circuit` instead of `circuit`
+        circuit.synthesize(&mut dummy_assembly)?; // TODO: use `dummy_circuit` instead of `circuit`
         dummy_assembly.finalize();
 
         // println!("Checking if satisfied");
@@ -489,43 +606,28 @@ impl BatchProofCircuitInput {
 
         let worker = franklin_crypto::bellman::worker::Worker::new();
 
-        let start = std::time::Instant::now();
+        let setup = dummy_assembly.create_setup::<UnlabeledBatchProofCircuit<Bn256>>(&worker)?;
+        check_crs_size(&crs, setup.n)?;
 
-        let setup = dummy_assembly
-            .create_setup::<BatchProofCircuit<Bn256>>(&worker)
-            .unwrap();
+        let vk = VerificationKey::<Bn256, UnlabeledBatchProofCircuit<Bn256>>::from_setup(
+            &setup, &worker, &crs,
+        )?;
 
-        println!(
-            "reduction ends: {} s",
-            start.elapsed().as_millis() as f64 / 1000.0
-        );
+        drop(span);
 
-        let vk =
-            VerificationKey::<Bn256, BatchProofCircuit<Bn256>>::from_setup(&setup, &worker, &crs)
-                .unwrap();
+        let span = tracing::info_span!("prove").entered();
+        check_satisfied(&circuit)?;
 
         let mut assembly =
             ProvingAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        circuit
-            .synthesize(&mut assembly)
-            .expect("must synthesize circuit");
+        circuit.synthesize(&mut assembly)?;
         assembly.finalize();
 
-        println!("prove");
-
-        let start = std::time::Instant::now();
-
         let proof = assembly
-            .create_proof::<BatchProofCircuit<Bn256>, RollingKeccakTranscript<<Bn256 as ScalarEngine>::Fr>>(
+            .create_proof::<UnlabeledBatchProofCircuit<Bn256>, RollingKeccakTranscript<Fr>>(
                 &worker, &setup, &crs, None,
-            ).expect("fail to create proof");
-
-        println!(
-            "reduction ends: {} s",
-            start.elapsed().as_millis() as f64 / 1000.0
-        );
-
-        dbg!(&proof.inputs);
+            )?;
+        drop(span);
 
         let result = (vk, proof);
 
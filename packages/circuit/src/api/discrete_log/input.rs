@@ -1,3 +1,9 @@
+use std::{
+    io::{Read, Write},
+    path::Path,
+    str::FromStr,
+};
+
 use franklin_crypto::{
     babyjubjub::{edwards, JubjubBn256, JubjubEngine, Unknown},
     bellman::{
@@ -5,16 +11,13 @@ use franklin_crypto::{
         pairing::bn256::Bn256,
         plonk::{
             better_better_cs::{
-                cs::{
-                    Circuit, ProvingAssembly, SetupAssembly, TrivialAssembly,
-                    Width4MainGateWithDNext,
-                },
+                cs::{Circuit, ProvingAssembly, SetupAssembly, Width4MainGateWithDNext},
                 proof::Proof,
                 setup::VerificationKey,
             },
             commitments::transcript::keccak_transcript::RollingKeccakTranscript,
         },
-        ScalarEngine, SynthesisError,
+        ScalarEngine,
     },
     plonk::circuit::{
         bigint::field::RnsParameters,
@@ -23,10 +26,13 @@ use franklin_crypto::{
     },
 };
 
+use crate::api::format::{CircuitKind, Curve, FormatError, FrameReader, FrameWriter, Header};
+use crate::api::point::{write_jubjub_point_into, PointEncoding};
+use crate::api::utils::check_crs_size;
+use crate::circuit::debug::check_satisfied;
 use crate::circuit::discrete_log::DiscreteLogCircuit;
-// use serde::{Deserialize, Serialize};
-
-// use crate::circuit::utils::read_field_element_le_from;
+use crate::circuit::utils::write_field_element_le_into;
+use crate::error::CircuitError;
 
 pub struct DiscreteLogCircuitInput {
     pub base_point: edwards::Point<Bn256, Unknown>,
@@ -52,13 +58,11 @@ fn test_discrete_log_circuit() -> Result<(), Box<dyn std::error::Error>> {
         RnsParameters::<Bn256, <Bn256 as JubjubEngine>::Fs>::new_for_field(68, 110, 4);
     // `Bn256::Fs::NUM_BITS` is odd number, so an assertion error occur at franklin-crypto/plonk/circuit/bigint/field.rs:400:9.
     // Therefore, we modify `rns_params.binary_limbs_bit_widths[3]` to the next even number.
-    dbg!(rns_params.binary_limbs_bit_widths.last().unwrap());
     let current_bits = rns_params.binary_limbs_bit_widths.last_mut().unwrap();
     let remainder = *current_bits % rns_params.range_check_info.minimal_multiple;
     if remainder != 0 {
         *current_bits += rns_params.range_check_info.minimal_multiple - remainder;
     }
-    dbg!(rns_params.binary_limbs_bit_widths.last().unwrap());
 
     let jubjub_params = &JubjubBn256::new();
 
@@ -156,8 +160,9 @@ impl DiscreteLogCircuitInput {
             VerificationKey<Bn256, DiscreteLogCircuit<Bn256, BN256AuxData>>,
             Proof<Bn256, DiscreteLogCircuit<Bn256, BN256AuxData>>,
         ),
-        SynthesisError,
+        CircuitError,
     > {
+        let _span = tracing::info_span!("create_plonk_proof", circuit = "discrete-log").entered();
         let aux_data = BN256AuxData::new();
         let dummy_circuit = DiscreteLogCircuit::<Bn256, BN256AuxData> {
             base_point: None,
@@ -178,36 +183,29 @@ impl DiscreteLogCircuitInput {
             jubjub_params,
         };
 
+        let span = tracing::info_span!("setup").entered();
         let mut dummy_assembly =
             SetupAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        dummy_circuit
-            .synthesize(&mut dummy_assembly)
-            .expect("must synthesize");
+        dummy_circuit.synthesize(&mut dummy_assembly)?;
         dummy_assembly.finalize();
 
         let worker = franklin_crypto::bellman::worker::Worker::new();
         let setup =
             dummy_assembly.create_setup::<DiscreteLogCircuit<Bn256, BN256AuxData>>(&worker)?;
+        check_crs_size(&crs, setup.n)?;
 
         let vk = VerificationKey::<Bn256, DiscreteLogCircuit<Bn256, BN256AuxData>>::from_setup(
             &setup, &worker, &crs,
         )?;
 
-        println!("Checking if satisfied");
-        let mut trivial_assembly =
-            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        circuit
-            .synthesize(&mut trivial_assembly)
-            .expect("must synthesize");
-        if !trivial_assembly.is_satisfied() {
-            return Err(SynthesisError::Unsatisfiable);
-        }
+        drop(span);
 
-        println!("prove");
+        let span = tracing::info_span!("prove").entered();
+        check_satisfied(&circuit)?;
 
         let mut assembly =
             ProvingAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        circuit.synthesize(&mut assembly).expect("must synthesize");
+        circuit.synthesize(&mut assembly)?;
         assembly.finalize();
 
         // TODO: Is this correct?
@@ -215,8 +213,7 @@ impl DiscreteLogCircuitInput {
     .create_proof::<DiscreteLogCircuit<Bn256, BN256AuxData>, RollingKeccakTranscript<<Bn256 as ScalarEngine>::Fr>>(
       &worker, &setup, &crs, None,
     )?;
-
-        dbg!(&proof.inputs);
+        drop(span);
 
         let result = (vk, proof);
 
@@ -224,41 +221,84 @@ impl DiscreteLogCircuitInput {
     }
 }
 
-// impl FromStr for DisCreteLogCircuitInput {
-//     type Err = anyhow::Error;
-
-//     fn from_str(s: &str) -> anyhow::Result<Self> {
-//         Self::from_bytes(s.as_bytes())
-//     }
-// }
-
-// impl DisCreteLogCircuitInput {
-//     pub fn from_path(path: &Path) -> anyhow::Result<Self> {
-//         let json_str = read_to_string(path)?;
-
-//         Self::from_str(&json_str)
-//     }
-
-//     pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
-//         assert_eq!(bytes.to_vec().len(), 96);
-//         let reader = &mut std::io::Cursor::new(bytes.to_vec());
-//         let base_point_x = read_field_element_le_from(reader).unwrap();
-//         let base_point_y = read_field_element_le_from(reader).unwrap();
-//         let coefficient = read_field_element_le_from(reader).unwrap();
-//         let input = Self {
-//             base_point_x: Some(base_point_x),
-//             base_point_y: Some(base_point_y),
-//             coefficient: Some(coefficient),
-//         };
-
-//         Ok(input)
-//     }
-
-//     pub fn default() -> Self {
-//         Self {
-//             base_point_x: None,
-//             base_point_y: None,
-//             coefficient: None,
-//         }
-//     }
-// }
+impl FromStr for DiscreteLogCircuitInput {
+    type Err = FormatError;
+
+    fn from_str(s: &str) -> Result<Self, FormatError> {
+        Self::from_bytes(s.as_bytes())
+    }
+}
+
+impl DiscreteLogCircuitInput {
+    pub fn from_path(path: &Path) -> Result<Self, FormatError> {
+        let bytes = std::fs::read(path)?;
+
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+        Self::read_from(&mut std::io::Cursor::new(bytes))
+    }
+
+    /// Read an input framed as `header(DiscreteLog, JubjubBn256, 0) | [base_point] | [coefficient]`.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, FormatError> {
+        let jubjub_params = &JubjubBn256::new();
+        let mut frame = FrameReader::new(reader, CircuitKind::DiscreteLog, Curve::JubjubBn256, 2)?;
+        let domain_size = frame.header().domain_size;
+        if domain_size != 0 {
+            return Err(FormatError::InvalidDomainSize(domain_size));
+        }
+
+        let mut section = frame.read_section("base_point")?;
+        let base_point = section.read_jubjub_point::<Bn256>(jubjub_params)?;
+        section.finish()?;
+
+        let mut section = frame.read_section("coefficient")?;
+        let coefficient = section.read_field_element()?;
+        section.finish()?;
+        frame.finish()?;
+
+        Ok(Self {
+            base_point,
+            coefficient,
+        })
+    }
+
+    /// Write the input framed as `header(DiscreteLog, JubjubBn256, 0) | [base_point] | [coefficient]`.
+    /// The points are written uncompressed.
+    pub fn write_into<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_into_with_encoding(writer, PointEncoding::Uncompressed)
+    }
+
+    /// Write the input framed in the same way as `write_into`, where the points are written with `encoding`.
+    pub fn write_into_with_encoding<W: Write>(
+        &self,
+        writer: &mut W,
+        encoding: PointEncoding,
+    ) -> std::io::Result<()> {
+        let header = Header {
+            kind: CircuitKind::DiscreteLog,
+            curve: Curve::JubjubBn256,
+            point_encoding: encoding,
+            domain_size: 0,
+        };
+        let mut frame = FrameWriter::new(writer, header, 2)?;
+
+        let mut section = vec![];
+        write_jubjub_point_into(&self.base_point, encoding, &mut section)?;
+        frame.write_section(&section)?;
+
+        let mut section = vec![];
+        write_field_element_le_into(self.coefficient, &mut section)?;
+        frame.write_section(&section)?;
+
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_into(&mut bytes).unwrap();
+
+        bytes
+    }
+}
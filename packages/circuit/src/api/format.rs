@@ -0,0 +1,637 @@
+//! The framed binary format shared by the `*CircuitInput` types.
+//!
+//! ```text
+//! magic (4 bytes) | version (u16) | circuit kind (u8) | curve (u8) | point encoding (u8)
+//! | domain size (u64) | number of sections (u8)
+//! | (section length (u32) | section payload) * number of sections
+//! ```
+//!
+//! All integers are little-endian, and so are the field elements in the payloads.
+//! A point of BN256 G1 is written as its coordinates, and the point at infinity as `(0, 0)`.
+//! A point of Jubjub is written with the `PointEncoding` in the header.
+
+use std::fmt;
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use franklin_crypto::babyjubjub::JubjubBn256;
+use franklin_crypto::bellman::pairing::bls12_381::Bls12;
+use franklin_crypto::bellman::pairing::bn256::{Bn256, Fq, G1Affine};
+use franklin_crypto::bellman::{CurveAffine, Field, PrimeField, PrimeFieldRepr};
+use franklin_crypto::jubjub::JubjubBls12;
+
+use crate::api::point::{decode_jubjub_point, PointEncoding, PointError};
+use crate::circuit::num::jubjub::JubjubEngine;
+use crate::circuit::utils::write_field_element_le_into;
+
+/// The first bytes of every framed input.
+pub const MAGIC: [u8; 4] = *b"VKTC";
+
+/// The version of the format which is written by this crate.
+///
+/// Version 2 records the encoding of points in the header.
+pub const VERSION: u16 = 2;
+
+/// The circuit which a framed input is made for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CircuitKind {
+    Poseidon = 1,
+    IpaFr = 2,
+    IpaFs = 3,
+    BatchProofFr = 4,
+    BatchProofFs = 5,
+    DiscreteLog = 6,
+}
+
+impl TryFrom<u8> for CircuitKind {
+    type Error = FormatError;
+
+    fn try_from(value: u8) -> Result<Self, FormatError> {
+        let kind = match value {
+            1 => Self::Poseidon,
+            2 => Self::IpaFr,
+            3 => Self::IpaFs,
+            4 => Self::BatchProofFr,
+            5 => Self::BatchProofFs,
+            6 => Self::DiscreteLog,
+            _ => return Err(FormatError::UnknownCircuitKind(value)),
+        };
+
+        Ok(kind)
+    }
+}
+
+impl CircuitKind {
+    pub const ALL: [Self; 6] = [
+        Self::Poseidon,
+        Self::IpaFr,
+        Self::IpaFs,
+        Self::BatchProofFr,
+        Self::BatchProofFs,
+        Self::DiscreteLog,
+    ];
+
+    /// The name used on the command line, e.g. `ipa-fs`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Poseidon => "poseidon",
+            Self::IpaFr => "ipa-fr",
+            Self::IpaFs => "ipa-fs",
+            Self::BatchProofFr => "batch-proof-fr",
+            Self::BatchProofFs => "batch-proof-fs",
+            Self::DiscreteLog => "discrete-log",
+        }
+    }
+}
+
+impl FromStr for CircuitKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names = Self::ALL.map(|kind| kind.name());
+                format!(
+                    "unknown circuit {:?}, expected one of {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// The curve on which the points of a framed input lie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Curve {
+    /// G1 of BN256, whose scalar field is `Fr`.
+    Bn256 = 1,
+    /// Jubjub over the scalar field of BN256, whose scalar field is `Fs`.
+    JubjubBn256 = 2,
+    /// Jubjub over the scalar field of BLS12-381, whose scalar field is `Fs`.
+    JubjubBls12 = 3,
+}
+
+impl TryFrom<u8> for Curve {
+    type Error = FormatError;
+
+    fn try_from(value: u8) -> Result<Self, FormatError> {
+        let curve = match value {
+            1 => Self::Bn256,
+            2 => Self::JubjubBn256,
+            3 => Self::JubjubBls12,
+            _ => return Err(FormatError::UnknownCurve(value)),
+        };
+
+        Ok(curve)
+    }
+}
+
+/// The engines over which the inputs of the circuits on Jubjub are framed.
+///
+/// `Bn256` frames Baby Jubjub and `Bls12` frames Jubjub over BLS12-381.
+pub trait JubjubCurve: JubjubEngine {
+    /// The curve written in the header for the points of Jubjub over `Self`.
+    const CURVE: Curve;
+
+    fn jubjub_params() -> Self::Params;
+}
+
+impl JubjubCurve for Bn256 {
+    const CURVE: Curve = Curve::JubjubBn256;
+
+    fn jubjub_params() -> JubjubBn256 {
+        JubjubBn256::new()
+    }
+}
+
+impl JubjubCurve for Bls12 {
+    const CURVE: Curve = Curve::JubjubBls12;
+
+    fn jubjub_params() -> JubjubBls12 {
+        JubjubBls12::new()
+    }
+}
+
+impl TryFrom<u8> for PointEncoding {
+    type Error = FormatError;
+
+    fn try_from(value: u8) -> Result<Self, FormatError> {
+        let encoding = match value {
+            0 => Self::Uncompressed,
+            1 => Self::Compressed,
+            _ => return Err(FormatError::UnknownPointEncoding(value)),
+        };
+
+        Ok(encoding)
+    }
+}
+
+/// The error which is returned when a framed input is malformed.
+#[derive(Debug)]
+pub enum FormatError {
+    Io(std::io::Error),
+    /// The input ended before the end of a header or a section.
+    Truncated,
+    BadMagic([u8; 4]),
+    UnsupportedVersion(u16),
+    UnknownCircuitKind(u8),
+    UnknownCurve(u8),
+    UnknownPointEncoding(u8),
+    /// Points on `curve` cannot be written with `encoding`.
+    UnsupportedPointEncoding {
+        curve: Curve,
+        encoding: PointEncoding,
+    },
+    UnexpectedCircuitKind {
+        expected: CircuitKind,
+        found: CircuitKind,
+    },
+    UnexpectedCurve {
+        expected: Curve,
+        found: Curve,
+    },
+    UnexpectedSectionCount {
+        expected: usize,
+        found: usize,
+    },
+    /// The domain size in the header does not fit the contents.
+    InvalidDomainSize(u64),
+    /// The length of a section or of a list in it is not the expected one.
+    LengthMismatch {
+        section: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// A field element is not less than the modulus.
+    InvalidFieldElement {
+        section: &'static str,
+    },
+    InvalidPoint {
+        section: &'static str,
+        reason: PointError,
+    },
+    /// An integer is out of the range which the circuit accepts.
+    InvalidValue {
+        section: &'static str,
+        value: u64,
+    },
+    TrailingBytes {
+        section: Option<&'static str>,
+    },
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read the input: {}", err),
+            Self::Truncated => write!(f, "unexpected end of the input"),
+            Self::BadMagic(magic) => write!(f, "invalid magic: {:?}", magic),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported version: {}", version),
+            Self::UnknownCircuitKind(kind) => write!(f, "unknown circuit kind: {}", kind),
+            Self::UnknownCurve(curve) => write!(f, "unknown curve: {}", curve),
+            Self::UnknownPointEncoding(encoding) => {
+                write!(f, "unknown point encoding: {}", encoding)
+            }
+            Self::UnsupportedPointEncoding { curve, encoding } => write!(
+                f,
+                "points on {:?} cannot be written with {:?}",
+                curve, encoding
+            ),
+            Self::UnexpectedCircuitKind { expected, found } => write!(
+                f,
+                "unexpected circuit kind: expected {:?}, but {:?}",
+                expected, found
+            ),
+            Self::UnexpectedCurve { expected, found } => write!(
+                f,
+                "unexpected curve: expected {:?}, but {:?}",
+                expected, found
+            ),
+            Self::UnexpectedSectionCount { expected, found } => write!(
+                f,
+                "unexpected number of sections: expected {}, but {}",
+                expected, found
+            ),
+            Self::InvalidDomainSize(domain_size) => {
+                write!(f, "invalid domain size: {}", domain_size)
+            }
+            Self::LengthMismatch {
+                section,
+                expected,
+                found,
+            } => write!(
+                f,
+                "invalid length of {}: expected {}, but {}",
+                section, expected, found
+            ),
+            Self::InvalidFieldElement { section } => {
+                write!(f, "{} contains an invalid field element", section)
+            }
+            Self::InvalidPoint { section, reason } => {
+                write!(f, "{} contains an invalid point: {}", section, reason)
+            }
+            Self::InvalidValue { section, value } => {
+                write!(f, "{} contains an invalid value: {}", section, value)
+            }
+            Self::TrailingBytes {
+                section: Some(section),
+            } => {
+                write!(f, "{} has trailing bytes", section)
+            }
+            Self::TrailingBytes { section: None } => write!(f, "the input has trailing bytes"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FormatError {
+    fn from(err: std::io::Error) -> Self {
+        if err.kind() == std::io::ErrorKind::UnexpectedEof {
+            Self::Truncated
+        } else {
+            Self::Io(err)
+        }
+    }
+}
+
+/// The header of a framed input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub kind: CircuitKind,
+    pub curve: Curve,
+    /// Only `PointEncoding::Uncompressed` is supported for `Curve::Bn256`.
+    pub point_encoding: PointEncoding,
+    /// The width of the permutation for Poseidon, the domain size of the IPA for IPA and batch proofs,
+    /// and 0 if the circuit has no domain.
+    pub domain_size: u64,
+}
+
+/// The number of IPA rounds for `domain_size`, which must be a power of two.
+pub fn num_ipa_rounds(domain_size: u64) -> Result<usize, FormatError> {
+    if !domain_size.is_power_of_two() {
+        return Err(FormatError::InvalidDomainSize(domain_size));
+    }
+
+    Ok(domain_size.trailing_zeros() as usize)
+}
+
+fn check_point_encoding(curve: Curve, encoding: PointEncoding) -> Result<(), FormatError> {
+    if curve == Curve::Bn256 && encoding != PointEncoding::Uncompressed {
+        return Err(FormatError::UnsupportedPointEncoding { curve, encoding });
+    }
+
+    Ok(())
+}
+
+/// Returns the `std::io::Error` which is reported when an input cannot be framed.
+pub fn invalid_input(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+}
+
+/// Writes the header and then the sections one by one.
+pub struct FrameWriter<'a, W: Write> {
+    writer: &'a mut W,
+}
+
+impl<'a, W: Write> FrameWriter<'a, W> {
+    pub fn new(writer: &'a mut W, header: Header, num_sections: usize) -> std::io::Result<Self> {
+        if num_sections > u8::MAX as usize {
+            return Err(invalid_input(format!(
+                "too many sections: {}",
+                num_sections
+            )));
+        }
+
+        if let Err(err) = check_point_encoding(header.curve, header.point_encoding) {
+            return Err(invalid_input(err.to_string()));
+        }
+
+        writer.write_all(&MAGIC)?;
+        writer.write_u16::<LittleEndian>(VERSION)?;
+        writer.write_u8(header.kind as u8)?;
+        writer.write_u8(header.curve as u8)?;
+        writer.write_u8(header.point_encoding as u8)?;
+        writer.write_u64::<LittleEndian>(header.domain_size)?;
+        writer.write_u8(num_sections as u8)?;
+
+        Ok(Self { writer })
+    }
+
+    pub fn write_section(&mut self, payload: &[u8]) -> std::io::Result<()> {
+        if payload.len() > u32::MAX as usize {
+            return Err(invalid_input(format!(
+                "too large section: {} bytes",
+                payload.len()
+            )));
+        }
+
+        self.writer
+            .write_u32::<LittleEndian>(payload.len() as u32)?;
+        self.writer.write_all(payload)?;
+
+        Ok(())
+    }
+}
+
+/// Reads the header and then the sections one by one.
+pub struct FrameReader<'a, R: Read> {
+    reader: &'a mut R,
+    header: Header,
+    num_sections: usize,
+}
+
+impl<'a, R: Read> FrameReader<'a, R> {
+    /// Read the header, checking that it is the one of `kind` on `curve` with `num_sections` sections.
+    pub fn new(
+        reader: &'a mut R,
+        kind: CircuitKind,
+        curve: Curve,
+        num_sections: usize,
+    ) -> Result<Self, FormatError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(FormatError::BadMagic(magic));
+        }
+
+        let version = reader.read_u16::<LittleEndian>()?;
+        if version != VERSION {
+            return Err(FormatError::UnsupportedVersion(version));
+        }
+
+        let found_kind = CircuitKind::try_from(reader.read_u8()?)?;
+        if found_kind != kind {
+            return Err(FormatError::UnexpectedCircuitKind {
+                expected: kind,
+                found: found_kind,
+            });
+        }
+
+        let found_curve = Curve::try_from(reader.read_u8()?)?;
+        if found_curve != curve {
+            return Err(FormatError::UnexpectedCurve {
+                expected: curve,
+                found: found_curve,
+            });
+        }
+
+        let point_encoding = PointEncoding::try_from(reader.read_u8()?)?;
+        check_point_encoding(curve, point_encoding)?;
+
+        let domain_size = reader.read_u64::<LittleEndian>()?;
+
+        let found_num_sections = reader.read_u8()? as usize;
+        if found_num_sections != num_sections {
+            return Err(FormatError::UnexpectedSectionCount {
+                expected: num_sections,
+                found: found_num_sections,
+            });
+        }
+
+        let header = Header {
+            kind,
+            curve,
+            point_encoding,
+            domain_size,
+        };
+
+        Ok(Self {
+            reader,
+            header,
+            num_sections,
+        })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Read the next section, which is called `name` in errors.
+    pub fn read_section(&mut self, name: &'static str) -> Result<Section, FormatError> {
+        assert!(self.num_sections > 0, "no sections are left");
+        self.num_sections -= 1;
+
+        let len = self.reader.read_u32::<LittleEndian>()? as u64;
+        // The length is not trusted, so the payload is not allocated in advance.
+        let mut payload = vec![];
+        self.reader.by_ref().take(len).read_to_end(&mut payload)?;
+        if (payload.len() as u64) < len {
+            return Err(FormatError::Truncated);
+        }
+
+        Ok(Section {
+            name,
+            payload,
+            position: 0,
+            point_encoding: self.header.point_encoding,
+        })
+    }
+
+    /// Check that the input ends after the last section.
+    pub fn finish(self) -> Result<(), FormatError> {
+        assert_eq!(self.num_sections, 0, "some sections are left");
+
+        let mut rest = [0u8; 1];
+        if self.reader.read(&mut rest)? != 0 {
+            return Err(FormatError::TrailingBytes { section: None });
+        }
+
+        Ok(())
+    }
+}
+
+/// The payload of a section.
+pub struct Section {
+    name: &'static str,
+    payload: Vec<u8>,
+    position: usize,
+    point_encoding: PointEncoding,
+}
+
+impl Section {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn remaining(&self) -> usize {
+        self.payload.len() - self.position
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8], FormatError> {
+        if self.remaining() < len {
+            return Err(FormatError::LengthMismatch {
+                section: self.name,
+                expected: self.position + len,
+                found: self.payload.len(),
+            });
+        }
+
+        let bytes = &self.payload[self.position..(self.position + len)];
+        self.position += len;
+
+        Ok(bytes)
+    }
+
+    /// The number of items of `item_size` bytes in the rest of the section.
+    pub fn count(&self, item_size: usize) -> Result<usize, FormatError> {
+        let remaining = self.remaining();
+        if remaining % item_size != 0 {
+            return Err(FormatError::LengthMismatch {
+                section: self.name,
+                expected: remaining - remaining % item_size,
+                found: remaining,
+            });
+        }
+
+        Ok(remaining / item_size)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, FormatError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_field_element<F: PrimeField>(&mut self) -> Result<F, FormatError> {
+        let mut repr = F::Repr::default();
+        let len = repr.as_ref().len() * 8;
+        repr.read_le(self.take(len)?)?;
+
+        F::from_repr(repr).map_err(|_| FormatError::InvalidFieldElement { section: self.name })
+    }
+
+    /// Read field elements until the end of the section.
+    pub fn read_field_elements<F: PrimeField>(&mut self) -> Result<Vec<F>, FormatError> {
+        let n = self.count(field_element_size::<F>())?;
+
+        (0..n).map(|_| self.read_field_element()).collect()
+    }
+
+    pub fn read_g1(&mut self) -> Result<G1Affine, FormatError> {
+        let x: Fq = self.read_field_element()?;
+        let y: Fq = self.read_field_element()?;
+        if x.is_zero() && y.is_zero() {
+            return Ok(G1Affine::zero());
+        }
+
+        G1Affine::from_xy_checked(x, y).map_err(|_| FormatError::InvalidPoint {
+            section: self.name,
+            reason: PointError::NotOnCurve,
+        })
+    }
+
+    /// Read points of G1 until the end of the section.
+    pub fn read_g1s(&mut self) -> Result<Vec<G1Affine>, FormatError> {
+        let n = self.count(2 * field_element_size::<Fq>())?;
+
+        (0..n).map(|_| self.read_g1()).collect()
+    }
+
+    /// Read a point of Jubjub with the encoding in the header,
+    /// checking that it is in the prime-order subgroup.
+    pub fn read_jubjub_point<E: JubjubEngine>(
+        &mut self,
+        jubjub_params: &E::Params,
+    ) -> Result<E::Point, FormatError> {
+        let encoding = self.point_encoding;
+        let name = self.name;
+        let bytes = self.take(encoding.point_size())?;
+
+        decode_jubjub_point::<E>(bytes, encoding, jubjub_params).map_err(|reason| {
+            FormatError::InvalidPoint {
+                section: name,
+                reason,
+            }
+        })
+    }
+
+    /// Read points of Jubjub until the end of the section.
+    pub fn read_jubjub_points<E: JubjubEngine>(
+        &mut self,
+        jubjub_params: &E::Params,
+    ) -> Result<Vec<E::Point>, FormatError> {
+        let n = self.count(self.point_encoding.point_size())?;
+
+        (0..n)
+            .map(|_| self.read_jubjub_point::<E>(jubjub_params))
+            .collect()
+    }
+
+    /// Check that the whole section has been read.
+    pub fn finish(self) -> Result<(), FormatError> {
+        if self.remaining() != 0 {
+            return Err(FormatError::TrailingBytes {
+                section: Some(self.name),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+pub fn field_element_size<F: PrimeField>() -> usize {
+    F::Repr::default().as_ref().len() * 8
+}
+
+pub fn write_g1_into<W: Write>(point: &G1Affine, writer: &mut W) -> std::io::Result<()> {
+    let (x, y) = if point.is_zero() {
+        (Fq::zero(), Fq::zero())
+    } else {
+        point.into_xy_unchecked()
+    };
+    write_field_element_le_into(x, writer)?;
+    write_field_element_le_into(y, writer)?;
+
+    Ok(())
+}
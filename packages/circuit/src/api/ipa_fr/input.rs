@@ -1,11 +1,9 @@
 use std::{
-    fs::read_to_string,
     io::{Read, Write},
     path::Path,
     str::FromStr,
 };
 
-use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use franklin_crypto::bellman::plonk::better_better_cs::proof::Proof;
 use franklin_crypto::bellman::plonk::better_better_cs::setup::VerificationKey;
 use franklin_crypto::bellman::plonk::commitments::transcript::keccak_transcript::RollingKeccakTranscript;
@@ -15,7 +13,7 @@ use franklin_crypto::bellman::{
     },
     CurveAffine,
 };
-use franklin_crypto::bellman::{ScalarEngine, SynthesisError};
+use franklin_crypto::bellman::{Field, ScalarEngine};
 use franklin_crypto::plonk::circuit::Width4WithCustomGates;
 use franklin_crypto::{
     bellman::kate_commitment::{Crs, CrsForMonomialForm},
@@ -30,14 +28,24 @@ use franklin_crypto::{
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use verkle_tree::ipa_fr::{
     config::{Committer, IpaConfig},
-    proof::{IpaProof, SerializableIpaProof},
+    proof::IpaProof,
     rns::BaseRnsParameters,
     utils::log2_ceil,
 };
 
-use crate::circuit::ipa_fr::circuit::IpaCircuit;
+use crate::api::format::{
+    invalid_input, num_ipa_rounds, write_g1_into, CircuitKind, Curve, FormatError, FrameReader,
+    FrameWriter, Header,
+};
+use crate::api::point::PointEncoding;
+use crate::api::utils::{check_crs_size, g1_from_hex_pair, g1_to_hex_pair};
+use crate::circuit::debug::check_satisfied;
+use crate::circuit::ipa_fr::circuit::UnlabeledIpaCircuit;
 use crate::circuit::ipa_fr::proof::OptionIpaProof;
-use crate::circuit::utils::{read_field_element_le_from, write_field_element_le_into};
+use crate::circuit::utils::{
+    field_element_from_hex, field_element_to_hex, write_field_element_le_into,
+};
+use crate::error::CircuitError;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct IpaCircuitInput {
@@ -55,13 +63,23 @@ mod ipa_api_tests {
 
     use franklin_crypto::bellman::kate_commitment::{Crs, CrsForMonomialForm};
     use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr, G1Affine};
+    use franklin_crypto::bellman::plonk::better_better_cs::cs::{
+        TrivialAssembly, Width4MainGateWithDNext,
+    };
+    use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
     use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::without_flag_unchecked::WrapperUnchecked;
+    use franklin_crypto::plonk::circuit::Width4WithCustomGates;
     use verkle_tree::ipa_fr::config::{IpaConfig, Committer};
     use verkle_tree::ipa_fr::proof::IpaProof;
     use verkle_tree::ipa_fr::rns::BaseRnsParameters;
     use verkle_tree::ipa_fr::transcript::{PoseidonBn256Transcript, Bn256Transcript};
     use verkle_tree::ipa_fr::utils::{read_field_element_le, test_poly};
 
+    use crate::circuit::ipa_fr::transcript::WrappedTranscript;
+    use crate::circuit::transcript::poseidon::PoseidonNativeTranscript;
+    use crate::circuit::transcript::sha256::{Sha256NativeTranscript, Sha256Transcript};
+    use crate::circuit::transcript::{label_to_field_element, Transcript, UnlabeledTranscript};
+
     use super::{IpaCircuitInput, VkAndProof};
 
     const CIRCUIT_NAME: &str = "ipa_fr";
@@ -281,11 +299,142 @@ mod ipa_api_tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_ipa_fr_circuit_input_default_serde_json() -> Result<(), Box<dyn std::error::Error>> {
+        let circuit_input = IpaCircuitInput::default();
+        let j = serde_json::to_value(&circuit_input)?;
+        let zero = format!("0x{}", "0".repeat(64));
+        assert_eq!(j["commitment"][0], zero.as_str());
+        assert_eq!(j["eval_point"], zero.as_str());
+        assert_eq!(j["proof"]["l"].as_array().map(|l| l.len()), Some(0));
+
+        let circuit_input2: IpaCircuitInput = serde_json::from_value(j)?;
+        assert_eq!(circuit_input, circuit_input2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipa_fr_circuit_input_invalid_json() -> Result<(), Box<dyn std::error::Error>> {
+        let mut j = serde_json::to_value(&IpaCircuitInput::default())?;
+        let one = format!("0x{}1", "0".repeat(63));
+        // (0, 1) is not on the curve.
+        j["commitment"][1] = one.clone().into();
+        assert!(serde_json::from_value::<IpaCircuitInput>(j.clone()).is_err());
+
+        j["commitment"][1] = j["commitment"][0].clone();
+        j["inner_prod"] = one[2..].into();
+        assert!(serde_json::from_value::<IpaCircuitInput>(j).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sha256_transcript_matches_native() -> Result<(), Box<dyn std::error::Error>> {
+        let mut native_transcript = Sha256NativeTranscript::<Fr>::with_bytes(b"ipa")?;
+        let elements = [
+            read_field_element_le::<Fr>(&[1])?,
+            read_field_element_le::<Fr>(&[2, 3])?,
+            read_field_element_le::<Fr>(&[255; 31])?,
+        ];
+
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        let init_state = AllocatedNum::alloc(&mut cs, || Ok(native_transcript.get_challenge()))?;
+        let mut transcript = Sha256Transcript::new(&mut cs, init_state)?;
+        for element in elements.iter() {
+            native_transcript.commit_field_element(element)?;
+            let allocated_element = AllocatedNum::alloc(&mut cs, || Ok(*element))?;
+            transcript.commit_alloc_num(&mut cs, &allocated_element)?;
+            assert_eq!(
+                transcript.get_challenge(&mut cs)?.get_value(),
+                Some(native_transcript.get_challenge())
+            );
+        }
+        assert!(cs.is_satisfied());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_labeled_sha256_transcript_matches_native() -> Result<(), Box<dyn std::error::Error>> {
+        let mut native_transcript = Sha256NativeTranscript::<Fr>::with_bytes(b"ipa")?;
+        let labels: [&[u8]; 3] = [b"C", b"input point", b"output point"];
+        let elements = [
+            read_field_element_le::<Fr>(&[1])?,
+            read_field_element_le::<Fr>(&[2, 3])?,
+            read_field_element_le::<Fr>(&[255; 31])?,
+        ];
+
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        let init_state = AllocatedNum::alloc(&mut cs, || Ok(native_transcript.get_challenge()))?;
+        let mut transcript = Sha256Transcript::new(&mut cs, init_state)?;
+        let mut unlabeled_transcript =
+            UnlabeledTranscript::<Sha256Transcript<Bn256>>::new(&mut cs, init_state)?;
+        for (label, element) in labels.iter().zip(elements.iter()) {
+            native_transcript.commit_field_element_with_label(label, element)?;
+            let allocated_element = AllocatedNum::alloc(&mut cs, || Ok(*element))?;
+            transcript.commit_alloc_num_with_label(&mut cs, label, &allocated_element)?;
+            unlabeled_transcript.commit_alloc_num_with_label(&mut cs, label, &allocated_element)?;
+            assert_eq!(
+                transcript.get_challenge(&mut cs)?.get_value(),
+                Some(native_transcript.get_challenge())
+            );
+        }
+        assert_ne!(
+            transcript.get_challenge(&mut cs)?.get_value(),
+            unlabeled_transcript.get_challenge(&mut cs)?.get_value()
+        );
+        assert!(cs.is_satisfied());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_labeled_poseidon_transcript_matches_native() -> Result<(), Box<dyn std::error::Error>> {
+        let init_state = read_field_element_le::<Fr>(b"ipa")?;
+        let mut native_transcript = PoseidonNativeTranscript::<Bn256>::new(init_state);
+        let labels: [&[u8]; 3] = [b"C", b"input point", b"output point"];
+        let elements = [
+            read_field_element_le::<Fr>(&[1])?,
+            read_field_element_le::<Fr>(&[2, 3])?,
+            read_field_element_le::<Fr>(&[255; 31])?,
+        ];
+
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        let init_state = AllocatedNum::alloc(&mut cs, || Ok(init_state))?;
+        let mut transcript = WrappedTranscript::<Bn256>::new(&mut cs, init_state)?;
+        for (label, element) in labels.iter().zip(elements.iter()) {
+            native_transcript.commit_field_element_with_label(label, element)?;
+            let allocated_element = AllocatedNum::alloc(&mut cs, || Ok(*element))?;
+            transcript.commit_alloc_num_with_label(&mut cs, label, &allocated_element)?;
+            assert_eq!(
+                transcript.get_challenge(&mut cs)?.get_value(),
+                Some(native_transcript.get_challenge())
+            );
+        }
+        assert!(cs.is_satisfied());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_label_to_field_element() {
+        assert_ne!(
+            label_to_field_element::<Fr>(b"C").unwrap(),
+            label_to_field_element::<Fr>(b"C\0").unwrap()
+        );
+        assert!(label_to_field_element::<Fr>(&[b'a'; 30]).is_ok());
+        assert!(label_to_field_element::<Fr>(&[b'a'; 31]).is_err());
+    }
 }
 
 pub struct VkAndProof<'a, WP: WrappedAffinePoint<'a, Bn256>, AD: AuxData<Bn256>>(
-    pub VerificationKey<Bn256, IpaCircuit<'a, Bn256, WP, AD>>,
-    pub Proof<Bn256, IpaCircuit<'a, Bn256, WP, AD>>,
+    pub VerificationKey<Bn256, UnlabeledIpaCircuit<'a, Bn256, WP, AD>>,
+    pub Proof<Bn256, UnlabeledIpaCircuit<'a, Bn256, WP, AD>>,
 );
 
 impl IpaCircuitInput {
@@ -309,10 +458,19 @@ impl IpaCircuitInput {
         ipa_conf: IpaConfig<G1Affine>,
         rns_params: &'a BaseRnsParameters<Bn256>,
         crs: Crs<Bn256, CrsForMonomialForm>,
-    ) -> Result<VkAndProof<'a, WP, BN256AuxData>, SynthesisError> {
+    ) -> Result<VkAndProof<'a, WP, BN256AuxData>, CircuitError> {
+        let _span = tracing::info_span!("create_plonk_proof", circuit = "ipa-fr").entered();
         let aux_data = BN256AuxData::new();
         let num_rounds = log2_ceil(ipa_conf.get_domain_size()) as usize;
-        let dummy_circuit = IpaCircuit::<'a, Bn256, WP, BN256AuxData> {
+        if self.proof.l.len() != num_rounds || self.proof.r.len() != num_rounds {
+            return Err(CircuitError::InvalidInputShape(format!(
+                "the numbers of points for L and R should be {}, but {} and {}",
+                num_rounds,
+                self.proof.l.len(),
+                self.proof.r.len()
+            )));
+        }
+        let dummy_circuit = UnlabeledIpaCircuit::<'a, Bn256, WP, BN256AuxData> {
             transcript_params: None,
             commitment: None,
             proof: OptionIpaProof::with_depth(num_rounds),
@@ -322,13 +480,13 @@ impl IpaCircuitInput {
             rns_params,
             aux_data: aux_data.clone(),
             _wp: std::marker::PhantomData,
+            _transcript: std::marker::PhantomData,
         };
 
+        let span = tracing::info_span!("setup").entered();
         let mut dummy_assembly =
             SetupAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        dummy_circuit
-            .synthesize(&mut dummy_assembly)
-            .expect("must synthesize");
+        dummy_circuit.synthesize(&mut dummy_assembly)?;
         dummy_assembly.finalize();
 
         // println!("Checking if satisfied");
@@ -336,14 +494,16 @@ impl IpaCircuitInput {
         // assert!(is_satisfied, "unsatisfied constraints");
 
         let worker = franklin_crypto::bellman::worker::Worker::new();
-        let setup =
-            dummy_assembly.create_setup::<IpaCircuit<'a, Bn256, WP, BN256AuxData>>(&worker)?;
+        let setup = dummy_assembly
+            .create_setup::<UnlabeledIpaCircuit<'a, Bn256, WP, BN256AuxData>>(&worker)?;
+        check_crs_size(&crs, setup.n)?;
 
-        let vk = VerificationKey::<Bn256, IpaCircuit<'a, Bn256, WP, BN256AuxData>>::from_setup(
-            &setup, &worker, &crs,
-        )?;
+        let vk =
+            VerificationKey::<Bn256, UnlabeledIpaCircuit<'a, Bn256, WP, BN256AuxData>>::from_setup(
+                &setup, &worker, &crs,
+            )?;
 
-        let circuit = IpaCircuit::<'a, Bn256, WP, BN256AuxData> {
+        let circuit = UnlabeledIpaCircuit::<'a, Bn256, WP, BN256AuxData> {
             transcript_params: Some(transcript_params),
             commitment: Some(self.commitment),
             proof: OptionIpaProof::from(self.proof.clone()),
@@ -353,19 +513,24 @@ impl IpaCircuitInput {
             rns_params,
             aux_data,
             _wp: std::marker::PhantomData,
+            _transcript: std::marker::PhantomData,
         };
 
+        drop(span);
+
+        let span = tracing::info_span!("prove").entered();
+        check_satisfied(&circuit)?;
+
         let mut assembly =
             ProvingAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        circuit.synthesize(&mut assembly).expect("must synthesize");
+        circuit.synthesize(&mut assembly)?;
         assembly.finalize();
 
-        println!("prove");
-
         let proof = assembly
-            .create_proof::<IpaCircuit<'a, Bn256, WP, BN256AuxData>, RollingKeccakTranscript<<Bn256 as ScalarEngine>::Fr>>(
+            .create_proof::<UnlabeledIpaCircuit<'a, Bn256, WP, BN256AuxData>, RollingKeccakTranscript<<Bn256 as ScalarEngine>::Fr>>(
                 &worker, &setup, &crs, None,
             )?;
+        drop(span);
 
         // assert_eq!(
         //     proof.inputs,
@@ -378,116 +543,221 @@ impl IpaCircuitInput {
         Ok(result)
     }
 
-    /// `[width, input[0], ..., inputs[t - 2], output]` -> `CircuitInput`
-    pub fn read_from<R: Read>(reader: &mut R) -> anyhow::Result<Self> {
-        let commitment_x = read_field_element_le_from(reader)?;
-        let commitment_y = read_field_element_le_from(reader)?;
-        let commitment = G1Affine::from_xy_checked(commitment_x, commitment_y)?;
-        let num_ipa_rounds = reader.read_u16::<LittleEndian>()?;
-
-        let mut proof_ls = vec![];
-        for _ in 0..num_ipa_rounds {
-            let l_x = read_field_element_le_from(reader)?;
-            let l_y = read_field_element_le_from(reader)?;
-            let l = G1Affine::from_xy_checked(l_x, l_y)?;
-            proof_ls.push(l);
+    /// Read an input framed as
+    /// `header(IpaFr, Bn256, domain_size) | [commitment] | [L] | [R] | [a] | [eval_point] | [inner_prod]`.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, FormatError> {
+        let mut frame = FrameReader::new(reader, CircuitKind::IpaFr, Curve::Bn256, 6)?;
+        let num_ipa_rounds = num_ipa_rounds(frame.header().domain_size)?;
+
+        let mut section = frame.read_section("commitment")?;
+        let commitment = section.read_g1()?;
+        section.finish()?;
+
+        let mut proof_points = vec![];
+        for name in ["L", "R"] {
+            let mut section = frame.read_section(name)?;
+            let points = section.read_g1s()?;
+            section.finish()?;
+            if points.len() != num_ipa_rounds {
+                return Err(FormatError::LengthMismatch {
+                    section: name,
+                    expected: num_ipa_rounds,
+                    found: points.len(),
+                });
+            }
+            proof_points.push(points);
         }
-        let mut proof_rs = vec![];
-        for _ in 0..num_ipa_rounds {
-            let r_x = read_field_element_le_from(reader)?;
-            let r_y = read_field_element_le_from(reader)?;
-            let r = G1Affine::from_xy_checked(r_x, r_y)?;
-            proof_rs.push(r);
+        let proof_r = proof_points.pop().unwrap();
+        let proof_l = proof_points.pop().unwrap();
+
+        let mut scalars: Vec<Fr> = vec![];
+        for name in ["a", "eval_point", "inner_prod"] {
+            let mut section = frame.read_section(name)?;
+            scalars.push(section.read_field_element()?);
+            section.finish()?;
         }
-        let proof_a = read_field_element_le_from(reader)?;
-        let proof = IpaProof {
-            l: proof_ls,
-            r: proof_rs,
-            a: proof_a,
-        };
-        let eval_point = read_field_element_le_from(reader)?;
-        let inner_prod = read_field_element_le_from(reader)?;
+        frame.finish()?;
 
         let result = Self {
             commitment,
-            proof,
-            eval_point,
-            inner_prod,
+            proof: IpaProof {
+                l: proof_l,
+                r: proof_r,
+                a: scalars[0],
+            },
+            eval_point: scalars[1],
+            inner_prod: scalars[2],
         };
 
         Ok(result)
     }
 
-    /// `CircuitInput` -> `[t, input[0], ..., inputs[t - 2], output]`
+    /// Write the input framed as
+    /// `header(IpaFr, Bn256, domain_size) | [commitment] | [L] | [R] | [a] | [eval_point] | [inner_prod]`.
     pub fn write_into<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        let (commitment_x, commitment_y) = self.commitment.into_xy_unchecked();
-        write_field_element_le_into(commitment_x, writer)?;
-        write_field_element_le_into(commitment_y, writer)?;
         let num_ipa_rounds = self.proof.l.len();
-        assert!(num_ipa_rounds <= u16::MAX as usize);
-        writer.write_u16::<LittleEndian>(num_ipa_rounds as u16)?;
-        for l in self.proof.l.iter() {
-            let (l_x, l_y) = l.into_xy_unchecked();
-            write_field_element_le_into(l_x, writer)?;
-            write_field_element_le_into(l_y, writer)?;
+        if self.proof.r.len() != num_ipa_rounds || num_ipa_rounds >= 64 {
+            return Err(invalid_input(format!(
+                "invalid numbers of IPA rounds: {} and {}",
+                num_ipa_rounds,
+                self.proof.r.len()
+            )));
         }
-        for r in self.proof.r.iter() {
-            let (r_x, r_y) = r.into_xy_unchecked();
-            write_field_element_le_into(r_x, writer)?;
-            write_field_element_le_into(r_y, writer)?;
+
+        let header = Header {
+            kind: CircuitKind::IpaFr,
+            curve: Curve::Bn256,
+            point_encoding: PointEncoding::Uncompressed,
+            domain_size: 1 << num_ipa_rounds,
+        };
+        let mut frame = FrameWriter::new(writer, header, 6)?;
+
+        let mut commitment = vec![];
+        write_g1_into(&self.commitment, &mut commitment)?;
+        frame.write_section(&commitment)?;
+
+        for points in [&self.proof.l, &self.proof.r] {
+            let mut section = vec![];
+            for point in points.iter() {
+                write_g1_into(point, &mut section)?;
+            }
+            frame.write_section(&section)?;
+        }
+
+        for scalar in [self.proof.a, self.eval_point, self.inner_prod] {
+            let mut section = vec![];
+            write_field_element_le_into(scalar, &mut section)?;
+            frame.write_section(&section)?;
         }
-        write_field_element_le_into(self.proof.a, writer)?;
-        write_field_element_le_into(self.eval_point, writer)?;
-        write_field_element_le_into(self.inner_prod, writer)?;
 
         Ok(())
     }
 
-    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
-        let json_str = read_to_string(path)?;
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_into(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+        Self::read_from(&mut std::io::Cursor::new(bytes))
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, FormatError> {
+        let bytes = std::fs::read(path)?;
 
-        Self::from_str(&json_str)
+        Self::from_bytes(&bytes)
     }
 }
 
 impl FromStr for IpaCircuitInput {
-    type Err = anyhow::Error;
+    type Err = FormatError;
 
-    fn from_str(s: &str) -> anyhow::Result<Self> {
-        Self::read_from(&mut s.as_bytes())
+    fn from_str(s: &str) -> Result<Self, FormatError> {
+        Self::from_bytes(s.as_bytes())
     }
 }
 
 impl Default for IpaCircuitInput {
+    /// The input with no IPA rounds, whose points are the point at infinity and whose scalars are zero.
     fn default() -> Self {
-        todo!()
+        Self {
+            commitment: G1Affine::zero(),
+            proof: IpaProof {
+                l: vec![],
+                r: vec![],
+                a: Fr::zero(),
+            },
+            eval_point: Fr::zero(),
+            inner_prod: Fr::zero(),
+        }
+    }
+}
+
+/// `SerializableIpaProof` is needed in the process of serializing `IpaProof` in the circuit inputs.
+/// Each point is serialized as a pair of hex strings of its coordinates.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SerializableIpaProof {
+    l: Vec<(String, String)>,
+    r: Vec<(String, String)>,
+    a: String,
+}
+
+impl SerializableIpaProof {
+    pub(crate) fn from_proof(proof: &IpaProof<G1Affine>) -> Self {
+        Self {
+            l: proof.l.iter().map(g1_to_hex_pair).collect(),
+            r: proof.r.iter().map(g1_to_hex_pair).collect(),
+            a: field_element_to_hex(proof.a),
+        }
+    }
+
+    pub(crate) fn to_proof(&self) -> anyhow::Result<IpaProof<G1Affine>> {
+        anyhow::ensure!(
+            self.l.len() == self.r.len(),
+            "the numbers of L and R points are different"
+        );
+        let l = self
+            .l
+            .iter()
+            .map(g1_from_hex_pair)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let r = self
+            .r
+            .iter()
+            .map(g1_from_hex_pair)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(IpaProof {
+            l,
+            r,
+            a: field_element_from_hex(&self.a)?,
+        })
     }
 }
 
-/// `SerializablePoseidonCircuitInput` is needed in the process of serializing `PoseidonCircuitInput`.
-/// `PoseidonCircuitInput` is serialized by treating `Fr` as a hex string.
+/// `SerializableIpaCircuitInput` is needed in the process of serializing `IpaCircuitInput`.
+/// `IpaCircuitInput` is serialized by treating `Fr` and the coordinates of `G1Affine` as hex strings.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 struct SerializableIpaCircuitInput {
-    pub(crate) commitment: (String, String),
-    pub(crate) proof: SerializableIpaProof,
-    pub(crate) eval_point: String,
-    pub(crate) inner_prod: String,
+    commitment: (String, String),
+    proof: SerializableIpaProof,
+    eval_point: String,
+    inner_prod: String,
 }
 
 impl Serialize for IpaCircuitInput {
-    fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        todo!()
+        let new_self = SerializableIpaCircuitInput {
+            commitment: g1_to_hex_pair(&self.commitment),
+            proof: SerializableIpaProof::from_proof(&self.proof),
+            eval_point: field_element_to_hex(self.eval_point),
+            inner_prod: field_element_to_hex(self.inner_prod),
+        };
+
+        new_self.serialize(serializer)
     }
 }
 
 impl<'de> Deserialize<'de> for IpaCircuitInput {
-    fn deserialize<D>(_deserializer: D) -> Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        todo!()
+        let raw = SerializableIpaCircuitInput::deserialize(deserializer)?;
+        let parse = || -> anyhow::Result<Self> {
+            Ok(Self {
+                commitment: g1_from_hex_pair(&raw.commitment)?,
+                proof: raw.proof.to_proof()?,
+                eval_point: field_element_from_hex(&raw.eval_point)?,
+                inner_prod: field_element_from_hex(&raw.inner_prod)?,
+            })
+        };
+
+        parse().map_err(serde::de::Error::custom)
     }
 }
@@ -1,11 +1,13 @@
-use std::{fs::read_to_string, path::Path, str::FromStr};
+use std::{
+    io::{Read, Write},
+    path::Path,
+    str::FromStr,
+};
 
-use byteorder::{LittleEndian, ReadBytesExt};
 use franklin_crypto::{
-    babyjubjub::{edwards, JubjubBn256, JubjubEngine, Unknown},
     bellman::{
         kate_commitment::{Crs, CrsForMonomialForm},
-        pairing::bn256::{Bn256, Fr},
+        pairing::bn256::Bn256,
         plonk::{
             better_better_cs::{
                 cs::{Circuit, ProvingAssembly, SetupAssembly, Width4MainGateWithDNext},
@@ -14,27 +16,36 @@ use franklin_crypto::{
             },
             commitments::transcript::keccak_transcript::RollingKeccakTranscript,
         },
-        PrimeField, PrimeFieldRepr, ScalarEngine, SynthesisError,
     },
     plonk::circuit::{
         bigint::field::RnsParameters, verifier_circuit::affine_point_wrapper::WrappedAffinePoint,
         Width4WithCustomGates,
     },
 };
-use verkle_tree::ipa_fs::{config::IpaConfig, proof::IpaProof};
 // use serde::{Deserialize, Serialize};
 
+use crate::api::format::{
+    invalid_input, num_ipa_rounds, CircuitKind, FormatError, FrameReader, FrameWriter, Header,
+    JubjubCurve,
+};
+use crate::api::point::{write_jubjub_point_into, PointEncoding};
+use crate::api::utils::check_crs_size;
 use crate::circuit::{
-    ipa_fs::{circuit::IpaCircuit, proof::OptionIpaProof},
-    utils::read_field_element_le_from,
+    debug::check_satisfied,
+    ipa_fs::{
+        accumulation::UnlabeledIpaAccumulationCircuit, circuit::UnlabeledIpaCircuit,
+        config::IpaSetup, native::NativeIpaProof, proof::OptionIpaProof,
+    },
+    utils::write_field_element_le_into,
 };
+use crate::error::CircuitError;
 
 #[derive(Clone)]
-pub struct IpaCircuitInput {
-    pub commitment: edwards::Point<Bn256, Unknown>,
-    pub proof: IpaProof<Bn256>,
-    pub eval_point: <Bn256 as JubjubEngine>::Fs,
-    pub inner_prod: <Bn256 as JubjubEngine>::Fs,
+pub struct IpaCircuitInput<E: JubjubCurve = Bn256> {
+    pub commitment: E::Point,
+    pub proof: NativeIpaProof<E>,
+    pub eval_point: E::Fs,
+    pub inner_prod: E::Fs,
 }
 
 #[cfg(test)]
@@ -42,17 +53,28 @@ mod ipa_api_tests {
     use std::{fs::OpenOptions, path::Path};
 
     use franklin_crypto::{
-        babyjubjub::{JubjubBn256, JubjubEngine},
+        babyjubjub::JubjubBn256,
         bellman::{
-            pairing::bn256::{Bn256, Fr},
+            pairing::{
+                bls12_381::Bls12,
+                bn256::{Bn256, Fr},
+                Engine,
+            },
             plonk::{
-                better_better_cs::verifier::verify,
+                better_better_cs::{
+                    cs::{TrivialAssembly, Width4MainGateWithDNext},
+                    verifier::verify,
+                },
                 commitments::transcript::keccak_transcript::RollingKeccakTranscript,
             },
+            Field, PrimeField, SynthesisError,
         },
+        jubjub::JubjubBls12,
         plonk::circuit::{
-            bigint::field::RnsParameters,
+            allocated_num::AllocatedNum,
+            bigint::field::{FieldElement, RnsParameters},
             verifier_circuit::affine_point_wrapper::without_flag_unchecked::WrapperUnchecked,
+            Width4WithCustomGates,
         },
     };
     use verkle_tree::{
@@ -65,7 +87,23 @@ mod ipa_api_tests {
         },
     };
 
+    use crate::api::format::{Curve, FormatError};
+    use crate::api::point::{
+        decode_compressed, decode_jubjub_point, encode_compressed, jubjub_point_from_xy,
+        PointEncoding, PointError, COMPRESSED_POINT_SIZE,
+    };
     use crate::api::utils::open_crs_for_log2_of_size;
+    use crate::circuit::debug::check_satisfied;
+    use crate::circuit::ipa_fs::accumulation::{verify_deferred_ipa_checks, DeferredIpaClaim};
+    use crate::circuit::ipa_fs::circuit::check_ipa_proof;
+    use crate::circuit::ipa_fs::dummy_transcript::WrappedDummyTranscript;
+    use crate::circuit::ipa_fs::native::{NativeIpaConfig, NativeIpaProof};
+    use crate::circuit::num::jubjub::JubjubEngine;
+    use crate::circuit::num::{
+        allocate_edwards_point, truncate_field_element, truncate_into_field_element,
+    };
+    use crate::circuit::transcript::UnlabeledTranscript;
+    use crate::error::CircuitError;
 
     use super::IpaCircuitInput;
 
@@ -88,12 +126,367 @@ mod ipa_api_tests {
 
         Ok(IpaCircuitInput {
             commitment,
-            proof,
+            proof: proof.into(),
             eval_point,
             inner_prod: ip,
         })
     }
 
+    fn make_bls12_test_input(
+        poly: &[u64],
+        eval_point: u64,
+        ipa_conf: &NativeIpaConfig<Bls12>,
+    ) -> Result<IpaCircuitInput<Bls12>, CircuitError> {
+        let to_fs = |v: u64| <Bls12 as JubjubEngine>::Fs::from_str(&v.to_string()).unwrap();
+        let poly = poly.iter().map(|&v| to_fs(v)).collect::<Vec<_>>();
+        let eval_point = to_fs(eval_point);
+        let commitment = ipa_conf.commit(&poly)?;
+        let (proof, inner_prod) = NativeIpaProof::create(
+            &commitment,
+            &poly,
+            eval_point,
+            bls12_transcript_params(),
+            ipa_conf,
+        )?;
+
+        Ok(IpaCircuitInput {
+            commitment,
+            proof,
+            eval_point,
+            inner_prod,
+        })
+    }
+
+    fn bls12_transcript_params() -> <Bls12 as Engine>::Fr {
+        <Bls12 as Engine>::Fr::from_str("12345").unwrap()
+    }
+
+    fn bls12_rns_params() -> RnsParameters<Bls12, <Bls12 as JubjubEngine>::Fs> {
+        let mut rns_params =
+            RnsParameters::<Bls12, <Bls12 as JubjubEngine>::Fs>::new_for_field(68, 110, 4);
+        let current_bits = rns_params.binary_limbs_bit_widths.last_mut().unwrap();
+        let remainder = *current_bits % rns_params.range_check_info.minimal_multiple;
+        if remainder != 0 {
+            *current_bits += rns_params.range_check_info.minimal_multiple - remainder;
+        }
+
+        rns_params
+    }
+
+    #[test]
+    fn test_ipa_fs_circuit_input_point_encodings() -> Result<(), Box<dyn std::error::Error>> {
+        let jubjub_params = &JubjubBn256::new();
+        let eval_point: <Bn256 as JubjubEngine>::Fs =
+            read_field_element_le(&123456789u64.to_le_bytes()).unwrap();
+        let domain_size = 4;
+        let ipa_conf = &IpaConfig::<Bn256>::new(domain_size, jubjub_params);
+        let poly = vec![12, 97, 37, 0];
+        let padded_poly = test_poly::<<Bn256 as JubjubEngine>::Fs>(&poly, domain_size);
+        let prover_transcript = PoseidonBn256Transcript::with_bytes(b"ipa");
+        let circuit_input = make_test_input(
+            &padded_poly,
+            eval_point,
+            prover_transcript.into_params(),
+            ipa_conf,
+        )?;
+        let bytes = circuit_input.to_bytes();
+
+        let mut compressed_bytes = vec![];
+        circuit_input.write_into_with_encoding(&mut compressed_bytes, PointEncoding::Compressed)?;
+        // The commitment, L and R have 5 points of 32 bytes less.
+        assert_eq!(compressed_bytes.len() + 5 * 32, bytes.len());
+        let circuit_input2 = IpaCircuitInput::<Bn256>::from_bytes(&compressed_bytes)?;
+        assert_eq!(circuit_input2.to_bytes(), bytes);
+        let circuit_input3 = IpaCircuitInput::<Bn256>::from_bytes(&bytes)?;
+        assert_eq!(circuit_input3.to_bytes(), bytes);
+
+        // The header is 18 bytes and the length of the commitment section is 4 bytes.
+        let x_offset = 22;
+        // An `x` with the same parity as the actual one is not on the curve.
+        let mut invalid_bytes = bytes.clone();
+        invalid_bytes[x_offset] ^= 2;
+        assert!(matches!(
+            IpaCircuitInput::<Bn256>::from_bytes(&invalid_bytes),
+            Err(FormatError::InvalidPoint {
+                section: "commitment",
+                reason: PointError::NotOnCurve,
+            })
+        ));
+
+        // A `y` which is not less than the modulus is rejected.
+        let mut invalid_bytes = compressed_bytes.clone();
+        invalid_bytes[x_offset..(x_offset + 31)].copy_from_slice(&[0xff; 31]);
+        invalid_bytes[x_offset + 31] = 0x7f;
+        assert!(matches!(
+            IpaCircuitInput::<Bn256>::from_bytes(&invalid_bytes),
+            Err(FormatError::InvalidPoint {
+                section: "commitment",
+                reason: PointError::InvalidCoordinate,
+            })
+        ));
+
+        // `(0, -1)` is the point of order 2.
+        let mut minus_one = Fr::one();
+        minus_one.negate();
+        assert_eq!(
+            jubjub_point_from_xy::<Bn256>(Fr::zero(), minus_one, jubjub_params).err(),
+            Some(PointError::NotInPrimeOrderSubgroup)
+        );
+
+        let commitment_bytes = encode_compressed(&circuit_input.commitment);
+        assert_eq!(commitment_bytes.len(), COMPRESSED_POINT_SIZE);
+        let commitment = decode_compressed::<Bn256>(&commitment_bytes, jubjub_params)?;
+        assert_eq!(commitment.into_xy(), circuit_input.commitment.into_xy());
+
+        // The identity `(0, 1)` with the sign bit set is not the canonical encoding.
+        let mut identity_bytes = [0u8; COMPRESSED_POINT_SIZE];
+        identity_bytes[0] = 1;
+        assert!(decode_compressed::<Bn256>(&identity_bytes, jubjub_params).is_ok());
+        identity_bytes[COMPRESSED_POINT_SIZE - 1] |= 0x80;
+        assert_eq!(
+            decode_compressed::<Bn256>(&identity_bytes, jubjub_params).err(),
+            Some(PointError::NonCanonicalEncoding)
+        );
+
+        // A slice whose length is not the size of a point is rejected instead of panicking.
+        assert_eq!(
+            decode_jubjub_point::<Bn256>(
+                &commitment_bytes[1..],
+                PointEncoding::Compressed,
+                jubjub_params
+            )
+            .err(),
+            Some(PointError::InvalidLength {
+                expected: COMPRESSED_POINT_SIZE,
+                actual: COMPRESSED_POINT_SIZE - 1,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipa_fs_circuit_input_bls12_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let jubjub_params = &JubjubBls12::new();
+        let ipa_conf = &NativeIpaConfig::<Bls12>::new(4, jubjub_params)?;
+        let circuit_input = make_bls12_test_input(&[12, 97, 37, 0], 123456789, ipa_conf)?;
+
+        let bytes = circuit_input.to_bytes();
+        let circuit_input2 = IpaCircuitInput::<Bls12>::from_bytes(&bytes)?;
+        assert_eq!(circuit_input2.to_bytes(), bytes);
+
+        let mut compressed_bytes = vec![];
+        circuit_input.write_into_with_encoding(&mut compressed_bytes, PointEncoding::Compressed)?;
+        let circuit_input3 = IpaCircuitInput::<Bls12>::from_bytes(&compressed_bytes)?;
+        assert_eq!(circuit_input3.to_bytes(), bytes);
+
+        // The curve in the header keeps an input on Jubjub over BLS12-381 from being read over BN256.
+        assert!(matches!(
+            IpaCircuitInput::<Bn256>::from_bytes(&bytes),
+            Err(FormatError::UnexpectedCurve {
+                expected: Curve::JubjubBn256,
+                found: Curve::JubjubBls12,
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipa_fs_circuit_bls12() -> Result<(), Box<dyn std::error::Error>> {
+        let jubjub_params = &JubjubBls12::new();
+        let rns_params = &bls12_rns_params();
+        let ipa_conf = &NativeIpaConfig::<Bls12>::new(4, jubjub_params)?;
+        let circuit_input = make_bls12_test_input(&[12, 97, 37, 0], 123456789, ipa_conf)?;
+
+        let circuit =
+            circuit_input.make_circuit_for_proving(bls12_transcript_params(), ipa_conf, rns_params);
+        check_satisfied(&circuit)?;
+
+        // A proof of another evaluation is rejected.
+        let mut invalid_input = circuit_input.clone();
+        invalid_input
+            .proof
+            .a
+            .add_assign(&<Bls12 as JubjubEngine>::Fs::one());
+        let circuit =
+            invalid_input.make_circuit_for_proving(bls12_transcript_params(), ipa_conf, rns_params);
+        assert!(check_satisfied(&circuit).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipa_fs_truncation() -> Result<(), Box<dyn std::error::Error>> {
+        // The native tree keeps the lower 250 bits with a mask on the last limb.
+        let mut minus_one = Fr::one();
+        minus_one.negate();
+        let result: <Bn256 as JubjubEngine>::Fs = truncate_field_element(&minus_one);
+        let mut expected = minus_one.into_repr();
+        expected.as_mut()[3] &= 0x03FFFFFFFFFFFFFF;
+        assert_eq!(result.into_repr().as_ref(), expected.as_ref());
+
+        // `p - 1` has the largest canonical bits, so the circuit must still accept it.
+        let mut rns_params =
+            RnsParameters::<Bn256, <Bn256 as JubjubEngine>::Fs>::new_for_field(68, 110, 4);
+        let current_bits = rns_params.binary_limbs_bit_widths.last_mut().unwrap();
+        let remainder = *current_bits % rns_params.range_check_info.minimal_multiple;
+        if remainder != 0 {
+            *current_bits += rns_params.range_check_info.minimal_multiple - remainder;
+        }
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        let challenge = AllocatedNum::alloc(&mut cs, || Ok(minus_one))?;
+        let truncated = truncate_into_field_element(&mut cs, &challenge, &rns_params)?;
+        assert_eq!(truncated.get_field_value(), Some(result));
+        assert!(cs.is_satisfied());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipa_fs_accumulation() -> Result<(), Box<dyn std::error::Error>> {
+        let jubjub_params = &JubjubBn256::new();
+        let mut rns_params =
+            RnsParameters::<Bn256, <Bn256 as JubjubEngine>::Fs>::new_for_field(68, 110, 4);
+        let current_bits = rns_params.binary_limbs_bit_widths.last_mut().unwrap();
+        let remainder = *current_bits % rns_params.range_check_info.minimal_multiple;
+        if remainder != 0 {
+            *current_bits += rns_params.range_check_info.minimal_multiple - remainder;
+        }
+
+        let domain_size = 4;
+        let num_rounds = 2;
+        let ipa_conf = &IpaConfig::<Bn256>::new(domain_size, jubjub_params);
+
+        let mut claims = vec![];
+        for (poly, eval_point) in [
+            (vec![12, 97, 37, 0], 123456789u64),
+            (vec![1, 208, 132, 3], 7),
+        ] {
+            let eval_point: <Bn256 as JubjubEngine>::Fs =
+                read_field_element_le(&eval_point.to_le_bytes()).unwrap();
+            let padded_poly = test_poly::<<Bn256 as JubjubEngine>::Fs>(&poly, domain_size);
+            let prover_transcript = PoseidonBn256Transcript::with_bytes(b"ipa");
+            let circuit_input = make_test_input(
+                &padded_poly,
+                eval_point,
+                prover_transcript.into_params(),
+                ipa_conf,
+            )?;
+
+            let verifier_transcript = PoseidonBn256Transcript::with_bytes(b"ipa");
+            let (vk, proof) = circuit_input
+                .create_plonk_accumulation_proof::<WrapperUnchecked<'_, Bn256>>(
+                    verifier_transcript.into_params(),
+                    ipa_conf,
+                    &rns_params,
+                    open_crs_for_log2_of_size(23),
+                )?;
+
+            let is_valid = verify::<_, _, RollingKeccakTranscript<Fr>>(&vk, &proof, None)
+                .expect("must perform verification");
+            assert!(is_valid);
+
+            claims.push(DeferredIpaClaim::<Bn256>::from_public_inputs(
+                &proof.inputs,
+                num_rounds,
+                jubjub_params,
+            )?);
+        }
+
+        let rng = &mut rand::thread_rng();
+        verify_deferred_ipa_checks(&claims, ipa_conf, rng)?;
+
+        // The folded basis of another opening is rejected.
+        let mut invalid_claims = claims.clone();
+        invalid_claims[0].final_basis = claims[1].final_basis.clone();
+        assert!(matches!(
+            verify_deferred_ipa_checks(&invalid_claims, ipa_conf, rng),
+            Err(CircuitError::InvalidProof { index: None })
+        ));
+
+        // A zero challenge has no inverse to fold the basis with.
+        let mut zero_challenge_claims = claims.clone();
+        zero_challenge_claims[0].challenges[0] = <Bn256 as JubjubEngine>::Fs::zero();
+        assert!(matches!(
+            verify_deferred_ipa_checks(&zero_challenge_claims, ipa_conf, rng),
+            Err(CircuitError::InvalidInputShape(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipa_fs_circuit_rejects_wrong_depth() -> Result<(), Box<dyn std::error::Error>> {
+        let jubjub_params = &JubjubBn256::new();
+        let mut rns_params =
+            RnsParameters::<Bn256, <Bn256 as JubjubEngine>::Fs>::new_for_field(68, 110, 4);
+        let current_bits = rns_params.binary_limbs_bit_widths.last_mut().unwrap();
+        let remainder = *current_bits % rns_params.range_check_info.minimal_multiple;
+        if remainder != 0 {
+            *current_bits += rns_params.range_check_info.minimal_multiple - remainder;
+        }
+
+        let eval_point: <Bn256 as JubjubEngine>::Fs =
+            read_field_element_le(&123456789u64.to_le_bytes()).unwrap();
+        let domain_size = 4;
+        let ipa_conf = &IpaConfig::<Bn256>::new(domain_size, jubjub_params);
+        let padded_poly = test_poly::<<Bn256 as JubjubEngine>::Fs>(&[12, 97, 37, 0], domain_size);
+        let prover_transcript = PoseidonBn256Transcript::with_bytes(b"ipa");
+        let circuit_input = make_test_input(
+            &padded_poly,
+            eval_point,
+            prover_transcript.into_params(),
+            ipa_conf,
+        )?;
+
+        let verifier_transcript = PoseidonBn256Transcript::with_bytes(b"ipa");
+        let circuit = circuit_input.make_circuit_for_proving(
+            verifier_transcript.into_params(),
+            ipa_conf,
+            &rns_params,
+        );
+
+        // A proof of the domain of size 2 has one round less than the one of size 4.
+        let mut short_proof = circuit.proof.clone();
+        short_proof.l.pop();
+        short_proof.r.pop();
+        // A proof of the domain of size 8 has one round more.
+        let mut long_proof = circuit.proof.clone();
+        long_proof.l.push(long_proof.l[0].clone());
+        long_proof.r.push(long_proof.r[0].clone());
+
+        for proof in [short_proof, long_proof] {
+            let mut cs =
+                TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+            let transcript_params = AllocatedNum::alloc(&mut cs, || {
+                circuit
+                    .transcript_params
+                    .ok_or(SynthesisError::UnconstrainedVariable)
+            })?;
+            let commitment = allocate_edwards_point(&mut cs, &circuit.commitment, jubjub_params)?;
+            let eval_point =
+                FieldElement::new_allocated_in_field(&mut cs, circuit.eval_point, &rns_params)?;
+            let inner_prod =
+                FieldElement::new_allocated_in_field(&mut cs, circuit.inner_prod, &rns_params)?;
+
+            let result =
+                check_ipa_proof::<Bn256, _, UnlabeledTranscript<WrappedDummyTranscript<Bn256>>>(
+                    &mut cs,
+                    transcript_params,
+                    commitment,
+                    &proof,
+                    eval_point,
+                    inner_prod,
+                    ipa_conf,
+                );
+            assert!(matches!(result, Err(CircuitError::InvalidInputShape(_))));
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_ipa_fs_circuit_case1() -> Result<(), Box<dyn std::error::Error>> {
         let crs = open_crs_for_log2_of_size(23);
@@ -173,75 +566,140 @@ mod ipa_api_tests {
     }
 }
 
-impl FromStr for IpaCircuitInput {
-    type Err = anyhow::Error;
+impl<E: JubjubCurve> FromStr for IpaCircuitInput<E> {
+    type Err = FormatError;
 
-    fn from_str(s: &str) -> anyhow::Result<Self> {
+    fn from_str(s: &str) -> Result<Self, FormatError> {
         Self::from_bytes(s.as_bytes())
     }
 }
 
-impl IpaCircuitInput {
-    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
-        let json_str = read_to_string(path)?;
+impl<E: JubjubCurve> IpaCircuitInput<E> {
+    pub fn from_path(path: &Path) -> Result<Self, FormatError> {
+        let bytes = std::fs::read(path)?;
 
-        Self::from_str(&json_str)
+        Self::from_bytes(&bytes)
     }
 
-    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
-        let jubjub_params = &JubjubBn256::new();
-        let reader = &mut std::io::Cursor::new(bytes.to_vec());
-        let commitment_x: Fr = read_field_element_le_from(reader)?;
-        let commitment_y: Fr = read_field_element_le_from(reader)?;
-        let commitment = edwards::Point::get_for_y(
-            commitment_y,
-            commitment_x.into_repr().is_odd(),
-            jubjub_params,
-        )
-        .unwrap();
-
-        let n = reader.read_u64::<LittleEndian>()?;
-        let mut proof_l = vec![];
-        for _ in 0..n {
-            let lix: Fr = read_field_element_le_from(reader)?;
-            let liy: Fr = read_field_element_le_from(reader)?;
-            let li =
-                edwards::Point::get_for_y(liy, lix.into_repr().is_odd(), jubjub_params).unwrap();
-            proof_l.push(li);
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+        Self::read_from(&mut std::io::Cursor::new(bytes))
+    }
+
+    /// Read an input framed as
+    /// `header(IpaFs, E::CURVE, domain_size) | [commitment] | [L] | [R] | [a] | [eval_point] | [inner_prod]`.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, FormatError> {
+        let jubjub_params = &E::jubjub_params();
+        let mut frame = FrameReader::new(reader, CircuitKind::IpaFs, E::CURVE, 6)?;
+        let num_ipa_rounds = num_ipa_rounds(frame.header().domain_size)?;
+
+        let mut section = frame.read_section("commitment")?;
+        let commitment = section.read_jubjub_point::<E>(jubjub_params)?;
+        section.finish()?;
+
+        let mut proof_points = vec![];
+        for name in ["L", "R"] {
+            let mut section = frame.read_section(name)?;
+            let points = section.read_jubjub_points::<E>(jubjub_params)?;
+            section.finish()?;
+            if points.len() != num_ipa_rounds {
+                return Err(FormatError::LengthMismatch {
+                    section: name,
+                    expected: num_ipa_rounds,
+                    found: points.len(),
+                });
+            }
+            proof_points.push(points);
         }
-        let mut proof_r = vec![];
-        for _ in 0..n {
-            let rix: Fr = read_field_element_le_from(reader)?;
-            let riy: Fr = read_field_element_le_from(reader)?;
-            let ri =
-                edwards::Point::get_for_y(riy, rix.into_repr().is_odd(), jubjub_params).unwrap();
-            proof_r.push(ri);
+        let proof_r = proof_points.pop().unwrap();
+        let proof_l = proof_points.pop().unwrap();
+
+        let mut scalars = vec![];
+        for name in ["a", "eval_point", "inner_prod"] {
+            let mut section = frame.read_section(name)?;
+            scalars.push(section.read_field_element::<E::Fs>()?);
+            section.finish()?;
         }
-        let proof_a: <Bn256 as JubjubEngine>::Fs = read_field_element_le_from(reader)?;
-        let proof = IpaProof {
-            l: proof_l,
-            r: proof_r,
-            a: proof_a,
-        };
-        let eval_point = read_field_element_le_from(reader)?;
-        let inner_prod = read_field_element_le_from(reader)?;
+        frame.finish()?;
+
         let input = Self {
             commitment,
-            proof,
-            eval_point,
-            inner_prod,
+            proof: NativeIpaProof {
+                l: proof_l,
+                r: proof_r,
+                a: scalars[0],
+            },
+            eval_point: scalars[1],
+            inner_prod: scalars[2],
         };
 
         Ok(input)
     }
+
+    /// Write the input framed as
+    /// `header(IpaFs, E::CURVE, domain_size) | [commitment] | [L] | [R] | [a] | [eval_point] | [inner_prod]`.
+    /// The points are written uncompressed.
+    pub fn write_into<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_into_with_encoding(writer, PointEncoding::Uncompressed)
+    }
+
+    /// Write the input framed in the same way as `write_into`, where the points are written with `encoding`.
+    pub fn write_into_with_encoding<W: Write>(
+        &self,
+        writer: &mut W,
+        encoding: PointEncoding,
+    ) -> std::io::Result<()> {
+        let num_ipa_rounds = self.proof.l.len();
+        if self.proof.r.len() != num_ipa_rounds || num_ipa_rounds >= 64 {
+            return Err(invalid_input(format!(
+                "invalid numbers of IPA rounds: {} and {}",
+                num_ipa_rounds,
+                self.proof.r.len()
+            )));
+        }
+
+        let header = Header {
+            kind: CircuitKind::IpaFs,
+            curve: E::CURVE,
+            point_encoding: encoding,
+            domain_size: 1 << num_ipa_rounds,
+        };
+        let mut frame = FrameWriter::new(writer, header, 6)?;
+
+        let mut commitment = vec![];
+        write_jubjub_point_into(&self.commitment, encoding, &mut commitment)?;
+        frame.write_section(&commitment)?;
+
+        for points in [&self.proof.l, &self.proof.r] {
+            let mut section = vec![];
+            for point in points.iter() {
+                write_jubjub_point_into(point, encoding, &mut section)?;
+            }
+            frame.write_section(&section)?;
+        }
+
+        for scalar in [self.proof.a, self.eval_point, self.inner_prod] {
+            let mut section = vec![];
+            write_field_element_le_into(scalar, &mut section)?;
+            frame.write_section(&section)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_into(&mut bytes).unwrap();
+
+        bytes
+    }
 }
 
-impl IpaCircuitInput {
+impl<E: JubjubCurve> IpaCircuitInput<E> {
     pub fn new(
-        commitment: edwards::Point<Bn256, Unknown>,
-        proof: IpaProof<Bn256>,
-        eval_point: <Bn256 as JubjubEngine>::Fs,
-        inner_prod: <Bn256 as JubjubEngine>::Fs,
+        commitment: E::Point,
+        proof: NativeIpaProof<E>,
+        eval_point: E::Fs,
+        inner_prod: E::Fs,
     ) -> Self {
         Self {
             commitment,
@@ -253,14 +711,14 @@ impl IpaCircuitInput {
 
     pub fn make_circuit_for_proving<'a, 'b, 'c>(
         &self,
-        transcript_params: Fr,
-        ipa_conf: &'c IpaConfig<'b, Bn256>,
-        rns_params: &'a RnsParameters<Bn256, <Bn256 as JubjubEngine>::Fs>,
-    ) -> IpaCircuit<'a, 'b, 'c, Bn256>
+        transcript_params: E::Fr,
+        ipa_conf: &'c (dyn IpaSetup<E> + 'b),
+        rns_params: &'a RnsParameters<E, E::Fs>,
+    ) -> UnlabeledIpaCircuit<'a, 'b, 'c, E>
     where
         'c: 'b,
     {
-        IpaCircuit::<Bn256> {
+        UnlabeledIpaCircuit::<E> {
             transcript_params: Some(transcript_params),
             commitment: Some(self.commitment.clone()),
             proof: OptionIpaProof::from(self.proof.clone()),
@@ -268,6 +726,7 @@ impl IpaCircuitInput {
             inner_prod: Some(self.inner_prod),
             ipa_conf,
             rns_params,
+            _transcript: std::marker::PhantomData,
         }
     }
 
@@ -342,20 +801,21 @@ impl IpaCircuitInput {
     // }
 
     #[allow(clippy::type_complexity)]
-    pub fn create_plonk_proof<'a, WP: WrappedAffinePoint<'a, Bn256>>(
+    pub fn create_plonk_proof<'a, WP: WrappedAffinePoint<'a, E>>(
         &self,
-        transcript_params: Fr,
-        ipa_conf: &IpaConfig<Bn256>,
-        rns_params: &'a RnsParameters<Bn256, <Bn256 as JubjubEngine>::Fs>,
-        crs: Crs<Bn256, CrsForMonomialForm>,
+        transcript_params: E::Fr,
+        ipa_conf: &dyn IpaSetup<E>,
+        rns_params: &'a RnsParameters<E, E::Fs>,
+        crs: Crs<E, CrsForMonomialForm>,
     ) -> Result<
         (
-            VerificationKey<Bn256, IpaCircuit<Bn256>>,
-            Proof<Bn256, IpaCircuit<Bn256>>,
+            VerificationKey<E, UnlabeledIpaCircuit<E>>,
+            Proof<E, UnlabeledIpaCircuit<E>>,
         ),
-        SynthesisError,
+        CircuitError,
     > {
-        let dummy_circuit = IpaCircuit::<Bn256>::initialize(ipa_conf, rns_params);
+        let _span = tracing::info_span!("create_plonk_proof", circuit = "ipa-fs").entered();
+        let dummy_circuit = UnlabeledIpaCircuit::<E>::initialize(ipa_conf, rns_params);
         // let dummy_circuit = {
         //     transcript_params: None,
         //     commitment: None,
@@ -367,11 +827,10 @@ impl IpaCircuitInput {
         //     rns_params,
         // };
 
+        let span = tracing::info_span!("setup").entered();
         let mut dummy_assembly =
-            SetupAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        dummy_circuit
-            .synthesize(&mut dummy_assembly)
-            .expect("must synthesize");
+            SetupAssembly::<E, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        dummy_circuit.synthesize(&mut dummy_assembly)?;
         dummy_assembly.finalize();
 
         // println!("Checking if satisfied");
@@ -379,9 +838,10 @@ impl IpaCircuitInput {
         // assert!(is_satisfied, "unsatisfied constraints");
 
         let worker = franklin_crypto::bellman::worker::Worker::new();
-        let setup = dummy_assembly.create_setup::<IpaCircuit<Bn256>>(&worker)?;
+        let setup = dummy_assembly.create_setup::<UnlabeledIpaCircuit<E>>(&worker)?;
+        check_crs_size(&crs, setup.n)?;
 
-        let vk = VerificationKey::<Bn256, IpaCircuit<Bn256>>::from_setup(&setup, &worker, &crs)?;
+        let vk = VerificationKey::<E, UnlabeledIpaCircuit<E>>::from_setup(&setup, &worker, &crs)?;
 
         let circuit = self.make_circuit_for_proving(transcript_params, ipa_conf, rns_params);
         // let circuit = IpaCircuit::<Bn256> {
@@ -395,17 +855,21 @@ impl IpaCircuitInput {
         //     rns_params,
         // };
 
+        drop(span);
+
+        let span = tracing::info_span!("prove").entered();
+        check_satisfied(&circuit)?;
+
         let mut assembly =
-            ProvingAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        circuit.synthesize(&mut assembly).expect("must synthesize");
+            ProvingAssembly::<E, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        circuit.synthesize(&mut assembly)?;
         assembly.finalize();
 
-        println!("prove");
-
         let proof = assembly
-            .create_proof::<IpaCircuit<Bn256>, RollingKeccakTranscript<<Bn256 as ScalarEngine>::Fr>>(
+            .create_proof::<UnlabeledIpaCircuit<E>, RollingKeccakTranscript<E::Fr>>(
                 &worker, &setup, &crs, None,
             )?;
+        drop(span);
 
         // assert_eq!(
         //     proof.inputs,
@@ -417,4 +881,80 @@ impl IpaCircuitInput {
 
         Ok(result)
     }
+
+    pub fn make_accumulation_circuit_for_proving<'a, 'b, 'c>(
+        &self,
+        transcript_params: E::Fr,
+        ipa_conf: &'c (dyn IpaSetup<E> + 'b),
+        rns_params: &'a RnsParameters<E, E::Fs>,
+    ) -> UnlabeledIpaAccumulationCircuit<'a, 'b, 'c, E>
+    where
+        'c: 'b,
+    {
+        UnlabeledIpaAccumulationCircuit::<E> {
+            transcript_params: Some(transcript_params),
+            commitment: Some(self.commitment.clone()),
+            proof: OptionIpaProof::from(self.proof.clone()),
+            eval_point: Some(self.eval_point),
+            inner_prod: Some(self.inner_prod),
+            ipa_conf,
+            rns_params,
+            _transcript: std::marker::PhantomData,
+        }
+    }
+
+    /// Prove the opening except for the folding of the SRS,
+    /// which is left to `accumulation::verify_deferred_ipa_checks`.
+    #[allow(clippy::type_complexity)]
+    pub fn create_plonk_accumulation_proof<'a, WP: WrappedAffinePoint<'a, E>>(
+        &self,
+        transcript_params: E::Fr,
+        ipa_conf: &dyn IpaSetup<E>,
+        rns_params: &'a RnsParameters<E, E::Fs>,
+        crs: Crs<E, CrsForMonomialForm>,
+    ) -> Result<
+        (
+            VerificationKey<E, UnlabeledIpaAccumulationCircuit<E>>,
+            Proof<E, UnlabeledIpaAccumulationCircuit<E>>,
+        ),
+        CircuitError,
+    > {
+        let _span =
+            tracing::info_span!("create_plonk_proof", circuit = "ipa-fs-accumulation").entered();
+        let dummy_circuit = UnlabeledIpaAccumulationCircuit::<E>::initialize(ipa_conf, rns_params);
+
+        let span = tracing::info_span!("setup").entered();
+        let mut dummy_assembly =
+            SetupAssembly::<E, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        dummy_circuit.synthesize(&mut dummy_assembly)?;
+        dummy_assembly.finalize();
+
+        let worker = franklin_crypto::bellman::worker::Worker::new();
+        let setup = dummy_assembly.create_setup::<UnlabeledIpaAccumulationCircuit<E>>(&worker)?;
+        check_crs_size(&crs, setup.n)?;
+
+        let vk = VerificationKey::<E, UnlabeledIpaAccumulationCircuit<E>>::from_setup(
+            &setup, &worker, &crs,
+        )?;
+
+        let circuit =
+            self.make_accumulation_circuit_for_proving(transcript_params, ipa_conf, rns_params);
+        drop(span);
+
+        let span = tracing::info_span!("prove").entered();
+        check_satisfied(&circuit)?;
+
+        let mut assembly =
+            ProvingAssembly::<E, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        circuit.synthesize(&mut assembly)?;
+        assembly.finalize();
+
+        let proof = assembly
+            .create_proof::<UnlabeledIpaAccumulationCircuit<E>, RollingKeccakTranscript<E::Fr>>(
+                &worker, &setup, &crs, None,
+            )?;
+        drop(span);
+
+        Ok((vk, proof))
+    }
 }
@@ -0,0 +1,474 @@
+use std::io::{Read, Write};
+
+use byteorder::{BigEndian, WriteBytesExt};
+use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr, G1Affine};
+use franklin_crypto::bellman::plonk::better_better_cs::cs::{Circuit, GateInternal};
+use franklin_crypto::bellman::plonk::better_better_cs::proof::Proof;
+use franklin_crypto::bellman::plonk::better_better_cs::setup::VerificationKey;
+use franklin_crypto::bellman::plonk::better_cs::keys::{
+    write_curve_affine, write_curve_affine_vec, write_fr_vec, write_optional_curve_affine,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::utils::{g1_from_hex_pair, g1_to_hex_pair, g2_from_hex_pair, g2_to_hex_pair};
+use crate::circuit::utils::{field_element_from_hex, field_element_to_hex};
+
+type HexPair = (String, String);
+
+/// The names of the main gate and of all the gates which `C` declares.
+fn gate_names<C: Circuit<Bn256>>() -> anyhow::Result<(String, Vec<String>)> {
+    let main_gate = C::MainGate::default().name().to_string();
+    let gates = C::declare_used_gates()?
+        .iter()
+        .map(|gate| gate.name().to_string())
+        .collect();
+
+    Ok((main_gate, gates))
+}
+
+fn check_gate_names<C: Circuit<Bn256>>(main_gate: &str, gates: &[String]) -> anyhow::Result<()> {
+    let (expected_main_gate, expected_gates) = gate_names::<C>()?;
+    anyhow::ensure!(
+        main_gate == expected_main_gate && gates == expected_gates,
+        "the gates are {} and {:?}, but the circuit uses {} and {:?}",
+        main_gate,
+        gates,
+        expected_main_gate,
+        expected_gates
+    );
+
+    Ok(())
+}
+
+fn g1_vec_to_hex(points: &[G1Affine]) -> Vec<HexPair> {
+    points.iter().map(g1_to_hex_pair).collect()
+}
+
+fn g1_vec_from_hex(pairs: &[HexPair]) -> anyhow::Result<Vec<G1Affine>> {
+    pairs.iter().map(g1_from_hex_pair).collect()
+}
+
+fn fr_vec_to_hex(values: &[Fr]) -> Vec<String> {
+    values.iter().map(|v| field_element_to_hex(*v)).collect()
+}
+
+fn fr_vec_from_hex(values: &[String]) -> anyhow::Result<Vec<Fr>> {
+    values.iter().map(|v| field_element_from_hex(v)).collect()
+}
+
+fn fr_option_from_hex(value: &Option<String>) -> anyhow::Result<Option<Fr>> {
+    value.as_deref().map(field_element_from_hex).transpose()
+}
+
+/// A verification key whose field elements are hex strings and whose points are pairs of them.
+/// The gates are recorded by name so that a key is not read as the one of another circuit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializableVerificationKey {
+    pub main_gate: String,
+    pub gates: Vec<String>,
+    pub n: usize,
+    pub num_inputs: usize,
+    pub state_width: usize,
+    pub num_witness_polys: usize,
+    pub gate_setup_commitments: Vec<HexPair>,
+    pub gate_selectors_commitments: Vec<HexPair>,
+    pub permutation_commitments: Vec<HexPair>,
+    pub total_lookup_entries_length: usize,
+    pub lookup_selector_commitment: Option<HexPair>,
+    pub lookup_tables_commitments: Vec<HexPair>,
+    pub lookup_table_type_commitment: Option<HexPair>,
+    pub non_residues: Vec<String>,
+    pub g2_elements: [(HexPair, HexPair); 2],
+}
+
+impl SerializableVerificationKey {
+    pub fn from_vk<C: Circuit<Bn256>>(vk: &VerificationKey<Bn256, C>) -> anyhow::Result<Self> {
+        let (main_gate, gates) = gate_names::<C>()?;
+
+        Ok(Self {
+            main_gate,
+            gates,
+            n: vk.n,
+            num_inputs: vk.num_inputs,
+            state_width: vk.state_width,
+            num_witness_polys: vk.num_witness_polys,
+            gate_setup_commitments: g1_vec_to_hex(&vk.gate_setup_commitments),
+            gate_selectors_commitments: g1_vec_to_hex(&vk.gate_selectors_commitments),
+            permutation_commitments: g1_vec_to_hex(&vk.permutation_commitments),
+            total_lookup_entries_length: vk.total_lookup_entries_length,
+            lookup_selector_commitment: vk.lookup_selector_commitment.as_ref().map(g1_to_hex_pair),
+            lookup_tables_commitments: g1_vec_to_hex(&vk.lookup_tables_commitments),
+            lookup_table_type_commitment: vk
+                .lookup_table_type_commitment
+                .as_ref()
+                .map(g1_to_hex_pair),
+            non_residues: fr_vec_to_hex(&vk.non_residues),
+            g2_elements: [
+                g2_to_hex_pair(&vk.g2_elements[0]),
+                g2_to_hex_pair(&vk.g2_elements[1]),
+            ],
+        })
+    }
+
+    /// The points are checked to be on the curve, and the gates to be the ones of `C`.
+    pub fn to_vk<C: Circuit<Bn256>>(&self) -> anyhow::Result<VerificationKey<Bn256, C>> {
+        check_gate_names::<C>(&self.main_gate, &self.gates)?;
+
+        // The key has a private marker field, so it is built by reading the encoding of `VerificationKey::write`.
+        let mut bytes = vec![];
+        for value in [
+            self.n,
+            self.num_inputs,
+            self.state_width,
+            self.num_witness_polys,
+        ] {
+            bytes.write_u64::<BigEndian>(value as u64)?;
+        }
+        write_curve_affine_vec(&g1_vec_from_hex(&self.gate_setup_commitments)?, &mut bytes)?;
+        write_curve_affine_vec(
+            &g1_vec_from_hex(&self.gate_selectors_commitments)?,
+            &mut bytes,
+        )?;
+        write_curve_affine_vec(&g1_vec_from_hex(&self.permutation_commitments)?, &mut bytes)?;
+        bytes.write_u64::<BigEndian>(self.total_lookup_entries_length as u64)?;
+        write_optional_curve_affine(
+            &self
+                .lookup_selector_commitment
+                .as_ref()
+                .map(g1_from_hex_pair)
+                .transpose()?,
+            &mut bytes,
+        )?;
+        write_curve_affine_vec(
+            &g1_vec_from_hex(&self.lookup_tables_commitments)?,
+            &mut bytes,
+        )?;
+        write_optional_curve_affine(
+            &self
+                .lookup_table_type_commitment
+                .as_ref()
+                .map(g1_from_hex_pair)
+                .transpose()?,
+            &mut bytes,
+        )?;
+        write_fr_vec(&fr_vec_from_hex(&self.non_residues)?, &mut bytes)?;
+        for element in self.g2_elements.iter() {
+            write_curve_affine(&g2_from_hex_pair(element)?, &mut bytes)?;
+        }
+
+        let vk = VerificationKey::read(bytes.as_slice())?;
+
+        Ok(vk)
+    }
+}
+
+/// A proof whose field elements are hex strings and whose points are pairs of them.
+/// The openings at the dilations are recorded with the indices of the polynomials and the dilations.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializableProof {
+    pub n: usize,
+    pub inputs: Vec<String>,
+    pub state_polys_commitments: Vec<HexPair>,
+    pub witness_polys_commitments: Vec<HexPair>,
+    pub copy_permutation_grand_product_commitment: HexPair,
+    pub lookup_s_poly_commitment: Option<HexPair>,
+    pub lookup_grand_product_commitment: Option<HexPair>,
+    pub quotient_poly_parts_commitments: Vec<HexPair>,
+    pub state_polys_openings_at_z: Vec<String>,
+    pub state_polys_openings_at_dilations: Vec<(usize, usize, String)>,
+    pub witness_polys_openings_at_z: Vec<String>,
+    pub witness_polys_openings_at_dilations: Vec<(usize, usize, String)>,
+    pub gate_setup_openings_at_z: Vec<(usize, usize, String)>,
+    pub gate_selectors_openings_at_z: Vec<(usize, String)>,
+    pub copy_permutation_polys_openings_at_z: Vec<String>,
+    pub copy_permutation_grand_product_opening_at_z_omega: String,
+    pub lookup_s_poly_opening_at_z_omega: Option<String>,
+    pub lookup_grand_product_opening_at_z_omega: Option<String>,
+    pub lookup_t_poly_opening_at_z: Option<String>,
+    pub lookup_t_poly_opening_at_z_omega: Option<String>,
+    pub lookup_selector_poly_opening_at_z: Option<String>,
+    pub lookup_table_type_poly_opening_at_z: Option<String>,
+    pub quotient_poly_opening_at_z: String,
+    pub linearization_poly_opening_at_z: String,
+    pub opening_proof_at_z: HexPair,
+    pub opening_proof_at_z_omega: HexPair,
+}
+
+fn dilations_to_hex(values: &[(usize, usize, Fr)]) -> Vec<(usize, usize, String)> {
+    values
+        .iter()
+        .map(|(i, dilation, v)| (*i, *dilation, field_element_to_hex(*v)))
+        .collect()
+}
+
+fn dilations_from_hex(
+    values: &[(usize, usize, String)],
+) -> anyhow::Result<Vec<(usize, usize, Fr)>> {
+    values
+        .iter()
+        .map(|(i, dilation, v)| Ok((*i, *dilation, field_element_from_hex(v)?)))
+        .collect()
+}
+
+impl SerializableProof {
+    pub fn from_proof<C: Circuit<Bn256>>(proof: &Proof<Bn256, C>) -> Self {
+        let fr_option_to_hex = |v: &Option<Fr>| v.map(field_element_to_hex);
+
+        Self {
+            n: proof.n,
+            inputs: fr_vec_to_hex(&proof.inputs),
+            state_polys_commitments: g1_vec_to_hex(&proof.state_polys_commitments),
+            witness_polys_commitments: g1_vec_to_hex(&proof.witness_polys_commitments),
+            copy_permutation_grand_product_commitment: g1_to_hex_pair(
+                &proof.copy_permutation_grand_product_commitment,
+            ),
+            lookup_s_poly_commitment: proof.lookup_s_poly_commitment.as_ref().map(g1_to_hex_pair),
+            lookup_grand_product_commitment: proof
+                .lookup_grand_product_commitment
+                .as_ref()
+                .map(g1_to_hex_pair),
+            quotient_poly_parts_commitments: g1_vec_to_hex(&proof.quotient_poly_parts_commitments),
+            state_polys_openings_at_z: fr_vec_to_hex(&proof.state_polys_openings_at_z),
+            state_polys_openings_at_dilations: dilations_to_hex(
+                &proof.state_polys_openings_at_dilations,
+            ),
+            witness_polys_openings_at_z: fr_vec_to_hex(&proof.witness_polys_openings_at_z),
+            witness_polys_openings_at_dilations: dilations_to_hex(
+                &proof.witness_polys_openings_at_dilations,
+            ),
+            gate_setup_openings_at_z: dilations_to_hex(&proof.gate_setup_openings_at_z),
+            gate_selectors_openings_at_z: proof
+                .gate_selectors_openings_at_z
+                .iter()
+                .map(|(i, v)| (*i, field_element_to_hex(*v)))
+                .collect(),
+            copy_permutation_polys_openings_at_z: fr_vec_to_hex(
+                &proof.copy_permutation_polys_openings_at_z,
+            ),
+            copy_permutation_grand_product_opening_at_z_omega: field_element_to_hex(
+                proof.copy_permutation_grand_product_opening_at_z_omega,
+            ),
+            lookup_s_poly_opening_at_z_omega: fr_option_to_hex(
+                &proof.lookup_s_poly_opening_at_z_omega,
+            ),
+            lookup_grand_product_opening_at_z_omega: fr_option_to_hex(
+                &proof.lookup_grand_product_opening_at_z_omega,
+            ),
+            lookup_t_poly_opening_at_z: fr_option_to_hex(&proof.lookup_t_poly_opening_at_z),
+            lookup_t_poly_opening_at_z_omega: fr_option_to_hex(
+                &proof.lookup_t_poly_opening_at_z_omega,
+            ),
+            lookup_selector_poly_opening_at_z: fr_option_to_hex(
+                &proof.lookup_selector_poly_opening_at_z,
+            ),
+            lookup_table_type_poly_opening_at_z: fr_option_to_hex(
+                &proof.lookup_table_type_poly_opening_at_z,
+            ),
+            quotient_poly_opening_at_z: field_element_to_hex(proof.quotient_poly_opening_at_z),
+            linearization_poly_opening_at_z: field_element_to_hex(
+                proof.linearization_poly_opening_at_z,
+            ),
+            opening_proof_at_z: g1_to_hex_pair(&proof.opening_proof_at_z),
+            opening_proof_at_z_omega: g1_to_hex_pair(&proof.opening_proof_at_z_omega),
+        }
+    }
+
+    pub fn to_proof<C: Circuit<Bn256>>(&self) -> anyhow::Result<Proof<Bn256, C>> {
+        let g1_option_from_hex = |v: &Option<HexPair>| v.as_ref().map(g1_from_hex_pair).transpose();
+
+        let mut proof = Proof::<Bn256, C>::empty();
+        proof.n = self.n;
+        proof.inputs = fr_vec_from_hex(&self.inputs)?;
+        proof.state_polys_commitments = g1_vec_from_hex(&self.state_polys_commitments)?;
+        proof.witness_polys_commitments = g1_vec_from_hex(&self.witness_polys_commitments)?;
+        proof.copy_permutation_grand_product_commitment =
+            g1_from_hex_pair(&self.copy_permutation_grand_product_commitment)?;
+        proof.lookup_s_poly_commitment = g1_option_from_hex(&self.lookup_s_poly_commitment)?;
+        proof.lookup_grand_product_commitment =
+            g1_option_from_hex(&self.lookup_grand_product_commitment)?;
+        proof.quotient_poly_parts_commitments =
+            g1_vec_from_hex(&self.quotient_poly_parts_commitments)?;
+        proof.state_polys_openings_at_z = fr_vec_from_hex(&self.state_polys_openings_at_z)?;
+        proof.state_polys_openings_at_dilations =
+            dilations_from_hex(&self.state_polys_openings_at_dilations)?;
+        proof.witness_polys_openings_at_z = fr_vec_from_hex(&self.witness_polys_openings_at_z)?;
+        proof.witness_polys_openings_at_dilations =
+            dilations_from_hex(&self.witness_polys_openings_at_dilations)?;
+        proof.gate_setup_openings_at_z = dilations_from_hex(&self.gate_setup_openings_at_z)?;
+        proof.gate_selectors_openings_at_z = self
+            .gate_selectors_openings_at_z
+            .iter()
+            .map(|(i, v)| Ok((*i, field_element_from_hex(v)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        proof.copy_permutation_polys_openings_at_z =
+            fr_vec_from_hex(&self.copy_permutation_polys_openings_at_z)?;
+        proof.copy_permutation_grand_product_opening_at_z_omega =
+            field_element_from_hex(&self.copy_permutation_grand_product_opening_at_z_omega)?;
+        proof.lookup_s_poly_opening_at_z_omega =
+            fr_option_from_hex(&self.lookup_s_poly_opening_at_z_omega)?;
+        proof.lookup_grand_product_opening_at_z_omega =
+            fr_option_from_hex(&self.lookup_grand_product_opening_at_z_omega)?;
+        proof.lookup_t_poly_opening_at_z = fr_option_from_hex(&self.lookup_t_poly_opening_at_z)?;
+        proof.lookup_t_poly_opening_at_z_omega =
+            fr_option_from_hex(&self.lookup_t_poly_opening_at_z_omega)?;
+        proof.lookup_selector_poly_opening_at_z =
+            fr_option_from_hex(&self.lookup_selector_poly_opening_at_z)?;
+        proof.lookup_table_type_poly_opening_at_z =
+            fr_option_from_hex(&self.lookup_table_type_poly_opening_at_z)?;
+        proof.quotient_poly_opening_at_z =
+            field_element_from_hex(&self.quotient_poly_opening_at_z)?;
+        proof.linearization_poly_opening_at_z =
+            field_element_from_hex(&self.linearization_poly_opening_at_z)?;
+        proof.opening_proof_at_z = g1_from_hex_pair(&self.opening_proof_at_z)?;
+        proof.opening_proof_at_z_omega = g1_from_hex_pair(&self.opening_proof_at_z_omega)?;
+
+        Ok(proof)
+    }
+}
+
+pub fn write_vk_json<C: Circuit<Bn256>, W: Write>(
+    vk: &VerificationKey<Bn256, C>,
+    writer: W,
+) -> anyhow::Result<()> {
+    serde_json::to_writer_pretty(writer, &SerializableVerificationKey::from_vk(vk)?)?;
+
+    Ok(())
+}
+
+pub fn read_vk_json<C: Circuit<Bn256>, R: Read>(
+    reader: R,
+) -> anyhow::Result<VerificationKey<Bn256, C>> {
+    let raw: SerializableVerificationKey = serde_json::from_reader(reader)?;
+
+    raw.to_vk()
+}
+
+pub fn write_proof_json<C: Circuit<Bn256>, W: Write>(
+    proof: &Proof<Bn256, C>,
+    writer: W,
+) -> anyhow::Result<()> {
+    serde_json::to_writer_pretty(writer, &SerializableProof::from_proof(proof))?;
+
+    Ok(())
+}
+
+pub fn read_proof_json<C: Circuit<Bn256>, R: Read>(reader: R) -> anyhow::Result<Proof<Bn256, C>> {
+    let raw: SerializableProof = serde_json::from_reader(reader)?;
+
+    raw.to_proof()
+}
+
+#[cfg(test)]
+mod json_api_tests {
+    use franklin_crypto::babyjubjub::fs::{Fs, FsRepr};
+    use franklin_crypto::babyjubjub::{edwards, JubjubBn256, Unknown};
+    use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr, FrRepr};
+    use franklin_crypto::bellman::plonk::better_better_cs::verifier::verify;
+    use franklin_crypto::bellman::plonk::commitments::transcript::keccak_transcript::RollingKeccakTranscript;
+    use franklin_crypto::bellman::PrimeField;
+    use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::aux_data::BN256AuxData;
+    use generic_array::typenum;
+    use verkle_tree::ipa_fr::utils::read_field_element_le;
+
+    use crate::api::discrete_log::input::DiscreteLogCircuitInput;
+    use crate::api::poseidon::input::{make_test_input, VkAndProof};
+    use crate::api::stats::make_fs_rns_params;
+    use crate::api::utils::open_crs_for_log2_of_size;
+    use crate::circuit::discrete_log::DiscreteLogCircuit;
+    use crate::circuit::poseidon::PoseidonCircuit;
+    use crate::circuit::utils::field_element_to_hex;
+
+    use super::{
+        read_proof_json, read_vk_json, write_proof_json, write_vk_json, SerializableProof,
+        SerializableVerificationKey,
+    };
+
+    #[test]
+    fn test_poseidon_proof_json() -> Result<(), Box<dyn std::error::Error>> {
+        let crs = open_crs_for_log2_of_size(12);
+        let input1 = read_field_element_le::<Fr>(&[1]).unwrap();
+        let input2 = read_field_element_le::<Fr>(&[2]).unwrap();
+        let circuit_input = make_test_input(vec![input1, input2]);
+        let VkAndProof(vk, proof) = circuit_input.create_plonk_proof(crs)?;
+
+        let mut vk_json = vec![];
+        write_vk_json(&vk, &mut vk_json)?;
+        let mut proof_json = vec![];
+        write_proof_json(&proof, &mut proof_json)?;
+
+        let vk2 = read_vk_json::<PoseidonCircuit<Bn256, typenum::U2>, _>(vk_json.as_slice())?;
+        let proof2 = read_proof_json(proof_json.as_slice())?;
+        let is_valid = verify::<_, _, RollingKeccakTranscript<Fr>>(&vk2, &proof2, None)
+            .expect("must perform verification");
+        assert!(is_valid);
+        assert_eq!(
+            SerializableVerificationKey::from_vk(&vk2)?,
+            SerializableVerificationKey::from_vk(&vk)?
+        );
+        assert_eq!(
+            SerializableProof::from_proof(&proof2),
+            SerializableProof::from_proof(&proof)
+        );
+
+        // The output is a hex string, and the gates are recorded by name.
+        let raw: serde_json::Value = serde_json::from_slice(&proof_json)?;
+        assert_eq!(raw["inputs"][0], field_element_to_hex(circuit_input.output));
+        let mut raw: serde_json::Value = serde_json::from_slice(&vk_json)?;
+        raw["gates"] = serde_json::json!([]);
+        let modified = serde_json::to_vec(&raw)?;
+        assert!(
+            read_vk_json::<PoseidonCircuit<Bn256, typenum::U2>, _>(modified.as_slice()).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_discrete_log_proof_json() -> Result<(), Box<dyn std::error::Error>> {
+        let jubjub_params = &JubjubBn256::new();
+        let rns_params = make_fs_rns_params();
+        let crs = open_crs_for_log2_of_size(14);
+
+        // The same base point as `test_discrete_log_circuit`, whose `x` is even.
+        let base_point_y = Fr::from_repr(FrRepr([
+            0x1f334e763bfd6753,
+            0xeb3d004136b45cfc,
+            0x9fbacc86a287b5b1,
+            0x190eddeda5ed1c18,
+        ]))?;
+        let base_point =
+            edwards::Point::<Bn256, Unknown>::get_for_y(base_point_y, false, jubjub_params)
+                .unwrap();
+        let coefficient = Fs::from_repr(FsRepr([10493827077, 0, 0, 0]))?;
+        let circuit_input = DiscreteLogCircuitInput::new(base_point, coefficient);
+        let (vk, proof) = circuit_input.create_plonk_proof(jubjub_params, &rns_params, crs)?;
+
+        let mut vk_json = vec![];
+        write_vk_json(&vk, &mut vk_json)?;
+        let mut proof_json = vec![];
+        write_proof_json(&proof, &mut proof_json)?;
+
+        type Circuit<'a> = DiscreteLogCircuit<'a, Bn256, BN256AuxData>;
+        let vk2 = read_vk_json::<Circuit, _>(vk_json.as_slice())?;
+        let proof2 = read_proof_json::<Circuit, _>(proof_json.as_slice())?;
+        let is_valid = verify::<_, _, RollingKeccakTranscript<Fr>>(&vk2, &proof2, None)
+            .expect("must perform verification");
+        assert!(is_valid);
+        assert_eq!(
+            SerializableVerificationKey::from_vk(&vk2)?,
+            SerializableVerificationKey::from_vk(&vk)?
+        );
+        assert_eq!(
+            SerializableProof::from_proof(&proof2),
+            SerializableProof::from_proof(&proof)
+        );
+
+        // The key of the discrete log circuit is not read as the one of the Poseidon circuit,
+        // which declares no range check gate.
+        assert!(
+            read_vk_json::<PoseidonCircuit<Bn256, typenum::U2>, _>(vk_json.as_slice()).is_err()
+        );
+
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
 pub mod batch_proof_fr;
 pub mod batch_proof_fs;
 pub mod discrete_log;
+pub mod format;
 pub mod ipa_fr;
 pub mod ipa_fs;
+pub mod json;
+pub mod point;
 pub mod poseidon;
-pub mod poseidon_fs;
+pub mod solidity;
+pub mod stats;
 pub mod utils;
 pub mod verkle_tree;
 pub mod verkle_tree_fs;
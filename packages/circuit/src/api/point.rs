@@ -0,0 +1,190 @@
+use std::fmt;
+use std::io::Write;
+
+use franklin_crypto::bellman::{PrimeField, PrimeFieldRepr};
+
+use crate::circuit::num::jubjub::{JubjubEngine, JubjubPoint};
+
+/// The size of a compressed point of Jubjub, which is `y` with the sign of `x` in the most significant bit.
+pub const COMPRESSED_POINT_SIZE: usize = 32;
+
+/// The size of an uncompressed point of Jubjub, which is `x` followed by `y`.
+pub const UNCOMPRESSED_POINT_SIZE: usize = 64;
+
+/// How points of Jubjub are encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PointEncoding {
+    /// The little-endian coordinates `x` and `y`.
+    Uncompressed = 0,
+    /// The little-endian `y` whose most significant bit is the parity of `x`.
+    Compressed = 1,
+}
+
+impl PointEncoding {
+    pub fn point_size(&self) -> usize {
+        match self {
+            Self::Uncompressed => UNCOMPRESSED_POINT_SIZE,
+            Self::Compressed => COMPRESSED_POINT_SIZE,
+        }
+    }
+}
+
+/// The reason why bytes are not a valid point of Jubjub.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointError {
+    /// A coordinate is not less than the modulus of `Fr`.
+    InvalidCoordinate,
+    /// The coordinates do not satisfy the curve equation.
+    NotOnCurve,
+    /// The point is on the curve, but it is not in the prime-order subgroup.
+    NotInPrimeOrderSubgroup,
+    /// The number of bytes does not match the size of a point.
+    InvalidLength { expected: usize, actual: usize },
+    /// The sign bit does not match the parity of `x`, e.g. it is set while `x` is zero.
+    NonCanonicalEncoding,
+}
+
+impl fmt::Display for PointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCoordinate => write!(f, "a coordinate is not less than the modulus"),
+            Self::NotOnCurve => write!(f, "the point is not on the curve"),
+            Self::NotInPrimeOrderSubgroup => {
+                write!(f, "the point is not in the prime-order subgroup")
+            }
+            Self::InvalidLength { expected, actual } => {
+                write!(
+                    f,
+                    "expected {} bytes for a point, but got {}",
+                    expected, actual
+                )
+            }
+            Self::NonCanonicalEncoding => write!(f, "the sign bit does not match the parity of x"),
+        }
+    }
+}
+
+impl std::error::Error for PointError {}
+
+fn read_coordinate<F: PrimeField>(bytes: &[u8]) -> Result<F, PointError> {
+    let mut repr = F::Repr::default();
+    repr.read_le(bytes)
+        .map_err(|_| PointError::InvalidCoordinate)?;
+
+    F::from_repr(repr).map_err(|_| PointError::InvalidCoordinate)
+}
+
+fn write_coordinate<F: PrimeField>(value: F, bytes: &mut [u8]) {
+    value.into_repr().write_le(bytes).unwrap();
+}
+
+/// Check that `point` is in the prime-order subgroup, i.e. it vanishes when multiplied by the order of `Fs`.
+fn check_prime_order<E: JubjubEngine>(
+    point: E::Point,
+    jubjub_params: &E::Params,
+) -> Result<E::Point, PointError> {
+    let order = <E::Fs as PrimeField>::char();
+    if point.mul(order, jubjub_params) != E::Point::zero() {
+        return Err(PointError::NotInPrimeOrderSubgroup);
+    }
+
+    Ok(point)
+}
+
+/// Returns the point `(x, y)`, checking the curve equation and the prime-order subgroup.
+pub fn jubjub_point_from_xy<E: JubjubEngine>(
+    x: E::Fr,
+    y: E::Fr,
+    jubjub_params: &E::Params,
+) -> Result<E::Point, PointError> {
+    // `get_for_y` solves the curve equation for `x`, so `(x, y)` is on the curve
+    // if and only if the solution with the same parity is `x` itself.
+    let point = E::Point::get_for_y(y, x.into_repr().is_odd(), jubjub_params)
+        .ok_or(PointError::NotOnCurve)?;
+    if point.into_xy().0 != x {
+        return Err(PointError::NotOnCurve);
+    }
+
+    check_prime_order(point, jubjub_params)
+}
+
+pub fn encode_uncompressed<E: JubjubEngine>(point: &E::Point) -> [u8; UNCOMPRESSED_POINT_SIZE] {
+    let (x, y) = point.into_xy();
+    let mut bytes = [0u8; UNCOMPRESSED_POINT_SIZE];
+    write_coordinate(x, &mut bytes[..32]);
+    write_coordinate(y, &mut bytes[32..]);
+
+    bytes
+}
+
+pub fn decode_uncompressed<E: JubjubEngine>(
+    bytes: &[u8; UNCOMPRESSED_POINT_SIZE],
+    jubjub_params: &E::Params,
+) -> Result<E::Point, PointError> {
+    let x = read_coordinate::<E::Fr>(&bytes[..32])?;
+    let y = read_coordinate::<E::Fr>(&bytes[32..])?;
+
+    jubjub_point_from_xy(x, y, jubjub_params)
+}
+
+pub fn encode_compressed<E: JubjubEngine>(point: &E::Point) -> [u8; COMPRESSED_POINT_SIZE] {
+    let (x, y) = point.into_xy();
+    let mut bytes = [0u8; COMPRESSED_POINT_SIZE];
+    write_coordinate(y, &mut bytes);
+    // `Fr` has less than 256 bits, so the most significant bit of `y` is always zero.
+    if x.into_repr().is_odd() {
+        bytes[COMPRESSED_POINT_SIZE - 1] |= 0x80;
+    }
+
+    bytes
+}
+
+pub fn decode_compressed<E: JubjubEngine>(
+    bytes: &[u8; COMPRESSED_POINT_SIZE],
+    jubjub_params: &E::Params,
+) -> Result<E::Point, PointError> {
+    let mut y_bytes = *bytes;
+    let sign = y_bytes[COMPRESSED_POINT_SIZE - 1] >> 7 == 1;
+    y_bytes[COMPRESSED_POINT_SIZE - 1] &= 0x7f;
+    let y = read_coordinate::<E::Fr>(&y_bytes)?;
+    let point = E::Point::get_for_y(y, sign, jubjub_params).ok_or(PointError::NotOnCurve)?;
+    // Negating `x = 0` leaves it even, so `get_for_y` ignores the sign for such points.
+    if point.into_xy().0.into_repr().is_odd() != sign {
+        return Err(PointError::NonCanonicalEncoding);
+    }
+
+    check_prime_order(point, jubjub_params)
+}
+
+/// Write `point` into `writer` with `encoding`.
+pub fn write_jubjub_point_into<E: JubjubEngine, W: Write>(
+    point: &E::Point,
+    encoding: PointEncoding,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    match encoding {
+        PointEncoding::Uncompressed => writer.write_all(&encode_uncompressed(point)),
+        PointEncoding::Compressed => writer.write_all(&encode_compressed(point)),
+    }
+}
+
+/// Decode `bytes` with `encoding`, whose length must be `encoding.point_size()`.
+pub fn decode_jubjub_point<E: JubjubEngine>(
+    bytes: &[u8],
+    encoding: PointEncoding,
+    jubjub_params: &E::Params,
+) -> Result<E::Point, PointError> {
+    let invalid_length = |_| PointError::InvalidLength {
+        expected: encoding.point_size(),
+        actual: bytes.len(),
+    };
+    match encoding {
+        PointEncoding::Uncompressed => {
+            decode_uncompressed(bytes.try_into().map_err(invalid_length)?, jubjub_params)
+        }
+        PointEncoding::Compressed => {
+            decode_compressed(bytes.try_into().map_err(invalid_length)?, jubjub_params)
+        }
+    }
+}
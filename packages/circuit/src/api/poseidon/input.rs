@@ -1,31 +1,35 @@
 use std::{
-    fs::read_to_string,
     io::{Read, Write},
     path::Path,
     str::FromStr,
 };
 
-use byteorder::{ReadBytesExt, WriteBytesExt};
 use franklin_crypto::bellman::kate_commitment::{Crs, CrsForMonomialForm};
 use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr};
 use franklin_crypto::bellman::plonk::better_better_cs::cs::{
-    Circuit, ProvingAssembly, SetupAssembly, TrivialAssembly, Width4MainGateWithDNext,
+    Circuit, ProvingAssembly, SetupAssembly, Width4MainGateWithDNext,
 };
 use franklin_crypto::bellman::plonk::better_better_cs::proof::Proof;
 use franklin_crypto::bellman::plonk::better_better_cs::setup::VerificationKey;
 use franklin_crypto::bellman::plonk::commitments::transcript::keccak_transcript::RollingKeccakTranscript;
-use franklin_crypto::bellman::{ScalarEngine, SynthesisError};
+use franklin_crypto::bellman::ScalarEngine;
 use franklin_crypto::plonk::circuit::Width4WithCustomGates;
 use generic_array::{typenum::*, ArrayLength, GenericArray};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::circuit::poseidon::PoseidonCircuit;
+use crate::api::format::{
+    invalid_input, CircuitKind, Curve, FormatError, FrameReader, FrameWriter, Header,
+};
+use crate::api::point::PointEncoding;
+use crate::api::utils::check_crs_size;
+use crate::circuit::debug::check_satisfied;
+use crate::circuit::poseidon::{PoseidonCircuit, PoseidonConfig, PoseidonFrConfig};
 // use serde::{Deserialize, Serialize};
 
 use crate::circuit::utils::{
-    read_field_element_be_from, read_field_element_le_from, write_field_element_be_into,
-    write_field_element_le_into,
+    read_field_element_be_from, write_field_element_be_into, write_field_element_le_into,
 };
+use crate::error::CircuitError;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PoseidonCircuitInput<N = U2>
@@ -37,6 +41,30 @@ where
     _n: std::marker::PhantomData<N>,
 }
 
+/// Returns the input whose output is the Poseidon hash of `inputs` computed by neptune.
+#[cfg(test)]
+pub(crate) fn make_test_input(inputs: Vec<Fr>) -> PoseidonCircuitInput<U2> {
+    use verkle_tree::ff_utils::bn256_fr::Bn256Fr;
+    use verkle_tree::ipa_fr::transcript::{convert_ff_ce_to_ff, convert_ff_to_ff_ce};
+    use verkle_tree::neptune::poseidon::PoseidonConstants;
+    use verkle_tree::neptune::Poseidon;
+
+    let preimage = inputs
+        .iter()
+        .map(|input| convert_ff_ce_to_ff(*input))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .unwrap();
+    let constants = PoseidonConstants::new();
+    let mut h = Poseidon::<Bn256Fr, U2>::new_with_preimage(&preimage, &constants);
+    let output = convert_ff_to_ff_ce(h.hash()).unwrap();
+
+    PoseidonCircuitInput {
+        inputs,
+        output,
+        _n: std::marker::PhantomData,
+    }
+}
+
 #[cfg(test)]
 mod poseidon_api_tests {
     use std::fs::{read_to_string, File, OpenOptions};
@@ -45,38 +73,32 @@ mod poseidon_api_tests {
 
     use franklin_crypto::bellman::kate_commitment::{Crs, CrsForMonomialForm};
     use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr};
+    use franklin_crypto::bellman::plonk::better_better_cs::cs::{
+        Circuit, SetupAssembly, TrivialAssembly, Width4MainGateWithDNext,
+    };
     use franklin_crypto::bellman::plonk::better_better_cs::verifier::verify;
     use franklin_crypto::bellman::plonk::commitments::transcript::keccak_transcript::RollingKeccakTranscript;
     use franklin_crypto::bellman::Field;
-    use generic_array::typenum;
+    use franklin_crypto::plonk::circuit::Width4WithCustomGates;
+    use generic_array::{typenum, ArrayLength, GenericArray};
     use verkle_tree::ff_utils::bn256_fr::Bn256Fr;
     use verkle_tree::ipa_fr::transcript::{convert_ff_ce_to_ff, convert_ff_to_ff_ce};
     use verkle_tree::ipa_fr::utils::read_field_element_le;
     use verkle_tree::neptune::poseidon::PoseidonConstants;
-    use verkle_tree::neptune::Poseidon;
-    // use crate::circuit::poseidon::PoseidonCircuit;
+    use verkle_tree::neptune::{Arity, Poseidon};
 
-    use super::{PoseidonCircuitInput, VkAndProof};
+    use crate::circuit::poseidon::{
+        permute_native, CustomGateSbox, PoseidonCircuit, PoseidonConfig,
+        PoseidonConstants as CircuitPoseidonConstants, PoseidonFrConfig, PoseidonFsConfig,
+    };
 
-    const CIRCUIT_NAME: &str = "poseidon";
+    use crate::api::format::{CircuitKind, FormatError};
+    use crate::api::point::PointEncoding;
+    use crate::error::CircuitError;
 
-    fn make_test_input(inputs: Vec<Fr>) -> PoseidonCircuitInput<typenum::U2> {
-        let preimage = inputs
-            .iter()
-            .map(|input| convert_ff_ce_to_ff(*input))
-            .collect::<anyhow::Result<Vec<_>>>()
-            .unwrap();
-        let constants = PoseidonConstants::new();
-        let mut h = Poseidon::<Bn256Fr, typenum::U2>::new_with_preimage(&preimage, &constants);
-        let output = convert_ff_to_ff_ce(h.hash()).unwrap();
-        println!("output: {:?}", output);
+    use super::{make_test_input, PoseidonCircuitInput, VkAndProof};
 
-        PoseidonCircuitInput {
-            inputs,
-            output,
-            _n: std::marker::PhantomData,
-        }
-    }
+    const CIRCUIT_NAME: &str = "poseidon";
 
     fn open_crs_for_log2_of_size(_log2_n: usize) -> Crs<Bn256, CrsForMonomialForm> {
         let full_path = Path::new("./test_cases").join("crs");
@@ -129,6 +151,196 @@ mod poseidon_api_tests {
         Ok(())
     }
 
+    fn check_poseidon_circuit_arity<A>() -> Result<(), Box<dyn std::error::Error>>
+    where
+        A: Arity<Bn256Fr> + ArrayLength<Option<Fr>>,
+    {
+        let inputs = (0..A::to_usize())
+            .map(|i| read_field_element_le::<Fr>(&[i as u8 + 1]))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let preimage = inputs
+            .iter()
+            .map(|input| convert_ff_ce_to_ff(*input))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let constants = PoseidonConstants::new();
+        let mut h = Poseidon::<Bn256Fr, A>::new_with_preimage(&preimage, &constants);
+        let output = convert_ff_to_ff_ce(h.hash())?;
+
+        let circuit = PoseidonCircuit::<Bn256, A> {
+            inputs: inputs
+                .iter()
+                .map(|&x| Some(x))
+                .collect::<GenericArray<_, _>>(),
+            output: Some(output),
+            _config: std::marker::PhantomData,
+        };
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        circuit.synthesize(&mut cs)?;
+        assert!(
+            cs.is_satisfied(),
+            "arity {} is not satisfied",
+            A::to_usize()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fr_poseidon_circuit_arities() -> Result<(), Box<dyn std::error::Error>> {
+        check_poseidon_circuit_arity::<typenum::U1>()?;
+        check_poseidon_circuit_arity::<typenum::U2>()?;
+        check_poseidon_circuit_arity::<typenum::U4>()?;
+        check_poseidon_circuit_arity::<typenum::U8>()?;
+        check_poseidon_circuit_arity::<typenum::U11>()?;
+        check_poseidon_circuit_arity::<typenum::U16>()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_native_poseidon_matches_neptune() {
+        // The dummy transcript of the IPA circuits over Jubjub hashes with `permute_native`.
+        let inputs = vec![
+            read_field_element_le::<Fr>(&[1]).unwrap(),
+            read_field_element_le::<Fr>(&[2]).unwrap(),
+        ];
+        let circuit_input = make_test_input(inputs.clone());
+
+        let constants = CircuitPoseidonConstants::<Bn256>::cached(2).unwrap();
+        let mut elements = vec![constants.domain_tag, inputs[0], inputs[1]];
+        permute_native(&mut elements, &constants);
+        assert_eq!(elements[1], circuit_input.output);
+    }
+
+    #[test]
+    fn test_fr_poseidon_circuit_size() -> Result<(), Box<dyn std::error::Error>> {
+        let circuit = PoseidonCircuit::<Bn256, typenum::U2> {
+            inputs: (0..2).map(|_| None).collect::<GenericArray<_, _>>(),
+            output: None,
+            _config: std::marker::PhantomData,
+        };
+        let mut cs = SetupAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        circuit.synthesize(&mut cs)?;
+
+        // The dense linear layer with multiplication gates took about 1,560 gates,
+        // and the linear combinations with the round constants folded in take less than a third.
+        let n = cs.n();
+        println!("the number of gates: {}", n);
+        assert!(n <= 520, "too many gates: {}", n);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fr_poseidon_circuit_with_custom_gate() -> Result<(), Box<dyn std::error::Error>> {
+        let input1 = read_field_element_le::<Fr>(&[1])?;
+        let input2 = read_field_element_le::<Fr>(&[2])?;
+        let circuit_input = make_test_input(vec![input1, input2]);
+        let circuit = PoseidonCircuit::<Bn256, typenum::U2, PoseidonFrConfig<CustomGateSbox>> {
+            inputs: circuit_input
+                .inputs
+                .iter()
+                .map(|&x| Some(x))
+                .collect::<GenericArray<_, _>>(),
+            output: Some(circuit_input.output),
+            _config: std::marker::PhantomData,
+        };
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        circuit.synthesize(&mut cs)?;
+        assert!(cs.is_satisfied());
+
+        let circuit = PoseidonCircuit::<Bn256, typenum::U2> {
+            inputs: circuit.inputs.clone(),
+            output: circuit.output,
+            _config: std::marker::PhantomData,
+        };
+        let mut main_gate_cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        circuit.synthesize(&mut main_gate_cs)?;
+
+        // Each S-box takes one row instead of three.
+        let constants = CircuitPoseidonConstants::<Bn256>::cached(2)?;
+        let num_sboxes = constants.full_rounds * constants.width() + constants.partial_rounds;
+        assert_eq!(main_gate_cs.n() - cs.n(), 2 * num_sboxes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fs_poseidon_circuit_case1() -> Result<(), Box<dyn std::error::Error>> {
+        let crs = open_crs_for_log2_of_size(14);
+        let input1 = read_field_element_le::<Fr>(&[1]).unwrap();
+        let input2 = read_field_element_le::<Fr>(&[2]).unwrap();
+        let inputs = vec![input1, input2];
+        let circuit_input = make_test_input(inputs);
+        let VkAndProof(vk, proof) =
+            circuit_input.create_plonk_proof_with_config::<PoseidonFsConfig>(crs)?;
+        let is_valid = verify::<_, _, RollingKeccakTranscript<Fr>>(&vk, &proof, None)
+            .expect("must perform verification");
+        assert!(is_valid);
+
+        let proof_path = Path::new("./test_cases")
+            .join("poseidon_fs")
+            .join("proof_case1");
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(proof_path)?;
+        proof.write(file)?;
+        let vk_path = Path::new("./test_cases")
+            .join("poseidon_fs")
+            .join("vk_case1");
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(vk_path)?;
+        vk.write(file)?;
+
+        Ok(())
+    }
+
+    fn check_poseidon_config<C: PoseidonConfig>(
+        num_gates: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // The Poseidon hash of `[1, 2]`, accepted by both former `poseidon` and `poseidon_fs`.
+        let input1 = read_field_element_le::<Fr>(&[1])?;
+        let input2 = read_field_element_le::<Fr>(&[2])?;
+        let output = read_field_element_le::<Fr>(&[
+            251, 230, 185, 64, 12, 136, 124, 164, 37, 71, 120, 65, 234, 225, 30, 7, 157, 148, 169,
+            225, 186, 183, 76, 63, 231, 241, 40, 189, 50, 55, 145, 23,
+        ])?;
+        let circuit = PoseidonCircuit::<Bn256, typenum::U2, C> {
+            inputs: vec![Some(input1), Some(input2)]
+                .into_iter()
+                .collect::<GenericArray<_, _>>(),
+            output: Some(output),
+            _config: std::marker::PhantomData,
+        };
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        circuit.synthesize(&mut cs)?;
+        assert!(cs.is_satisfied());
+
+        let gates = PoseidonCircuit::<Bn256, typenum::U2, C>::declare_used_gates()?;
+        assert_eq!(gates.len(), num_gates);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_poseidon_configs() -> Result<(), Box<dyn std::error::Error>> {
+        // The former `poseidon` circuit declared only the main gate.
+        check_poseidon_config::<PoseidonFrConfig>(1)?;
+        // The former `poseidon_fs` circuit also declared the two-bit decomposition gate.
+        check_poseidon_config::<PoseidonFsConfig>(2)?;
+
+        Ok(())
+    }
+
     #[test]
     fn test_fr_poseidon_circuit_case2() -> Result<(), Box<dyn std::error::Error>> {
         let crs = open_crs_for_log2_of_size(12);
@@ -217,6 +429,113 @@ mod poseidon_api_tests {
         Ok(())
     }
 
+    #[test]
+    fn test_fr_poseidon_circuit_input_malformed() -> Result<(), Box<dyn std::error::Error>> {
+        let input1 = read_field_element_le::<Fr>(&[1]).unwrap();
+        let input2 = read_field_element_le::<Fr>(&[2]).unwrap();
+        let circuit_input = make_test_input(vec![input1, input2]);
+        let bytes = circuit_input.to_bytes();
+        assert_eq!(
+            PoseidonCircuitInput::<typenum::U2>::from_bytes(&bytes)?,
+            circuit_input
+        );
+
+        let read_modified = |f: &dyn Fn(&mut Vec<u8>)| {
+            let mut bytes = bytes.clone();
+            f(&mut bytes);
+            PoseidonCircuitInput::<typenum::U2>::from_bytes(&bytes).unwrap_err()
+        };
+
+        let err = read_modified(&|bytes| bytes[0] = b'X');
+        assert!(matches!(err, FormatError::BadMagic(_)), "{}", err);
+        let err = read_modified(&|bytes| bytes[4] = 1);
+        assert!(matches!(err, FormatError::UnsupportedVersion(1)), "{}", err);
+        let err = read_modified(&|bytes| bytes[6] = CircuitKind::IpaFr as u8);
+        assert!(
+            matches!(err, FormatError::UnexpectedCircuitKind { .. }),
+            "{}",
+            err
+        );
+        let err = read_modified(&|bytes| bytes[6] = 0xff);
+        assert!(
+            matches!(err, FormatError::UnknownCircuitKind(0xff)),
+            "{}",
+            err
+        );
+        let err = read_modified(&|bytes| bytes[8] = PointEncoding::Compressed as u8);
+        assert!(
+            matches!(err, FormatError::UnsupportedPointEncoding { .. }),
+            "{}",
+            err
+        );
+        let err = read_modified(&|bytes| bytes[9] = 4);
+        assert!(matches!(err, FormatError::InvalidDomainSize(4)), "{}", err);
+        let err = read_modified(&|bytes| bytes.truncate(bytes.len() - 1));
+        assert!(matches!(err, FormatError::Truncated), "{}", err);
+        let err = read_modified(&|bytes| bytes.push(0));
+        assert!(
+            matches!(err, FormatError::TrailingBytes { section: None }),
+            "{}",
+            err
+        );
+
+        // The output is not less than the modulus.
+        let err = read_modified(&|bytes| {
+            let len = bytes.len();
+            bytes[(len - 32)..].copy_from_slice(&[0xff; 32]);
+        });
+        assert!(
+            matches!(err, FormatError::InvalidFieldElement { section: "output" }),
+            "{}",
+            err
+        );
+
+        // Any input of another arity is rejected.
+        assert!(matches!(
+            PoseidonCircuitInput::<typenum::U4>::from_bytes(&bytes),
+            Err(FormatError::InvalidDomainSize(3))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fr_poseidon_circuit_errors() -> Result<(), Box<dyn std::error::Error>> {
+        let input1 = read_field_element_le::<Fr>(&[1]).unwrap();
+        let input2 = read_field_element_le::<Fr>(&[2]).unwrap();
+        let circuit_input = make_test_input(vec![input1, input2]);
+
+        let small_crs = Crs::<Bn256, CrsForMonomialForm>::dummy_crs(16);
+        match circuit_input.create_plonk_proof(small_crs) {
+            Err(CircuitError::CrsTooSmall {
+                required,
+                available,
+            }) => assert!(available == 16 && required > available),
+            _ => panic!("expected `CrsTooSmall`"),
+        }
+
+        let mut wrong_shape = circuit_input.clone();
+        wrong_shape.inputs.push(input1);
+        let crs = open_crs_for_log2_of_size(14);
+        assert!(matches!(
+            wrong_shape.create_plonk_proof(crs),
+            Err(CircuitError::InvalidInputShape(_))
+        ));
+
+        let mut wrong_output = circuit_input;
+        wrong_output.output = input1;
+        let crs = open_crs_for_log2_of_size(14);
+        match wrong_output.create_plonk_proof(crs) {
+            Err(CircuitError::UnsatisfiedGate(gate)) => assert_eq!(
+                gate.label.as_deref(),
+                Some("poseidon::PoseidonCircuit / output equality")
+            ),
+            _ => panic!("expected `UnsatisfiedGate`"),
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_fr_poseidon_circuit_input_serde_json() -> Result<(), Box<dyn std::error::Error>> {
         let input1 = read_field_element_le::<Fr>(&[1]).unwrap();
@@ -258,9 +577,9 @@ mod poseidon_api_tests {
     }
 }
 
-pub struct VkAndProof<N: ArrayLength<Option<Fr>>>(
-    pub VerificationKey<Bn256, PoseidonCircuit<Bn256, N>>,
-    pub Proof<Bn256, PoseidonCircuit<Bn256, N>>,
+pub struct VkAndProof<N: ArrayLength<Option<Fr>>, C = PoseidonFrConfig>(
+    pub VerificationKey<Bn256, PoseidonCircuit<Bn256, N, C>>,
+    pub Proof<Bn256, PoseidonCircuit<Bn256, N, C>>,
 );
 
 impl<N: ArrayLength<Option<Fr>>> PoseidonCircuitInput<N> {
@@ -289,61 +608,75 @@ impl<N: ArrayLength<Option<Fr>>> PoseidonCircuitInput<N> {
     pub fn create_plonk_proof(
         &self,
         crs: Crs<Bn256, CrsForMonomialForm>,
-    ) -> Result<VkAndProof<N>, SynthesisError> {
+    ) -> Result<VkAndProof<N>, CircuitError> {
+        self.create_plonk_proof_with_config::<PoseidonFrConfig>(crs)
+    }
+
+    /// Create a proof of `PoseidonCircuit` whose declared gates and S-boxes are decided by `C`.
+    pub fn create_plonk_proof_with_config<C: PoseidonConfig>(
+        &self,
+        crs: Crs<Bn256, CrsForMonomialForm>,
+    ) -> Result<VkAndProof<N, C>, CircuitError> {
+        let _span = tracing::info_span!("create_plonk_proof", circuit = "poseidon").entered();
+        if self.inputs.len() != N::to_usize() {
+            return Err(CircuitError::InvalidInputShape(format!(
+                "the number of inputs should be {}, but {}",
+                N::to_usize(),
+                self.inputs.len()
+            )));
+        }
+
         let dummy_inputs = self
             .inputs
             .iter()
             .map(|&_| None)
             .collect::<GenericArray<_, _>>();
-        let dummy_circuit = PoseidonCircuit::<Bn256> {
+        let dummy_circuit = PoseidonCircuit::<Bn256, N, C> {
             inputs: dummy_inputs,
             output: None,
+            _config: std::marker::PhantomData,
         };
 
-        let circuit = PoseidonCircuit::<Bn256, N> {
+        let circuit = PoseidonCircuit::<Bn256, N, C> {
             inputs: self
                 .inputs
                 .iter()
                 .map(|&x| Some(x))
                 .collect::<GenericArray<_, _>>(),
             output: Some(self.output),
+            _config: std::marker::PhantomData,
         };
 
+        let span = tracing::info_span!("setup").entered();
         let mut dummy_assembly =
             SetupAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        dummy_circuit
-            .synthesize(&mut dummy_assembly)
-            .expect("must synthesize");
+        dummy_circuit.synthesize(&mut dummy_assembly)?;
         dummy_assembly.finalize();
 
         let worker = franklin_crypto::bellman::worker::Worker::new();
-        let setup = dummy_assembly.create_setup::<PoseidonCircuit<Bn256, N>>(&worker)?;
+        let setup = dummy_assembly.create_setup::<PoseidonCircuit<Bn256, N, C>>(&worker)?;
+        check_crs_size(&crs, setup.n)?;
 
-        let vk =
-            VerificationKey::<Bn256, PoseidonCircuit<Bn256, N>>::from_setup(&setup, &worker, &crs)?;
+        let vk = VerificationKey::<Bn256, PoseidonCircuit<Bn256, N, C>>::from_setup(
+            &setup, &worker, &crs,
+        )?;
 
-        println!("Checking if satisfied");
-        let mut trivial_assembly =
-            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        circuit
-            .synthesize(&mut trivial_assembly)
-            .expect("must synthesize");
-        if !trivial_assembly.is_satisfied() {
-            return Err(SynthesisError::Unsatisfiable);
-        }
+        drop(span);
 
-        println!("prove");
+        let span = tracing::info_span!("prove").entered();
+        check_satisfied(&circuit)?;
 
         let mut assembly =
             ProvingAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
-        circuit.synthesize(&mut assembly).expect("must synthesize");
+        circuit.synthesize(&mut assembly)?;
         assembly.finalize();
 
         // TODO: Is this correct?
         let proof = assembly
-    .create_proof::<PoseidonCircuit<Bn256, N>, RollingKeccakTranscript<<Bn256 as ScalarEngine>::Fr>>(
+    .create_proof::<PoseidonCircuit<Bn256, N, C>, RollingKeccakTranscript<<Bn256 as ScalarEngine>::Fr>>(
       &worker, &setup, &crs, None,
     )?;
+        drop(span);
 
         assert_eq!(
             proof.inputs,
@@ -356,23 +689,30 @@ impl<N: ArrayLength<Option<Fr>>> PoseidonCircuitInput<N> {
         Ok(result)
     }
 
-    /// `[width, input[0], ..., inputs[t - 2], output]` -> `CircuitInput`
-    pub fn read_from<R: Read>(reader: &mut R) -> anyhow::Result<Self> {
-        let width = reader.read_u8()?;
-        assert_eq!(
-            width as usize,
-            N::to_usize() + 1,
-            "invalid length of inputs: expected {}, but {}",
-            N::to_usize(),
-            width - 1
-        );
+    /// Read an input framed as `header(Poseidon, Bn256, width) | [inputs] | [output]`.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, FormatError> {
+        let mut frame = FrameReader::new(reader, CircuitKind::Poseidon, Curve::Bn256, 2)?;
+        let width = frame.header().domain_size;
+        if width != N::to_u64() + 1 {
+            return Err(FormatError::InvalidDomainSize(width));
+        }
 
-        let mut inputs = vec![];
-        for _ in 1..width {
-            let input = read_field_element_le_from(reader)?;
-            inputs.push(input);
+        let mut section = frame.read_section("inputs")?;
+        let inputs = section.read_field_elements()?;
+        section.finish()?;
+        if inputs.len() != N::to_usize() {
+            return Err(FormatError::LengthMismatch {
+                section: "inputs",
+                expected: N::to_usize(),
+                found: inputs.len(),
+            });
         }
-        let output = read_field_element_le_from(reader)?;
+
+        let mut section = frame.read_section("output")?;
+        let output = section.read_field_element()?;
+        section.finish()?;
+        frame.finish()?;
+
         let circuit_input = Self {
             inputs,
             output,
@@ -382,32 +722,60 @@ impl<N: ArrayLength<Option<Fr>>> PoseidonCircuitInput<N> {
         Ok(circuit_input)
     }
 
-    /// `CircuitInput` -> `[t, input[0], ..., inputs[t - 2], output]`
+    /// Write the input framed as `header(Poseidon, Bn256, width) | [inputs] | [output]`.
     pub fn write_into<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        let width = N::to_usize() + 1;
-        assert!(width < 256);
+        if self.inputs.len() != N::to_usize() {
+            return Err(invalid_input(format!(
+                "invalid length of inputs: expected {}, but {}",
+                N::to_usize(),
+                self.inputs.len()
+            )));
+        }
+
+        let header = Header {
+            kind: CircuitKind::Poseidon,
+            curve: Curve::Bn256,
+            point_encoding: PointEncoding::Uncompressed,
+            domain_size: N::to_u64() + 1,
+        };
+        let mut frame = FrameWriter::new(writer, header, 2)?;
 
-        writer.write_u8(width as u8)?;
-        for i in 0..N::to_usize() {
-            write_field_element_le_into(self.inputs[i], writer)?;
+        let mut inputs = vec![];
+        for &input in self.inputs.iter() {
+            write_field_element_le_into(input, &mut inputs)?;
         }
-        write_field_element_le_into(self.output, writer)?;
+        frame.write_section(&inputs)?;
+
+        let mut output = vec![];
+        write_field_element_le_into(self.output, &mut output)?;
+        frame.write_section(&output)?;
 
         Ok(())
     }
 
-    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
-        let json_str = read_to_string(path)?;
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_into(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+        Self::read_from(&mut std::io::Cursor::new(bytes))
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, FormatError> {
+        let bytes = std::fs::read(path)?;
 
-        Self::from_str(&json_str)
+        Self::from_bytes(&bytes)
     }
 }
 
 impl<N: ArrayLength<Option<Fr>>> FromStr for PoseidonCircuitInput<N> {
-    type Err = anyhow::Error;
+    type Err = FormatError;
 
-    fn from_str(s: &str) -> anyhow::Result<Self> {
-        Self::read_from(&mut s.as_bytes())
+    fn from_str(s: &str) -> Result<Self, FormatError> {
+        Self::from_bytes(s.as_bytes())
     }
 }
 
@@ -0,0 +1,463 @@
+use franklin_crypto::bellman::pairing::bn256::{Bn256, Fq, Fq2, Fr, G1Affine};
+use franklin_crypto::bellman::plonk::better_better_cs::cs::{
+    Circuit, ConstraintSystem, Gate, GateInternal, Width4MainGateWithDNext,
+};
+use franklin_crypto::bellman::plonk::better_better_cs::proof::Proof;
+use franklin_crypto::bellman::plonk::better_better_cs::setup::VerificationKey;
+use franklin_crypto::bellman::plonk::domains::Domain;
+use franklin_crypto::bellman::{CurveAffine, Field, PrimeField, SynthesisError};
+use franklin_crypto::plonk::circuit::bigint::range_constraint_gate::TwoBitDecompositionRangecheckCustomGate;
+
+use crate::api::utils::g1_to_hex_pair;
+use crate::circuit::utils::{field_element_to_hex, write_field_element_be_into};
+use crate::error::CircuitError;
+
+const VERIFIER_TEMPLATE: &str = include_str!("../../templates/verifier.sol");
+
+/// The verifier core which the rendered contracts import from `PLONK_CORE_FILE_NAME`.
+pub const PLONK_CORE_SOURCE: &str = include_str!("../../templates/PlonkCore.sol");
+
+/// The path of the verifier core relative to the rendered contract.
+pub const PLONK_CORE_FILE_NAME: &str = "PlonkCore.sol";
+
+/// The selector of `verify_serialized_proof(uint256[],uint256[])`.
+pub const VERIFY_SELECTOR: [u8; 4] = [0x33, 0x0d, 0xeb, 0x9f];
+
+/// The shape of the keys which the verifier core accepts:
+/// `Width4MainGateWithDNext` and the range check gate without lookup tables.
+const STATE_WIDTH: usize = 4;
+const NUM_GATE_SETUP_COMMITMENTS: usize = 7;
+const NUM_GATE_SELECTOR_COMMITMENTS: usize = 2;
+
+/// A 256-bit big-endian word of the EVM.
+pub type Word = [u8; 32];
+
+/// The verification keys and the proofs are encoded in the same way for all the circuits,
+/// so those of any circuit can be read as the ones of `AnyCircuit`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AnyCircuit;
+
+impl Circuit<Bn256> for AnyCircuit {
+    type MainGate = Width4MainGateWithDNext;
+
+    /// The gates which the verifier core supports.
+    fn declare_used_gates() -> Result<Vec<Box<dyn GateInternal<Bn256>>>, SynthesisError> {
+        Ok(vec![
+            Self::MainGate::default().into_internal(),
+            TwoBitDecompositionRangecheckCustomGate::default().into_internal(),
+        ])
+    }
+
+    fn synthesize<CS: ConstraintSystem<Bn256>>(&self, _cs: &mut CS) -> Result<(), SynthesisError> {
+        Ok(())
+    }
+}
+
+/// Render the Solidity verifier contract `contract_name` for `vk` of the circuit `circuit_name`.
+/// The contract inherits the verifier core `Plonk4VerifierWithAccessToDNext` from `PlonkCore.sol`,
+/// which must be placed next to it with the contents of `PLONK_CORE_SOURCE`.
+///
+/// The verifier core supports only `Width4MainGateWithDNext` with the range check gate,
+/// i.e. the keys with 2 gate selectors and no lookup tables.
+/// The other gates are rejected if they are declared by `C`, which is unknown for `AnyCircuit`.
+pub fn render_verifier<C: Circuit<Bn256>>(
+    vk: &VerificationKey<Bn256, C>,
+    circuit_name: &str,
+    contract_name: &str,
+) -> Result<String, CircuitError> {
+    check_verification_key(vk).map_err(|reason| CircuitError::UnsupportedCircuit {
+        circuit: circuit_name.to_string(),
+        reason,
+    })?;
+
+    let domain_size = vk.n + 1;
+    let omega = Domain::<Fr>::new_for_size(domain_size as u64)?.generator;
+
+    let mut lines = vec![
+        format!("vk.domain_size = {};", domain_size),
+        format!("vk.num_inputs = {};", vk.num_inputs),
+        format!(
+            "vk.omega = PairingsBn254.new_fr({});",
+            field_element_to_hex(omega)
+        ),
+    ];
+    for (name, points) in [
+        ("gate_setup_commitments", &vk.gate_setup_commitments),
+        ("gate_selector_commitments", &vk.gate_selectors_commitments),
+        ("copy_permutation_commitments", &vk.permutation_commitments),
+    ] {
+        for (i, point) in points.iter().enumerate() {
+            let (x, y) = g1_to_hex_pair(point);
+            lines.push(format!(
+                "vk.{}[{}] = PairingsBn254.new_g1({}, {});",
+                name, i, x, y
+            ));
+        }
+    }
+    for (i, non_residue) in vk.non_residues.iter().enumerate() {
+        lines.push(format!(
+            "vk.copy_permutation_non_residues[{}] = PairingsBn254.new_fr({});",
+            i,
+            field_element_to_hex(*non_residue)
+        ));
+    }
+
+    // The coefficients of `Fq2` are in the order of `(c1, c0)` for the pairing precompile.
+    let (x, y) = vk.g2_elements[1].into_xy_unchecked();
+    let ((x1, x0), (y1, y0)) = (fq2_to_hex_pair(&x), fq2_to_hex_pair(&y));
+    lines.push(format!(
+        "vk.g2_x = PairingsBn254.new_g2([{}, {}], [{}, {}]);",
+        x1, x0, y1, y0
+    ));
+
+    let verification_key = lines
+        .iter()
+        .map(|line| format!("        {}", line))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(VERIFIER_TEMPLATE
+        .replace("{{contract_name}}", contract_name)
+        .replace("{{num_inputs}}", &vk.num_inputs.to_string())
+        .replace("{{verification_key}}", &verification_key))
+}
+
+/// Returns the reason why the verifier core cannot check the proofs for `vk`.
+fn check_verification_key<C: Circuit<Bn256>>(vk: &VerificationKey<Bn256, C>) -> Result<(), String> {
+    if !(vk.n + 1).is_power_of_two() {
+        return Err(format!(
+            "the domain size should be a power of two, but {}",
+            vk.n + 1
+        ));
+    }
+
+    // The key does not record its gates, so those declared by `C` are checked if it is known.
+    let gate_names = C::declare_used_gates()
+        .map_err(|err| err.to_string())?
+        .iter()
+        .map(|gate| gate.name())
+        .collect::<Vec<_>>();
+    let supported_gate_names = AnyCircuit::declare_used_gates()
+        .map_err(|err| err.to_string())?
+        .iter()
+        .map(|gate| gate.name())
+        .collect::<Vec<_>>();
+    if gate_names.len() > 1 && gate_names != supported_gate_names {
+        return Err(format!(
+            "the verifier supports the gates {:?}, but the circuit declares {:?}",
+            supported_gate_names, gate_names
+        ));
+    }
+
+    if vk.state_width != STATE_WIDTH
+        || vk.num_witness_polys != 0
+        || vk.gate_setup_commitments.len() != NUM_GATE_SETUP_COMMITMENTS
+        || vk.gate_selectors_commitments.len() != NUM_GATE_SELECTOR_COMMITMENTS
+        || vk.permutation_commitments.len() != STATE_WIDTH
+        || vk.non_residues.len() != STATE_WIDTH - 1
+    {
+        return Err(format!(
+            "the verifier supports the main gate of width {} with the range check gate, \
+            but the key has {} setup and {} selector commitments of width {}",
+            STATE_WIDTH,
+            vk.gate_setup_commitments.len(),
+            vk.gate_selectors_commitments.len(),
+            vk.state_width
+        ));
+    }
+
+    if vk.total_lookup_entries_length != 0 {
+        return Err("the verifier does not support lookup tables".to_string());
+    }
+
+    Ok(())
+}
+
+/// Encode an `Fq2` element as the hex strings of `(c1, c0)`.
+fn fq2_to_hex_pair(value: &Fq2) -> (String, String) {
+    (
+        field_element_to_hex(value.c1),
+        field_element_to_hex(value.c0),
+    )
+}
+
+fn to_word<F: PrimeField>(value: F) -> Word {
+    let mut word = [0u8; 32];
+    write_field_element_be_into(value, &mut word.as_mut()).unwrap();
+
+    word
+}
+
+/// The point at infinity is encoded as `(0, 0)` as the precompiles do.
+fn push_g1(words: &mut Vec<Word>, point: &G1Affine) {
+    let (x, y) = if point.is_zero() {
+        (Fq::zero(), Fq::zero())
+    } else {
+        point.into_xy_unchecked()
+    };
+    words.push(to_word(x));
+    words.push(to_word(y));
+}
+
+/// Split `proof` into the public inputs and the words in the order in which `deserialize_proof` reads them.
+pub fn serialize_proof<C: Circuit<Bn256>>(
+    proof: &Proof<Bn256, C>,
+) -> Result<(Vec<Word>, Vec<Word>), CircuitError> {
+    if !proof.witness_polys_commitments.is_empty()
+        || !proof.gate_setup_openings_at_z.is_empty()
+        || proof.lookup_s_poly_commitment.is_some()
+        || proof.lookup_grand_product_commitment.is_some()
+    {
+        return Err(CircuitError::InvalidInputShape(
+            "the verifier does not support witness polynomials, setup openings or lookup tables"
+                .to_string(),
+        ));
+    }
+
+    let public_inputs = proof.inputs.iter().map(|input| to_word(*input)).collect();
+
+    let mut words = vec![];
+    for commitment in proof.state_polys_commitments.iter() {
+        push_g1(&mut words, commitment);
+    }
+    push_g1(&mut words, &proof.copy_permutation_grand_product_commitment);
+    for commitment in proof.quotient_poly_parts_commitments.iter() {
+        push_g1(&mut words, commitment);
+    }
+    words.extend(proof.state_polys_openings_at_z.iter().map(|v| to_word(*v)));
+    words.extend(
+        proof
+            .state_polys_openings_at_dilations
+            .iter()
+            .map(|(_, _, v)| to_word(*v)),
+    );
+    words.extend(
+        proof
+            .gate_selectors_openings_at_z
+            .iter()
+            .map(|(_, v)| to_word(*v)),
+    );
+    words.extend(
+        proof
+            .copy_permutation_polys_openings_at_z
+            .iter()
+            .map(|v| to_word(*v)),
+    );
+    words.push(to_word(
+        proof.copy_permutation_grand_product_opening_at_z_omega,
+    ));
+    words.push(to_word(proof.quotient_poly_opening_at_z));
+    words.push(to_word(proof.linearization_poly_opening_at_z));
+    push_g1(&mut words, &proof.opening_proof_at_z);
+    push_g1(&mut words, &proof.opening_proof_at_z_omega);
+
+    Ok((public_inputs, words))
+}
+
+/// ABI-encode the call of `verify_serialized_proof(public_inputs, serialized_proof)` for `proof`.
+pub fn encode_calldata<C: Circuit<Bn256>>(
+    proof: &Proof<Bn256, C>,
+) -> Result<Vec<u8>, CircuitError> {
+    let (public_inputs, serialized_proof) = serialize_proof(proof)?;
+
+    let head_size = 2 * 32;
+    let first_offset = head_size;
+    let second_offset = first_offset + 32 * (1 + public_inputs.len());
+
+    let mut calldata = VERIFY_SELECTOR.to_vec();
+    calldata.extend(usize_to_word(first_offset));
+    calldata.extend(usize_to_word(second_offset));
+    for array in [public_inputs, serialized_proof] {
+        calldata.extend(usize_to_word(array.len()));
+        for word in array {
+            calldata.extend(word);
+        }
+    }
+
+    Ok(calldata)
+}
+
+fn usize_to_word(value: usize) -> Word {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+
+    word
+}
+
+#[cfg(test)]
+mod solidity_api_tests {
+    use std::error::Error;
+    use std::process::Command;
+
+    use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr};
+    use franklin_crypto::bellman::plonk::better_better_cs::setup::VerificationKey;
+    use franklin_crypto::bellman::Field;
+    use verkle_tree::ipa_fr::utils::read_field_element_le;
+
+    use crate::api::poseidon::input::{make_test_input, VkAndProof};
+    use crate::api::utils::open_crs_for_log2_of_size;
+    use crate::circuit::poseidon::{CustomGateSbox, PoseidonFrConfig, PoseidonFsConfig};
+    use crate::circuit::utils::write_field_element_be_into;
+    use crate::error::CircuitError;
+
+    use super::{
+        encode_calldata, render_verifier, serialize_proof, AnyCircuit, PLONK_CORE_FILE_NAME,
+        PLONK_CORE_SOURCE, VERIFY_SELECTOR,
+    };
+
+    const CIRCUIT_NAME: &str = "poseidon";
+    const CONTRACT_NAME: &str = "PoseidonVerifier";
+
+    /// Returns the command given by the environment variable `env` or `default` if it can be run.
+    fn find_tool(env: &str, default: &str) -> Option<String> {
+        let tool = std::env::var(env).unwrap_or_else(|_| default.to_string());
+        let output = Command::new(&tool).arg("--version").output().ok()?;
+
+        output.status.success().then_some(tool)
+    }
+
+    /// Compile `contract` next to the verifier core with `solc`
+    /// and return the runtime bytecode of `contract_name`.
+    fn compile_verifier(
+        solc: &str,
+        contract: &str,
+        contract_name: &str,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let file_name = format!("{}.sol", contract_name);
+        std::fs::write(dir.path().join(&file_name), contract)?;
+        std::fs::write(dir.path().join(PLONK_CORE_FILE_NAME), PLONK_CORE_SOURCE)?;
+
+        let output = Command::new(solc)
+            .current_dir(dir.path())
+            .args(["--optimize", "--combined-json", "bin-runtime", &file_name])
+            .output()
+            .map_err(|err| format!("failed to run {}: {}", solc, err))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).into());
+        }
+
+        let compiled: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let suffix = format!(":{}", contract_name);
+        let bin = compiled["contracts"]
+            .as_object()
+            .and_then(|contracts| contracts.iter().find(|(name, _)| name.ends_with(&suffix)))
+            .and_then(|(_, contract)| contract["bin-runtime"].as_str())
+            .ok_or("the contract is not compiled")?;
+
+        Ok(hex::decode(bin)?)
+    }
+
+    /// Run `code` with `evm` of go-ethereum and return the output of the call with `calldata`.
+    /// The verification key is embedded in the code, so the contract has no state to deploy.
+    fn call_verifier(evm: &str, code: &[u8], calldata: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let code_path = dir.path().join("code");
+        std::fs::write(&code_path, hex::encode(code))?;
+
+        let output = Command::new(evm)
+            .arg("--codefile")
+            .arg(&code_path)
+            .args(["--input", &hex::encode(calldata), "run"])
+            .output()
+            .map_err(|err| format!("failed to run {}: {}", evm, err))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).into());
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        let result = stdout.trim();
+
+        Ok(hex::decode(result.strip_prefix("0x").unwrap_or(result))?)
+    }
+
+    /// Returns the ABI encoding of `value`.
+    fn encode_bool(value: bool) -> Vec<u8> {
+        let mut word = vec![0u8; 32];
+        word[31] = value as u8;
+
+        word
+    }
+
+    #[test]
+    fn test_poseidon_solidity_export() -> Result<(), Box<dyn Error>> {
+        let crs = open_crs_for_log2_of_size(12);
+        let input1 = read_field_element_le::<Fr>(&[1]).unwrap();
+        let input2 = read_field_element_le::<Fr>(&[2]).unwrap();
+        let circuit_input = make_test_input(vec![input1, input2]);
+        // The verifier core supports the main gate with the range check gate.
+        let VkAndProof(vk, proof) =
+            circuit_input.create_plonk_proof_with_config::<PoseidonFsConfig>(crs)?;
+
+        // The key is read back as the one of any circuit.
+        let mut vk_bytes = vec![];
+        vk.write(&mut vk_bytes)?;
+        let any_vk = VerificationKey::<Bn256, AnyCircuit>::read(vk_bytes.as_slice())?;
+        let contract = render_verifier(&vk, CIRCUIT_NAME, CONTRACT_NAME)?;
+        assert_eq!(
+            render_verifier(&any_vk, CIRCUIT_NAME, CONTRACT_NAME)?,
+            contract
+        );
+        assert!(contract.contains("contract PoseidonVerifier is Plonk4VerifierWithAccessToDNext"));
+        assert!(contract.contains(&format!("vk.domain_size = {};", vk.n + 1)));
+        assert!(contract.contains("vk.num_inputs = 1;"));
+        assert!(!contract.contains("{{"));
+
+        let (public_inputs, serialized_proof) = serialize_proof(&proof)?;
+        let mut output = vec![];
+        write_field_element_be_into(circuit_input.output, &mut output)?;
+        assert_eq!(public_inputs, vec![output.as_slice().try_into()?]);
+
+        // selector | offsets | public inputs | serialized proof
+        let calldata = encode_calldata(&proof)?;
+        assert_eq!(calldata[..4], VERIFY_SELECTOR);
+        assert_eq!(calldata[4 + 31], 0x40);
+        assert_eq!(calldata[4 + 63], 0x80);
+        assert_eq!(calldata[4 + 95], 1);
+        assert_eq!(calldata[(4 + 96)..(4 + 128)], output[..]);
+        assert_eq!(calldata[4 + 159] as usize, serialized_proof.len());
+        assert_eq!(calldata.len(), 4 + 32 * (5 + serialized_proof.len()));
+
+        // The proof is accepted by the contract, but not with another public input.
+        let mut wrong_proof = proof.clone();
+        wrong_proof.inputs[0].add_assign(&Fr::one());
+        let wrong_calldata = encode_calldata(&wrong_proof)?;
+        let (solc, evm) = match (find_tool("SOLC", "solc"), find_tool("EVM", "evm")) {
+            (Some(solc), Some(evm)) => (solc, evm),
+            _ => {
+                eprintln!(
+                    "skipping the EVM check of test_poseidon_solidity_export: \
+                    solc and evm (go-ethereum) are not found in PATH, SOLC or EVM"
+                );
+                return Ok(());
+            }
+        };
+        let code = compile_verifier(&solc, &contract, CONTRACT_NAME)?;
+        assert_eq!(call_verifier(&evm, &code, &calldata)?, encode_bool(true));
+        assert_eq!(
+            call_verifier(&evm, &code, &wrong_calldata)?,
+            encode_bool(false)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solidity_export_rejects_custom_gates() -> Result<(), Box<dyn Error>> {
+        let crs = open_crs_for_log2_of_size(12);
+        let input1 = read_field_element_le::<Fr>(&[1]).unwrap();
+        let input2 = read_field_element_le::<Fr>(&[2]).unwrap();
+        let circuit_input = make_test_input(vec![input1, input2]);
+        // The main gate and the Rescue5 S-box gate have as many selectors as the supported gates.
+        let VkAndProof(vk, _) = circuit_input
+            .create_plonk_proof_with_config::<PoseidonFrConfig<CustomGateSbox>>(crs)?;
+        assert_eq!(vk.gate_selectors_commitments.len(), 2);
+
+        assert!(matches!(
+            render_verifier(&vk, CIRCUIT_NAME, CONTRACT_NAME),
+            Err(CircuitError::UnsupportedCircuit { circuit, .. }) if circuit == CIRCUIT_NAME
+        ));
+
+        Ok(())
+    }
+}
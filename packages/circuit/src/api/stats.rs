@@ -0,0 +1,206 @@
+use franklin_crypto::babyjubjub::{JubjubBn256, JubjubEngine};
+use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr, G1Affine};
+use franklin_crypto::plonk::circuit::bigint::field::RnsParameters;
+use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::aux_data::{
+    AuxData, BN256AuxData,
+};
+use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::without_flag_unchecked::WrapperUnchecked;
+use generic_array::typenum::{
+    U1, U10, U11, U12, U13, U14, U15, U16, U2, U3, U4, U5, U6, U7, U8, U9,
+};
+use generic_array::{ArrayLength, GenericArray};
+use verkle_tree::ipa_fr::config::IpaConfig as IpaFrConfig;
+use verkle_tree::ipa_fr::rns::BaseRnsParameters;
+use verkle_tree::ipa_fr::utils::log2_ceil;
+use verkle_tree::ipa_fs::config::IpaConfig as IpaFsConfig;
+
+use crate::api::format::CircuitKind;
+use crate::circuit::batch_proof_fr::BatchProofCircuit as BatchProofFrCircuit;
+use crate::circuit::batch_proof_fs::BatchProofCircuit as BatchProofFsCircuit;
+use crate::circuit::debug::{profile_gates, GateProfile};
+use crate::circuit::discrete_log::DiscreteLogCircuit;
+use crate::circuit::ipa_fr::circuit::IpaCircuit as IpaFrCircuit;
+use crate::circuit::ipa_fr::proof::OptionIpaProof as OptionIpaFrProof;
+use crate::circuit::ipa_fs::circuit::IpaCircuit as IpaFsCircuit;
+use crate::circuit::poseidon::generator::PoseidonParams;
+use crate::circuit::poseidon::PoseidonCircuit;
+use crate::error::CircuitError;
+
+/// Count the gates of the circuit `kind` in each namespace.
+///
+/// `domain_size` is the arity for `CircuitKind::Poseidon`,
+/// which must be between 1 and `PoseidonParams::MAX_ARITY`,
+/// and is ignored for `CircuitKind::DiscreteLog`.
+pub fn profile_circuit(kind: CircuitKind, domain_size: usize) -> Result<GateProfile, CircuitError> {
+    let uses_domain = kind != CircuitKind::Poseidon && kind != CircuitKind::DiscreteLog;
+    if uses_domain && (domain_size < 2 || !domain_size.is_power_of_two()) {
+        return Err(CircuitError::InvalidInputShape(format!(
+            "the domain size should be a power of two greater than 1, but {}",
+            domain_size
+        )));
+    }
+
+    match kind {
+        CircuitKind::Poseidon => match domain_size {
+            1 => profile_poseidon::<U1>(),
+            2 => profile_poseidon::<U2>(),
+            3 => profile_poseidon::<U3>(),
+            4 => profile_poseidon::<U4>(),
+            5 => profile_poseidon::<U5>(),
+            6 => profile_poseidon::<U6>(),
+            7 => profile_poseidon::<U7>(),
+            8 => profile_poseidon::<U8>(),
+            9 => profile_poseidon::<U9>(),
+            10 => profile_poseidon::<U10>(),
+            11 => profile_poseidon::<U11>(),
+            12 => profile_poseidon::<U12>(),
+            13 => profile_poseidon::<U13>(),
+            14 => profile_poseidon::<U14>(),
+            15 => profile_poseidon::<U15>(),
+            16 => profile_poseidon::<U16>(),
+            _ => Err(CircuitError::InvalidInputShape(format!(
+                "the arity of Poseidon should be between 1 and {}, but {}",
+                PoseidonParams::MAX_ARITY,
+                domain_size
+            ))),
+        },
+        CircuitKind::IpaFr => {
+            let rns_params = BaseRnsParameters::<Bn256>::new_for_field(68, 110, 4);
+            let num_rounds = log2_ceil(domain_size) as usize;
+            let circuit = IpaFrCircuit::<'_, Bn256, WrapperUnchecked<'_, Bn256>, BN256AuxData> {
+                transcript_params: None,
+                commitment: None,
+                proof: OptionIpaFrProof::with_depth(num_rounds),
+                eval_point: None,
+                inner_prod: None,
+                ipa_conf: IpaFrConfig::<G1Affine>::new(domain_size),
+                rns_params: &rns_params,
+                aux_data: BN256AuxData::new(),
+                _wp: std::marker::PhantomData,
+                _transcript: std::marker::PhantomData,
+            };
+
+            profile_gates(&circuit)
+        }
+        CircuitKind::IpaFs => {
+            let jubjub_params = &JubjubBn256::new();
+            let rns_params = make_fs_rns_params();
+            let ipa_conf = IpaFsConfig::<Bn256>::new(domain_size, jubjub_params);
+            let circuit = IpaFsCircuit::<Bn256>::initialize(&ipa_conf, &rns_params);
+
+            profile_gates(&circuit)
+        }
+        CircuitKind::BatchProofFr => {
+            let rns_params = BaseRnsParameters::<Bn256>::new_for_field(68, 110, 4);
+            let num_rounds = log2_ceil(domain_size) as usize;
+            // The same number of queries as `BatchProofCircuit::initialize` of `batch_proof_fs`.
+            let num_queries = num_rounds;
+            let circuit =
+                BatchProofFrCircuit::<'_, Bn256, WrapperUnchecked<'_, Bn256>, BN256AuxData> {
+                    transcript_params: None,
+                    proof: OptionIpaFrProof::with_depth(num_rounds),
+                    d: None,
+                    commitments: vec![None; num_queries],
+                    ys: vec![None; num_queries],
+                    zs: vec![None; num_queries],
+                    ipa_conf: IpaFrConfig::<G1Affine>::new(domain_size),
+                    rns_params: &rns_params,
+                    aux_data: BN256AuxData::new(),
+                    _wp: std::marker::PhantomData,
+                    _transcript: std::marker::PhantomData,
+                };
+
+            profile_gates(&circuit)
+        }
+        CircuitKind::BatchProofFs => {
+            let jubjub_params = &JubjubBn256::new();
+            let rns_params = make_fs_rns_params();
+            let ipa_conf = IpaFsConfig::<Bn256>::new(domain_size, jubjub_params);
+            let circuit = BatchProofFsCircuit::<Bn256>::initialize(&ipa_conf, &rns_params);
+
+            profile_gates(&circuit)
+        }
+        CircuitKind::DiscreteLog => {
+            let jubjub_params = &JubjubBn256::new();
+            let rns_params = make_fs_rns_params();
+            let circuit = DiscreteLogCircuit::<Bn256, BN256AuxData> {
+                base_point: None,
+                coefficient: None,
+                output: None,
+                rns_params: &rns_params,
+                aux_data: BN256AuxData::new(),
+                jubjub_params,
+            };
+
+            profile_gates(&circuit)
+        }
+    }
+}
+
+fn profile_poseidon<N: ArrayLength<Option<Fr>>>() -> Result<GateProfile, CircuitError> {
+    let circuit = PoseidonCircuit::<Bn256, N> {
+        inputs: GenericArray::default(),
+        output: None,
+        _config: std::marker::PhantomData,
+    };
+
+    profile_gates(&circuit)
+}
+
+/// `Bn256::Fs::NUM_BITS` is odd number, so the bit width of the last limb is rounded up
+/// to a multiple of `minimal_multiple` as in the tests of `api::ipa_fs`.
+pub(crate) fn make_fs_rns_params() -> RnsParameters<Bn256, <Bn256 as JubjubEngine>::Fs> {
+    let mut rns_params =
+        RnsParameters::<Bn256, <Bn256 as JubjubEngine>::Fs>::new_for_field(68, 110, 4);
+    let current_bits = rns_params.binary_limbs_bit_widths.last_mut().unwrap();
+    let remainder = *current_bits % rns_params.range_check_info.minimal_multiple;
+    if remainder != 0 {
+        *current_bits += rns_params.range_check_info.minimal_multiple - remainder;
+    }
+
+    rns_params
+}
+
+#[cfg(test)]
+mod stats_api_tests {
+    use crate::api::format::CircuitKind;
+    use crate::error::CircuitError;
+
+    use super::profile_circuit;
+
+    #[test]
+    fn test_poseidon_gate_profile() -> Result<(), Box<dyn std::error::Error>> {
+        let profile = profile_circuit(CircuitKind::Poseidon, 2)?;
+        println!("{}", profile);
+
+        let circuit = profile
+            .counts
+            .iter()
+            .find(|count| count.label == "poseidon::PoseidonCircuit")
+            .expect("the circuit should be labelled");
+        let permutation = profile
+            .counts
+            .iter()
+            .find(|count| count.label == "poseidon")
+            .expect("the permutation should be labelled");
+        assert_eq!(permutation.calls, 1);
+        assert!(permutation.gates > 0 && permutation.gates < circuit.gates);
+        assert_eq!(
+            circuit.gates + profile.unlabelled_gates,
+            profile.num_aux_gates
+        );
+        // Every arity which the parameters support can be profiled.
+        assert!(profile_circuit(CircuitKind::Poseidon, 1).is_ok());
+        assert!(profile_circuit(CircuitKind::Poseidon, 3).is_ok());
+        assert!(matches!(
+            profile_circuit(CircuitKind::Poseidon, 0),
+            Err(CircuitError::InvalidInputShape(_))
+        ));
+        assert!(matches!(
+            profile_circuit(CircuitKind::Poseidon, 17),
+            Err(CircuitError::InvalidInputShape(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -2,16 +2,93 @@ use std::{fs::File, path::Path};
 
 use franklin_crypto::bellman::{
     kate_commitment::{Crs, CrsForMonomialForm},
-    pairing::bn256::Bn256,
+    pairing::bn256::{Bn256, Fq, Fq2, G1Affine, G2Affine},
+    pairing::Engine,
+    CurveAffine, Field,
 };
 
+use crate::circuit::utils::{field_element_from_hex, field_element_to_hex};
+use crate::error::CircuitError;
+
 pub fn open_crs_for_log2_of_size(_log2_n: usize) -> Crs<Bn256, CrsForMonomialForm> {
     let full_path = Path::new("./test_cases").join("crs");
-    println!("Opening {}", full_path.to_string_lossy());
+    let _span = tracing::info_span!("open_crs", path = %full_path.display()).entered();
     let file = File::open(&full_path).unwrap();
     let reader = std::io::BufReader::with_capacity(1 << 24, file);
     let crs = Crs::<Bn256, CrsForMonomialForm>::read(reader).unwrap();
-    println!("Load {}", full_path.to_string_lossy());
 
     crs
 }
+
+/// Encode a point as a pair of hex strings of its coordinates.
+/// The point at infinity is encoded as `(0, 0)`, which is not on the curve.
+pub fn g1_to_hex_pair(point: &G1Affine) -> (String, String) {
+    let (x, y) = if point.is_zero() {
+        (Fq::zero(), Fq::zero())
+    } else {
+        point.into_xy_unchecked()
+    };
+
+    (field_element_to_hex(x), field_element_to_hex(y))
+}
+
+/// Decode a pair of hex strings into a point, checking that it is on the curve.
+pub fn g1_from_hex_pair(pair: &(String, String)) -> anyhow::Result<G1Affine> {
+    let x: Fq = field_element_from_hex(&pair.0)?;
+    let y: Fq = field_element_from_hex(&pair.1)?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::zero());
+    }
+
+    let point = G1Affine::from_xy_checked(x, y)?;
+
+    Ok(point)
+}
+
+/// Encode a point of G2 as the pairs of hex strings of `(c0, c1)` of its coordinates.
+pub fn g2_to_hex_pair(point: &G2Affine) -> ((String, String), (String, String)) {
+    let (x, y) = if point.is_zero() {
+        (Fq2::zero(), Fq2::zero())
+    } else {
+        point.into_xy_unchecked()
+    };
+    let to_hex_pair = |v: Fq2| (field_element_to_hex(v.c0), field_element_to_hex(v.c1));
+
+    (to_hex_pair(x), to_hex_pair(y))
+}
+
+/// Decode the pairs of hex strings into a point of G2, checking that it is on the curve.
+pub fn g2_from_hex_pair(pair: &((String, String), (String, String))) -> anyhow::Result<G2Affine> {
+    let from_hex_pair = |(c0, c1): &(String, String)| -> anyhow::Result<Fq2> {
+        Ok(Fq2 {
+            c0: field_element_from_hex(c0)?,
+            c1: field_element_from_hex(c1)?,
+        })
+    };
+    let x = from_hex_pair(&pair.0)?;
+    let y = from_hex_pair(&pair.1)?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::zero());
+    }
+
+    let point = G2Affine::from_xy_checked(x, y)?;
+
+    Ok(point)
+}
+
+/// Check that `crs` has enough bases to commit to the polynomials of a circuit with `n` gates.
+pub fn check_crs_size<E: Engine>(
+    crs: &Crs<E, CrsForMonomialForm>,
+    n: usize,
+) -> Result<(), CircuitError> {
+    let required = (n + 1).next_power_of_two();
+    let available = crs.g1_bases.len();
+    if available < required {
+        return Err(CircuitError::CrsTooSmall {
+            required,
+            available,
+        });
+    }
+
+    Ok(())
+}
@@ -5,16 +5,26 @@ mod batch_proof_api_tests {
 
     use franklin_crypto::bellman::kate_commitment::{Crs, CrsForMonomialForm};
     use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr, G1Affine};
+    use franklin_crypto::bellman::plonk::better_better_cs::cs::{
+        TrivialAssembly, Width4MainGateWithDNext,
+    };
     use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::without_flag_unchecked::WrapperUnchecked;
+    use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::aux_data::{
+        AuxData, BN256AuxData,
+    };
+    use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::WrappedAffinePoint;
+    use franklin_crypto::plonk::circuit::Width4WithCustomGates;
     use verkle_tree::batch_proof_fr::BatchProof;
     use verkle_tree::bn256_verkle_tree::VerkleTreeWith32BytesKeyValue;
     use verkle_tree::bn256_verkle_tree::proof::VerkleProof;
+    use verkle_tree::bn256_verkle_tree::utils::point_to_field_element;
     use verkle_tree::ipa_fr::config::{IpaConfig};
     use verkle_tree::ipa_fr::rns::BaseRnsParameters;
     use verkle_tree::ipa_fr::transcript::{PoseidonBn256Transcript, Bn256Transcript};
     use verkle_tree::verkle_tree::witness::Elements;
 
     use crate::api::batch_proof_fr::input::{BatchProofCircuitInput, VkAndProof};
+    use crate::circuit::ipa_fr::utils::commitment_to_scalar;
 
     const CIRCUIT_NAME: &str = "verkle_tree";
 
@@ -130,4 +140,40 @@ mod batch_proof_api_tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_commitment_to_scalar() -> Result<(), Box<dyn std::error::Error>> {
+        let domain_size = 4;
+        let rns_params = &BaseRnsParameters::<Bn256>::new_for_field(68, 110, 4);
+        let aux_data = BN256AuxData::new();
+
+        let committer = IpaConfig::new(domain_size);
+        let mut tree = VerkleTreeWith32BytesKeyValue::new(committer);
+        let mut key = [0u8; 32];
+        key[0] = 1;
+        let mut value = [0u8; 32];
+        value[0] = 27;
+        tree.insert(key, value);
+        let mut key = [0u8; 32];
+        key[0] = 1;
+        key[1] = 3;
+        let mut value = [0u8; 32];
+        value[0] = 85;
+        tree.insert(key, value);
+        tree.compute_digest().unwrap();
+        let (proof, _) = VerkleProof::create(&mut tree, &[key]).unwrap();
+
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        for commitment in proof.commitments {
+            let expected = point_to_field_element(&commitment)?;
+            let wrapped_commitment =
+                WrapperUnchecked::alloc(&mut cs, Some(commitment), rns_params, &aux_data)?;
+            let scalar = commitment_to_scalar(&mut cs, &wrapped_commitment)?;
+            assert_eq!(scalar.get_value(), Some(expected));
+        }
+        assert!(cs.is_satisfied());
+
+        Ok(())
+    }
 }
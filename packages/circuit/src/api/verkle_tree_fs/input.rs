@@ -8,17 +8,25 @@ mod batch_proof_api_tests {
         bellman::{
             pairing::bn256::{Bn256, Fr},
             plonk::{
-                better_better_cs::verifier::verify,
+                better_better_cs::{
+                    cs::{TrivialAssembly, Width4MainGateWithDNext},
+                    verifier::verify,
+                },
                 commitments::transcript::keccak_transcript::RollingKeccakTranscript,
             },
+            BitIterator, Field, PrimeField, PrimeFieldRepr,
         },
         plonk::circuit::{
             bigint::field::RnsParameters,
+            boolean::{AllocatedBit, Boolean},
             verifier_circuit::affine_point_wrapper::without_flag_unchecked::WrapperUnchecked,
+            Width4WithCustomGates,
         },
     };
     use verkle_tree::{
-        bn256_verkle_tree_fs::{proof::VerkleProof, VerkleTreeWith32BytesKeyValue},
+        bn256_verkle_tree_fs::{
+            proof::VerkleProof, utils::point_to_field_element, VerkleTreeWith32BytesKeyValue,
+        },
         ipa_fs::{
             config::IpaConfig,
             transcript::{Bn256Transcript, PoseidonBn256Transcript},
@@ -29,6 +37,10 @@ mod batch_proof_api_tests {
     use crate::api::{
         batch_proof_fs::input::BatchProofCircuitInput, utils::open_crs_for_log2_of_size,
     };
+    use crate::circuit::{
+        ipa_fs::utils::commitment_to_scalar,
+        num::{allocate_edwards_point, enforce_bits_in_field},
+    };
 
     const CIRCUIT_NAME: &str = "verkle_tree_fs";
 
@@ -121,4 +133,73 @@ mod batch_proof_api_tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_commitment_to_scalar() -> Result<(), Box<dyn std::error::Error>> {
+        let jubjub_params = &JubjubBn256::new();
+        let mut rns_params =
+            RnsParameters::<Bn256, <Bn256 as JubjubEngine>::Fs>::new_for_field(68, 110, 4);
+        let current_bits = rns_params.binary_limbs_bit_widths.last_mut().unwrap();
+        let remainder = *current_bits % rns_params.range_check_info.minimal_multiple;
+        if remainder != 0 {
+            *current_bits += rns_params.range_check_info.minimal_multiple - remainder;
+        }
+
+        let domain_size = 4;
+        let ipa_conf = &IpaConfig::new(domain_size, jubjub_params);
+        let mut tree = VerkleTreeWith32BytesKeyValue::new(ipa_conf);
+        let mut key1 = [0u8; 32];
+        key1[0] = 2;
+        key1[31] = 3;
+        let mut value1 = [0u8; 32];
+        value1[0] = 3;
+        tree.insert(key1, value1);
+        let mut key2 = [0u8; 32];
+        key2[0] = 1;
+        key2[1] = 3;
+        let mut value2 = [0u8; 32];
+        value2[0] = 2;
+        tree.insert(key2, value2);
+        tree.compute_digest().unwrap();
+        let transcript = PoseidonBn256Transcript::with_bytes(b"verkle_tree");
+        let (verkle_proof, _) =
+            VerkleProof::create(&mut tree, &[key1], transcript.into_params()).unwrap();
+
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        for commitment in verkle_proof.commitments {
+            let expected = point_to_field_element(&commitment)?;
+            let allocated_commitment =
+                allocate_edwards_point(&mut cs, &Some(commitment), jubjub_params)?;
+            let scalar = commitment_to_scalar(&mut cs, &allocated_commitment, &rns_params)?;
+            assert_eq!(scalar.get_field_value(), Some(expected));
+        }
+        assert!(cs.is_satisfied());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commitment_to_scalar_rejects_non_canonical_bits(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // `1 + p` also fits in the bits of an x-coordinate, which would give another scalar.
+        let mut raw_non_canonical = Fr::char();
+        raw_non_canonical.add_nocarry(&1u64.into());
+        for (raw_value, is_canonical) in [(Fr::one().into_repr(), true), (raw_non_canonical, false)]
+        {
+            let mut cs =
+                TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+            let bits = BitIterator::new(raw_value)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev()
+                .take(Fr::NUM_BITS as usize)
+                .map(|bit| AllocatedBit::alloc(&mut cs, Some(bit)).map(Boolean::from))
+                .collect::<Result<Vec<_>, _>>()?;
+            enforce_bits_in_field::<Bn256, _, Fr>(&mut cs, &bits)?;
+            assert_eq!(cs.is_satisfied(), is_canonical);
+        }
+
+        Ok(())
+    }
 }
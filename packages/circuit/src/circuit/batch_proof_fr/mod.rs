@@ -11,11 +11,25 @@ use verkle_tree::ipa_fr::config::IpaConfig;
 use verkle_tree::ipa_fr::rns::BaseRnsParameters;
 use verkle_tree::ipa_fr::utils::read_field_element_le;
 
+use crate::circuit::debug::enter_namespace;
+use crate::circuit::transcript::UnlabeledTranscript;
+use crate::error::CircuitError;
+
 use super::ipa_fr::circuit::IpaCircuit;
 use super::ipa_fr::proof::OptionIpaProof;
 use super::ipa_fr::transcript::{Transcript, WrappedTranscript};
 
-pub struct BatchProofCircuit<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> {
+/// verkle-tree commits no labels to its transcript, so its proofs are checked with this circuit.
+pub type UnlabeledBatchProofCircuit<'a, E, WP, AD> =
+    BatchProofCircuit<'a, E, WP, AD, UnlabeledTranscript<WrappedTranscript<E>>>;
+
+pub struct BatchProofCircuit<
+    'a,
+    E: Engine,
+    WP: WrappedAffinePoint<'a, E>,
+    AD: AuxData<E>,
+    T = WrappedTranscript<E>,
+> {
     pub transcript_params: Option<E::Fr>,
     pub proof: OptionIpaProof<E::G1Affine>,
     pub d: Option<E::G1Affine>,
@@ -26,61 +40,67 @@ pub struct BatchProofCircuit<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: A
     pub rns_params: &'a BaseRnsParameters<E>,
     pub aux_data: AD,
     pub _wp: std::marker::PhantomData<WP>,
+    pub _transcript: std::marker::PhantomData<T>,
 }
 
-impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
-    for BatchProofCircuit<'a, E, WP, AD>
+impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>, T> Circuit<E>
+    for BatchProofCircuit<'a, E, WP, AD, T>
+where
+    T: Transcript<E, Params = AllocatedNum<E>>,
 {
     type MainGate = Width4MainGateWithDNext;
 
     fn declare_used_gates() -> Result<Vec<Box<dyn GateInternal<E>>>, SynthesisError> {
-        Ok(vec![
+        let mut gates = vec![
             Self::MainGate::default().into_internal(),
             TwoBitDecompositionRangecheckCustomGate::default().into_internal(),
-        ])
+        ];
+        gates.extend(T::used_gates());
+
+        Ok(gates)
     }
 
     fn synthesize<CS: ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
         let transcript_params = AllocatedNum::alloc(cs, || Ok(self.transcript_params.unwrap()))?;
-        let mut transcript = WrappedTranscript::new(transcript_params);
-
-        if self.commitments.len() != self.ys.len() {
-            panic!(
-                "number of commitments = {}, while number of output points = {}",
-                self.commitments.len(),
-                self.ys.len()
-            );
-        }
-        if self.commitments.len() != self.zs.len() {
-            panic!(
-                "number of commitments = {}, while number of input points = {}",
-                self.commitments.len(),
-                self.zs.len()
-            );
-        }
+        let mut transcript = T::new(cs, transcript_params)?;
 
         let num_queries = self.commitments.len();
         if num_queries == 0 {
-            panic!("cannot create a multi proof with no data");
+            return Err(CircuitError::InvalidInputShape(
+                "cannot create a multi proof with no data".to_string(),
+            )
+            .into());
         }
 
+        if self.ys.len() != num_queries || self.zs.len() != num_queries {
+            return Err(CircuitError::InvalidInputShape(format!(
+                "{} output points and {} input points are given for {} commitments",
+                self.ys.len(),
+                self.zs.len(),
+                num_queries,
+            ))
+            .into());
+        }
+
+        let mut guard = enter_namespace(cs, "batch_proof_fr::BatchProofCircuit");
+        let cs = &mut *guard;
         for i in 0..num_queries {
             let allocated_commitment_i =
                 WP::alloc::<CS, AD>(cs, self.commitments[i], self.rns_params, &self.aux_data)?;
-            transcript.commit_point(cs, &allocated_commitment_i)?; // commitments[i]
+            transcript.commit_point_with_label(cs, b"C", &allocated_commitment_i)?;
             let zi = self.zs[i]
                 .map(|zi| E::Fr::from_repr(<E::Fr as PrimeField>::Repr::from(zi as u64)).unwrap());
             let allocated_zi = AllocatedNum::alloc(cs, || Ok(zi.unwrap()))?;
-            transcript.commit_alloc_num(cs, allocated_zi)?; // z
+            transcript.commit_alloc_num_with_label(cs, b"input point", &allocated_zi)?;
             let allocated_yi = AllocatedNum::alloc(cs, || Ok(self.ys[i].unwrap()))?;
-            transcript.commit_alloc_num(cs, allocated_yi)?; // y
+            transcript.commit_alloc_num_with_label(cs, b"output point", &allocated_yi)?;
         }
 
-        let r = transcript.get_challenge();
+        let r = transcript.get_challenge(cs)?;
 
         let allocated_d = WP::alloc::<CS, AD>(cs, self.d, self.rns_params, &self.aux_data)?;
-        transcript.commit_point(cs, &allocated_d)?; // D
-        let t = transcript.get_challenge();
+        transcript.commit_point_with_label(cs, b"D", &allocated_d)?;
+        let t = transcript.get_challenge(cs)?;
 
         // Compute helper_scalars. This is r^i / t - z_i
         //
@@ -130,14 +150,14 @@ impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
             e = e.add(cs, &mut tmp, self.rns_params)?;
         }
 
-        transcript.commit_point(cs, &e)?; // E
+        transcript.commit_point_with_label(cs, b"E", &e)?;
 
         let mut d = WP::alloc(cs, self.d, self.rns_params, &self.aux_data)?;
         let mut minus_d = d.negate(cs, self.rns_params)?;
         let e_minus_d = e.add(cs, &mut minus_d, self.rns_params)?;
 
-        let transcript_params = transcript.get_challenge();
-        let ipa = IpaCircuit::<'_, E, WP, AD> {
+        let transcript_params = transcript.get_challenge(cs)?;
+        let ipa = IpaCircuit::<'_, E, WP, AD, T> {
             commitment: e_minus_d.get_point().get_value(),
             proof: self.proof.clone(),
             eval_point: t.get_value(),
@@ -147,8 +167,11 @@ impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
             aux_data: self.aux_data.clone(),
             transcript_params: transcript_params.get_value(),
             _wp: std::marker::PhantomData,
+            _transcript: std::marker::PhantomData,
         };
 
-        ipa.synthesize(cs)
+        ipa.synthesize(cs)?;
+
+        Ok(())
     }
 }
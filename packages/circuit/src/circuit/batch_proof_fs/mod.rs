@@ -1,4 +1,3 @@
-use franklin_crypto::babyjubjub::{edwards, JubjubEngine, Unknown};
 use franklin_crypto::bellman::plonk::better_better_cs::cs::{
     Circuit, ConstraintSystem, Gate, GateInternal, Width4MainGateWithDNext,
 };
@@ -6,49 +5,58 @@ use franklin_crypto::bellman::{Field, PrimeField, SynthesisError};
 use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
 use franklin_crypto::plonk::circuit::bigint::field::{FieldElement, RnsParameters};
 use franklin_crypto::plonk::circuit::bigint::range_constraint_gate::TwoBitDecompositionRangecheckCustomGate;
-use verkle_tree::ipa_fs::config::{Committer, IpaConfig};
 use verkle_tree::ipa_fs::utils::log2_ceil;
 
+use crate::circuit::debug::enter_namespace;
 use crate::circuit::ipa_fs::circuit::check_ipa_proof;
+use crate::circuit::num::jubjub::JubjubEngine;
 use crate::circuit::num::{allocate_edwards_point, convert_bits_le};
+use crate::circuit::transcript::UnlabeledTranscript;
+use crate::error::CircuitError;
 
-use super::ipa_fs::dummy_transcript::WrappedDummyTranscript as WrappedTranscript;
+use super::ipa_fs::config::IpaSetup;
+use super::ipa_fs::dummy_transcript::WrappedDummyTranscript;
 // use super::ipa_fs::transcript::WrappedTranscript;
 use super::ipa_fs::proof::OptionIpaProof;
 use super::ipa_fs::transcript::Transcript;
 use super::num::baby_ecc::EdwardsPoint;
 
-pub struct BatchProofCircuit<'a, 'b, 'c, E: JubjubEngine>
+/// verkle-tree commits no labels to its transcript, so its proofs are checked with this circuit.
+pub type UnlabeledBatchProofCircuit<'a, 'b, 'c, E> =
+    BatchProofCircuit<'a, 'b, 'c, E, UnlabeledTranscript<WrappedDummyTranscript<E>>>;
+
+pub struct BatchProofCircuit<'a, 'b, 'c, E: JubjubEngine, T = WrappedDummyTranscript<E>>
 where
     'c: 'b,
 {
     // public inputs
     pub transcript_params: Option<E::Fr>,
-    pub commitments: Vec<Option<edwards::Point<E, Unknown>>>,
+    pub commitments: Vec<Option<E::Point>>,
 
     // private inputs
     pub proof: OptionIpaProof<E>,
-    pub d: Option<edwards::Point<E, Unknown>>,
+    pub d: Option<E::Point>,
     pub ys: Vec<Option<E::Fs>>,
     pub zs: Vec<Option<usize>>,
 
     // constant parameters
-    pub ipa_conf: &'c IpaConfig<'b, E>,
+    pub ipa_conf: &'c (dyn IpaSetup<E> + 'b),
     pub rns_params: &'a RnsParameters<E, E::Fs>,
+    pub _transcript: std::marker::PhantomData<T>,
 }
 
-impl<'a, 'b, 'c, E: JubjubEngine> BatchProofCircuit<'a, 'b, 'c, E>
+impl<'a, 'b, 'c, E: JubjubEngine, T> BatchProofCircuit<'a, 'b, 'c, E, T>
 where
     'c: 'b,
 {
     // Initialize variables with None.
     pub fn initialize(
-        ipa_conf: &'c IpaConfig<'b, E>,
+        ipa_conf: &'c (dyn IpaSetup<E> + 'b),
         rns_params: &'a RnsParameters<E, E::Fs>,
     ) -> Self {
-        let num_rounds = log2_ceil(ipa_conf.get_domain_size());
+        let num_rounds = log2_ceil(ipa_conf.domain_size());
 
-        BatchProofCircuit::<E> {
+        BatchProofCircuit {
             transcript_params: None,
             commitments: vec![None; num_rounds],
             proof: OptionIpaProof::with_depth(num_rounds),
@@ -57,34 +65,35 @@ where
             zs: vec![None; num_rounds],
             ipa_conf,
             rns_params,
+            _transcript: std::marker::PhantomData,
         }
     }
 }
 
-impl<'a, 'b, 'c, E: JubjubEngine> Circuit<E> for BatchProofCircuit<'a, 'b, 'c, E> {
+impl<'a, 'b, 'c, E: JubjubEngine, T> Circuit<E> for BatchProofCircuit<'a, 'b, 'c, E, T>
+where
+    T: Transcript<E, Params = AllocatedNum<E>>,
+{
     type MainGate = Width4MainGateWithDNext;
 
     fn declare_used_gates() -> Result<Vec<Box<dyn GateInternal<E>>>, SynthesisError> {
-        Ok(vec![
+        let mut gates = vec![
             Self::MainGate::default().into_internal(),
             TwoBitDecompositionRangecheckCustomGate::default().into_internal(),
-        ])
+        ];
+        gates.extend(T::used_gates());
+
+        Ok(gates)
     }
 
     fn synthesize<CS: ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
-        let jubjub_params = self.ipa_conf.jubjub_params;
-        dbg!(self.transcript_params);
+        let jubjub_params = self.ipa_conf.jubjub_params();
         let transcript_params = AllocatedNum::<E>::alloc(cs, || {
             self.transcript_params
                 .ok_or(SynthesisError::UnconstrainedVariable)
         })?;
         // transcript_params.inputize(cs)?;
 
-        dbg!(self
-            .commitments
-            .iter()
-            .map(|v| v.as_ref().map(|v| v.into_xy()))
-            .collect::<Vec<_>>());
         let commitments = self
             .commitments
             .iter()
@@ -115,7 +124,7 @@ impl<'a, 'b, 'c, E: JubjubEngine> Circuit<E> for BatchProofCircuit<'a, 'b, 'c, E
             .collect::<Result<Vec<_>, _>>()?;
         let d = allocate_edwards_point(cs, &self.d, jubjub_params)?;
 
-        check_batch_proof(
+        check_batch_proof::<E, CS, T>(
             cs,
             transcript_params,
             commitments,
@@ -125,103 +134,131 @@ impl<'a, 'b, 'c, E: JubjubEngine> Circuit<E> for BatchProofCircuit<'a, 'b, 'c, E
             zs,
             self.ipa_conf,
             self.rns_params,
-        )
+        )?;
+
+        Ok(())
     }
 }
 
-pub fn check_batch_proof<'a, E: JubjubEngine, CS: ConstraintSystem<E>>(
+pub fn check_batch_proof<E: JubjubEngine, CS: ConstraintSystem<E>, T: Transcript<E>>(
     cs: &mut CS,
-    transcript_params: AllocatedNum<E>,
+    transcript_params: T::Params,
     commitments: Vec<EdwardsPoint<E>>,
     proof: &OptionIpaProof<E>,
     d: EdwardsPoint<E>,
     ys: Vec<FieldElement<E, E::Fs>>,
     zs: Vec<FieldElement<E, E::Fs>>,
-    ipa_conf: &IpaConfig<'a, E>,
+    ipa_conf: &dyn IpaSetup<E>,
     rns_params: &RnsParameters<E, E::Fs>,
-) -> Result<(), SynthesisError> {
-    let jubjub_params = ipa_conf.jubjub_params;
-    let mut transcript = WrappedTranscript::new(cs, transcript_params);
+) -> Result<(), CircuitError> {
+    let jubjub_params = ipa_conf.jubjub_params();
+    let mut transcript = T::new(cs, transcript_params)?;
 
     let num_queries = commitments.len();
     if num_queries == 0 {
-        panic!("cannot create a multi proof with no data");
+        return Err(CircuitError::InvalidInputShape(
+            "cannot create a multi proof with no data".to_string(),
+        ));
     }
 
-    assert_eq!(
-        ys.len(),
-        num_queries,
-        "number of output points = {}, while number of commitments = {}",
-        ys.len(),
-        num_queries,
-    );
-    assert_eq!(
-        zs.len(),
-        num_queries,
-        "number of input points = {}, while number of commitments = {}",
-        zs.len(),
-        num_queries,
-    );
+    if ys.len() != num_queries || zs.len() != num_queries {
+        return Err(CircuitError::InvalidInputShape(format!(
+            "{} output points and {} input points are given for {} commitments",
+            ys.len(),
+            zs.len(),
+            num_queries,
+        )));
+    }
 
+    let _span = tracing::info_span!(
+        "batch_proof_fs::check_batch_proof",
+        domain_size = ipa_conf.domain_size(),
+        num_queries
+    )
+    .entered();
+    let mut guard = enter_namespace(cs, "batch_proof_fs::check_batch_proof");
+    let cs = &mut *guard;
     for i in 0..num_queries {
-        transcript.commit_point(cs, &commitments[i])?;
-        transcript.commit_field_element(cs, &zs[i])?;
-        transcript.commit_field_element(cs, &ys[i])?;
+        transcript.commit_point_with_label(cs, b"C", &commitments[i])?;
+        transcript.commit_field_element_with_label(cs, b"input point", &zs[i])?;
+        transcript.commit_field_element_with_label(cs, b"output point", &ys[i])?;
     }
 
-    let r = transcript.get_challenge(cs, rns_params)?;
+    let r: FieldElement<E, E::Fs> = transcript.get_challenge_as(cs, rns_params)?;
 
-    transcript.commit_point(cs, &d)?;
-    let t = transcript.get_challenge(cs, rns_params)?;
+    transcript.commit_point_with_label(cs, b"D", &d)?;
+    let t: FieldElement<E, E::Fs> = transcript.get_challenge_as(cs, rns_params)?;
 
     // Compute helper_scalars.
-    let mut helper_scalars = Vec::with_capacity(num_queries);
-    let one = FieldElement::new_constant(E::Fs::one(), rns_params);
-    let mut powers_of_r = one.clone(); // powers_of_r = 1
-    for zi in zs {
-        // helper_scalars[i] = r^i / (t - z_i)
-        let t_minus_zi = t.clone().sub(cs, zi.clone())?.0;
-        let raw_inv_t_minus_zi = if let Some(raw_t_minus_zi) = t_minus_zi.get_field_value() {
-            Some(raw_t_minus_zi.inverse().ok_or("division by zero").unwrap())
-        } else {
-            None
-        };
-        let inv_t_minus_zi =
-            FieldElement::new_allocated_in_field(cs, raw_inv_t_minus_zi, rns_params)?;
-        // inv_t_minus_zi.mul(cs, t_minus_zi)?.assert_equal(cs, one)?;
-        let helper_scalars_i = inv_t_minus_zi.mul(cs, powers_of_r.clone())?.0;
-        {
-            let _helper_scalars_i = powers_of_r.clone().div(cs, t_minus_zi)?.0;
-            assert_eq!(
-                helper_scalars_i.get_field_value(),
-                _helper_scalars_i.get_field_value()
-            );
+    let helper_scalars = {
+        let mut guard = enter_namespace(cs, "helper scalars");
+        let cs = &mut *guard;
+        let mut helper_scalars = Vec::with_capacity(num_queries);
+        let one = FieldElement::new_constant(E::Fs::one(), rns_params);
+        let mut powers_of_r = one.clone(); // powers_of_r = 1
+        for zi in zs {
+            // helper_scalars[i] = r^i / (t - z_i)
+            let t_minus_zi = t.clone().sub(cs, zi.clone())?.0;
+            let raw_inv_t_minus_zi = if let Some(raw_t_minus_zi) = t_minus_zi.get_field_value() {
+                Some(
+                    raw_t_minus_zi
+                        .inverse()
+                        .ok_or(SynthesisError::DivisionByZero)?,
+                )
+            } else {
+                None
+            };
+            let inv_t_minus_zi =
+                FieldElement::new_allocated_in_field(cs, raw_inv_t_minus_zi, rns_params)?;
+            // inv_t_minus_zi.mul(cs, t_minus_zi)?.assert_equal(cs, one)?;
+            let helper_scalars_i = inv_t_minus_zi.mul(cs, powers_of_r.clone())?.0;
+            {
+                let _helper_scalars_i = powers_of_r.clone().div(cs, t_minus_zi)?.0;
+                assert_eq!(
+                    helper_scalars_i.get_field_value(),
+                    _helper_scalars_i.get_field_value()
+                );
+            }
+            helper_scalars.push(helper_scalars_i);
+
+            // powers_of_r *= r
+            powers_of_r = powers_of_r.mul(cs, r.clone())?.0;
         }
-        helper_scalars.push(helper_scalars_i);
 
-        // powers_of_r *= r
-        powers_of_r = powers_of_r.mul(cs, r.clone())?.0;
-    }
+        helper_scalars
+    };
 
     // Compute g_2(t) = SUM y_i * (r^i / t - z_i) = SUM y_i * helper_scalars
-    let mut g_2_t = FieldElement::new_constant(E::Fs::zero(), rns_params);
-    for (i, helper_scalars_i) in helper_scalars.iter().enumerate() {
-        let tmp = ys[i].clone().mul(cs, helper_scalars_i.clone())?.0;
-        g_2_t = g_2_t.add(cs, tmp)?.0;
-    }
+    let g_2_t = {
+        let mut guard = enter_namespace(cs, "g_2(t)");
+        let cs = &mut *guard;
+        let mut g_2_t = FieldElement::new_constant(E::Fs::zero(), rns_params);
+        for (i, helper_scalars_i) in helper_scalars.iter().enumerate() {
+            let tmp = ys[i].clone().mul(cs, helper_scalars_i.clone())?.0;
+            g_2_t = g_2_t.add(cs, tmp)?.0;
+        }
+
+        g_2_t
+    };
 
     // Compute E = SUM C_i * (r^i / t - z_i) = SUM C_i * helper_scalars
-    let mut e = {
-        let helper_scalars_i_bits = convert_bits_le(cs, helper_scalars[0].clone(), None)?;
-        commitments[0].mul(cs, &helper_scalars_i_bits, jubjub_params)?
+    let e = {
+        let mut guard = enter_namespace(cs, "E");
+        let cs = &mut *guard;
+        let mut e = {
+            let helper_scalars_i_bits = convert_bits_le(cs, helper_scalars[0].clone(), None)?;
+            commitments[0].mul(cs, &helper_scalars_i_bits, jubjub_params)?
+        };
+        for (i, helper_scalars_i) in helper_scalars.iter().enumerate().skip(1) {
+            let helper_scalars_i_bits = convert_bits_le(cs, helper_scalars_i.clone(), None)?;
+            let tmp = commitments[i].mul(cs, &helper_scalars_i_bits, jubjub_params)?;
+            e = e.add(cs, &tmp, jubjub_params)?;
+        }
+
+        e
     };
-    for (i, helper_scalars_i) in helper_scalars.iter().enumerate().skip(1) {
-        let helper_scalars_i_bits = convert_bits_le(cs, helper_scalars_i.clone(), None)?;
-        let tmp = commitments[i].mul(cs, &helper_scalars_i_bits, jubjub_params)?;
-        e = e.add(cs, &tmp, jubjub_params)?;
-    }
 
-    transcript.commit_point(cs, &e)?;
+    transcript.commit_point_with_label(cs, b"E", &e)?;
 
     // ipa_commitment = E - D
     let minus_d = {
@@ -242,7 +279,7 @@ pub fn check_batch_proof<'a, E: JubjubEngine, CS: ConstraintSystem<E>>(
     //     None
     // };
 
-    check_ipa_proof(
+    check_ipa_proof::<E, CS, T>(
         cs,
         transcript.into_params(),
         e_minus_d,
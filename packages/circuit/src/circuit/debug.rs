@@ -0,0 +1,320 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use franklin_crypto::bellman::pairing::Engine;
+use franklin_crypto::bellman::plonk::better_better_cs::cs::{
+    Circuit, ConstraintSystem, MainGate, SetupAssembly, TrivialAssembly,
+};
+use franklin_crypto::bellman::worker::Worker;
+use franklin_crypto::bellman::Field;
+use franklin_crypto::plonk::circuit::Width4WithCustomGates;
+
+use crate::error::CircuitError;
+
+thread_local! {
+    /// The namespaces are recorded only while `check_satisfied` or `profile_gates` synthesizes a circuit.
+    static RECORDER: RefCell<Option<NamespaceRecorder>> = RefCell::new(None);
+}
+
+#[derive(Default)]
+struct NamespaceRecorder {
+    stack: Vec<String>,
+    /// Pairs of the number of auxiliary gates and the namespaces entered from that gate on.
+    markers: Vec<(usize, Vec<String>)>,
+    /// The number of times each namespace is entered.
+    calls: HashMap<String, usize>,
+}
+
+impl NamespaceRecorder {
+    fn mark(&mut self, num_aux_gates: usize) {
+        self.markers.push((num_aux_gates, self.stack.clone()));
+    }
+
+    fn label_of(&self, aux_gate_index: usize) -> Option<String> {
+        let i = self
+            .markers
+            .partition_point(|(position, _)| *position <= aux_gate_index);
+        if i == 0 || self.markers[i - 1].1.is_empty() {
+            return None;
+        }
+
+        Some(self.markers[i - 1].1.join(" / "))
+    }
+}
+
+/// Run `f` recording the namespaces which are entered in it.
+fn record_namespaces<R>(f: impl FnOnce() -> R) -> (R, NamespaceRecorder) {
+    RECORDER.with(|recorder| *recorder.borrow_mut() = Some(NamespaceRecorder::default()));
+    let result = f();
+    let recorder = RECORDER
+        .with(|recorder| recorder.borrow_mut().take())
+        .unwrap_or_default();
+
+    (result, recorder)
+}
+
+/// Enter the namespace `name`, with which the gates allocated through the returned guard are labelled.
+/// The namespace is left when the guard is dropped, including on an early return with `?`.
+/// This records nothing unless `check_satisfied` or `profile_gates` is running.
+///
+/// ```ignore
+/// let mut guard = enter_namespace(cs, "fold_points");
+/// let cs = &mut *guard;
+/// ```
+pub fn enter_namespace<'a, E: Engine, CS: ConstraintSystem<E>>(
+    cs: &'a mut CS,
+    name: &str,
+) -> NamespaceGuard<'a, E, CS> {
+    RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+            recorder.stack.push(name.to_string());
+            *recorder.calls.entry(name.to_string()).or_default() += 1;
+            recorder.mark(cs.get_current_aux_gate_number());
+        }
+    });
+
+    NamespaceGuard {
+        cs,
+        _marker: PhantomData,
+    }
+}
+
+/// The constraint system borrowed while a namespace is entered. See `enter_namespace`.
+pub struct NamespaceGuard<'a, E: Engine, CS: ConstraintSystem<E>> {
+    cs: &'a mut CS,
+    _marker: PhantomData<E>,
+}
+
+impl<'a, E: Engine, CS: ConstraintSystem<E>> Deref for NamespaceGuard<'a, E, CS> {
+    type Target = CS;
+
+    fn deref(&self) -> &CS {
+        self.cs
+    }
+}
+
+impl<'a, E: Engine, CS: ConstraintSystem<E>> DerefMut for NamespaceGuard<'a, E, CS> {
+    fn deref_mut(&mut self) -> &mut CS {
+        self.cs
+    }
+}
+
+impl<'a, E: Engine, CS: ConstraintSystem<E>> Drop for NamespaceGuard<'a, E, CS> {
+    fn drop(&mut self) {
+        RECORDER.with(|recorder| {
+            if let Some(recorder) = recorder.borrow_mut().as_mut() {
+                recorder.stack.pop();
+                recorder.mark(self.cs.get_current_aux_gate_number());
+            }
+        });
+    }
+}
+
+/// The first gate which the witness does not satisfy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsatisfiedGate {
+    /// The index of the gate among the auxiliary gates.
+    pub aux_gate_index: usize,
+    /// The name of the gate type, e.g. the main gate.
+    pub gate_name: String,
+    /// The namespaces in which the gate is allocated, e.g. `ipa_fs::check_ipa_proof / final equality x`.
+    pub label: Option<String>,
+}
+
+impl fmt::Display for UnsatisfiedGate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at aux gate {}", self.gate_name, self.aux_gate_index)?;
+        if let Some(label) = &self.label {
+            write!(f, " in {}", label)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Synthesize `circuit` into `TrivialAssembly` and check that the witness satisfies the constraints.
+/// If not, the first unsatisfied gate is reported with the namespaces in which it is allocated.
+pub fn check_satisfied<E: Engine, C: Circuit<E>>(circuit: &C) -> Result<(), CircuitError> {
+    let mut assembly = TrivialAssembly::<E, Width4WithCustomGates, C::MainGate>::new();
+    let (result, recorder) = record_namespaces(|| circuit.synthesize(&mut assembly));
+    result?;
+
+    if assembly.is_satisfied() {
+        return Ok(());
+    }
+
+    match find_unsatisfied_gate(&assembly)? {
+        Some((aux_gate_index, gate_name)) => Err(CircuitError::UnsatisfiedGate(UnsatisfiedGate {
+            aux_gate_index,
+            gate_name,
+            label: recorder.label_of(aux_gate_index),
+        })),
+        // The public inputs or the lookup tables are unsatisfied.
+        None => Err(CircuitError::Unsatisfied),
+    }
+}
+
+/// `TrivialAssembly::is_satisfied` checks the gates type by type,
+/// so the gate with the least index is searched for over all types.
+fn find_unsatisfied_gate<E: Engine, MG: MainGate<E>>(
+    assembly: &TrivialAssembly<E, Width4WithCustomGates, MG>,
+) -> Result<Option<(usize, String)>, CircuitError> {
+    let worker = Worker::new();
+    let storage = assembly.make_assembled_poly_storage(&worker, false)?;
+    let last_row = assembly.n() - 1;
+
+    let mut first: Option<(usize, String)> = None;
+    for (gate, density) in assembly.aux_gate_density.0.iter() {
+        for (aux_gate_index, is_applicable) in density.iter().enumerate() {
+            if matches!(&first, Some((index, _)) if *index <= aux_gate_index) {
+                break;
+            }
+            if !is_applicable {
+                continue;
+            }
+
+            let row = assembly.num_input_gates + aux_gate_index;
+            let value = gate.verify_on_row(row, &storage, row == last_row);
+            if !value.is_zero() {
+                first = Some((aux_gate_index, gate.name().to_string()));
+                break;
+            }
+        }
+    }
+
+    Ok(first)
+}
+
+/// The number of gates allocated in a namespace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GateCount {
+    pub label: String,
+    /// The number of times the namespace is entered.
+    pub calls: usize,
+    /// The number of gates allocated in the namespace, including its inner namespaces.
+    pub gates: usize,
+    /// The number of gates allocated in the namespace, but not in its inner namespaces.
+    pub self_gates: usize,
+}
+
+/// The gate counts of a circuit, attributed to the namespaces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GateProfile {
+    pub num_input_gates: usize,
+    pub num_aux_gates: usize,
+    /// The auxiliary gates allocated out of any namespace.
+    pub unlabelled_gates: usize,
+    /// Sorted in descending order of `gates`.
+    pub counts: Vec<GateCount>,
+}
+
+impl fmt::Display for GateProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .counts
+            .iter()
+            .map(|count| count.label.len())
+            .chain(Some("unlabelled".len()))
+            .max()
+            .unwrap_or_default();
+        let share = |gates: usize| 100.0 * gates as f64 / self.num_aux_gates.max(1) as f64;
+
+        writeln!(
+            f,
+            "{:<width$} {:>8} {:>12} {:>12} {:>8}",
+            "namespace",
+            "calls",
+            "gates",
+            "self gates",
+            "share",
+            width = width
+        )?;
+        for count in self.counts.iter() {
+            writeln!(
+                f,
+                "{:<width$} {:>8} {:>12} {:>12} {:>7.2}%",
+                count.label,
+                count.calls,
+                count.gates,
+                count.self_gates,
+                share(count.gates),
+                width = width
+            )?;
+        }
+        writeln!(
+            f,
+            "{:<width$} {:>8} {:>12} {:>12} {:>7.2}%",
+            "unlabelled",
+            "-",
+            self.unlabelled_gates,
+            self.unlabelled_gates,
+            share(self.unlabelled_gates),
+            width = width
+        )?;
+        write!(
+            f,
+            "total: {} input gates and {} aux gates",
+            self.num_input_gates, self.num_aux_gates
+        )
+    }
+}
+
+/// Synthesize `circuit` into `SetupAssembly` and count the gates allocated in each namespace.
+/// Since no witness is needed, `circuit` may be the one used for the setup.
+///
+/// A gate is counted for every namespace on the stack when it is allocated,
+/// so the counts of nested namespaces overlap.
+pub fn profile_gates<E: Engine, C: Circuit<E>>(circuit: &C) -> Result<GateProfile, CircuitError> {
+    let mut assembly = SetupAssembly::<E, Width4WithCustomGates, C::MainGate>::new();
+    let (result, recorder) = record_namespaces(|| circuit.synthesize(&mut assembly));
+    result?;
+
+    let num_aux_gates = assembly.get_current_aux_gate_number();
+    let mut counts = HashMap::<String, GateCount>::new();
+    let mut labelled_gates = 0;
+    for (i, (start, stack)) in recorder.markers.iter().enumerate() {
+        let end = recorder
+            .markers
+            .get(i + 1)
+            .map_or(num_aux_gates, |(position, _)| *position);
+        let gates = end - start;
+        let innermost = match stack.last() {
+            Some(innermost) if gates != 0 => innermost,
+            _ => continue,
+        };
+
+        labelled_gates += gates;
+        for (depth, label) in stack.iter().enumerate() {
+            // A namespace entered recursively is counted once.
+            if stack[..depth].contains(label) {
+                continue;
+            }
+
+            counts
+                .entry(label.clone())
+                .or_insert_with(|| GateCount {
+                    label: label.clone(),
+                    calls: recorder.calls.get(label).copied().unwrap_or_default(),
+                    gates: 0,
+                    self_gates: 0,
+                })
+                .gates += gates;
+        }
+        if let Some(count) = counts.get_mut(innermost) {
+            count.self_gates += gates;
+        }
+    }
+
+    let mut counts = counts.into_values().collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.gates.cmp(&a.gates).then_with(|| a.label.cmp(&b.label)));
+
+    Ok(GateProfile {
+        num_input_gates: assembly.n() - num_aux_gates,
+        num_aux_gates,
+        unlabelled_gates: num_aux_gates - labelled_gates,
+        counts,
+    })
+}
@@ -1,6 +1,5 @@
 pub mod utils;
 
-use franklin_crypto::babyjubjub::{edwards, JubjubEngine, Unknown};
 use franklin_crypto::bellman::plonk::better_better_cs::cs::{ArithmeticTerm, MainGateTerm};
 use franklin_crypto::bellman::{
     plonk::better_better_cs::cs::{
@@ -23,6 +22,7 @@ use verkle_tree::ipa_fr::{
 };
 
 use crate::circuit::num::baby_ecc::EdwardsPoint;
+use crate::circuit::num::jubjub::JubjubEngine;
 
 use super::num::{allocate_edwards_point, split_into_fixed_number_of_bits};
 
@@ -31,9 +31,9 @@ use super::num::{allocate_edwards_point, split_into_fixed_number_of_bits};
 // It is difficult to compute `a` using only `G` and `H` because discrete logarithm assumption.
 // So only those who know `a` will be able to pass this verification.
 pub struct DiscreteLogCircuit<'a, E: JubjubEngine, AD: AuxData<E>> {
-    pub base_point: Option<edwards::Point<E, Unknown>>,
+    pub base_point: Option<E::Point>,
     pub coefficient: Option<E::Fs>,
-    pub output: Option<edwards::Point<E, Unknown>>,
+    pub output: Option<E::Point>,
     pub rns_params: &'a RnsParameters<E, E::Fs>,
     pub aux_data: AD,
     pub jubjub_params: &'a E::Params,
@@ -12,7 +12,7 @@ pub fn bytes_to_keep<E: Engine>() -> usize {
     (E::Fr::CAPACITY / 8) as usize
 }
 
-fn allocated_num_to_alligned_big_endian<E: Engine, CS: ConstraintSystem<E>>(
+pub fn allocated_num_to_alligned_big_endian<E: Engine, CS: ConstraintSystem<E>>(
     cs: &mut CS,
     el: &AllocatedNum<E>,
 ) -> Result<Vec<Boolean>, SynthesisError> {
@@ -1,5 +1,3 @@
-use std::io::{Error, ErrorKind};
-
 use franklin_crypto::bellman::pairing::Engine;
 use franklin_crypto::bellman::plonk::better_better_cs::cs::{
     Circuit, ConstraintSystem, Gate, GateInternal, Width4MainGateWithDNext,
@@ -14,13 +12,27 @@ use verkle_tree::ipa_fr::config::{Committer, IpaConfig};
 use verkle_tree::ipa_fr::rns::BaseRnsParameters;
 use verkle_tree::ipa_fr::utils::log2_ceil;
 
+use crate::circuit::debug::enter_namespace;
+use crate::circuit::transcript::UnlabeledTranscript;
+use crate::error::CircuitError;
+
 use super::config::compute_barycentric_coefficients;
 use super::proof::{generate_challenges, OptionIpaProof};
 use super::transcript::{Transcript, WrappedTranscript};
 use super::utils::{commit, fold_points, fold_scalars};
 
+/// verkle-tree commits no labels to its transcript, so its proofs are checked with this circuit.
+pub type UnlabeledIpaCircuit<'a, E, WP, AD> =
+    IpaCircuit<'a, E, WP, AD, UnlabeledTranscript<WrappedTranscript<E>>>;
+
 #[derive(Clone)]
-pub struct IpaCircuit<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> {
+pub struct IpaCircuit<
+    'a,
+    E: Engine,
+    WP: WrappedAffinePoint<'a, E>,
+    AD: AuxData<E>,
+    T = WrappedTranscript<E>,
+> {
     pub transcript_params: Option<E::Fr>,
     pub commitment: Option<E::G1Affine>,
     pub proof: OptionIpaProof<E::G1Affine>,
@@ -30,40 +42,51 @@ pub struct IpaCircuit<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<
     pub rns_params: &'a BaseRnsParameters<E>,
     pub aux_data: AD,
     pub _wp: std::marker::PhantomData<WP>,
+    pub _transcript: std::marker::PhantomData<T>,
 }
 
-impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
-    for IpaCircuit<'a, E, WP, AD>
+impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>, T> Circuit<E>
+    for IpaCircuit<'a, E, WP, AD, T>
+where
+    T: Transcript<E, Params = AllocatedNum<E>>,
 {
     type MainGate = Width4MainGateWithDNext;
 
     fn declare_used_gates() -> Result<Vec<Box<dyn GateInternal<E>>>, SynthesisError> {
-        Ok(vec![
+        let mut gates = vec![
             Self::MainGate::default().into_internal(),
             TwoBitDecompositionRangecheckCustomGate::default().into_internal(),
-        ])
+        ];
+        gates.extend(T::used_gates());
+
+        Ok(gates)
     }
 
     fn synthesize<CS: ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
         let transcript_params = AllocatedNum::alloc(cs, || Ok(self.transcript_params.unwrap()))?;
-        let mut transcript = WrappedTranscript::new(transcript_params);
+        let mut transcript = T::new(cs, transcript_params)?;
 
         if self.proof.l.len() != self.proof.r.len() {
-            return Err(
-                Error::new(ErrorKind::InvalidData, "L and R should be the same size").into(),
-            );
+            return Err(CircuitError::InvalidInputShape(
+                "L and R should be the same size".to_string(),
+            )
+            .into());
         }
 
         let domain_size = self.ipa_conf.get_domain_size();
         let num_rounds = log2_ceil(domain_size) as usize;
         if self.proof.l.len() != num_rounds {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "The number of points for L or R should be equal to the number of rounds",
-            )
+            return Err(CircuitError::InvalidInputShape(format!(
+                "the number of points for L or R should be {}, but {}",
+                num_rounds,
+                self.proof.l.len()
+            ))
             .into());
         }
 
+        let _span = tracing::info_span!("ipa_fr::IpaCircuit", domain_size).entered();
+        let mut guard = enter_namespace(cs, "ipa_fr::IpaCircuit");
+        let cs = &mut *guard;
         let eval_point = AllocatedNum::alloc(cs, || Ok(self.eval_point.unwrap()))?;
         let inner_prod = AllocatedNum::alloc(cs, || Ok(self.inner_prod.unwrap()))?;
         let mut commitment = WP::alloc(cs, self.commitment, self.rns_params, &self.aux_data)?;
@@ -76,18 +99,17 @@ impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
         .unwrap();
 
         if b.len() != self.ipa_conf.get_srs().len() {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "`barycentric_coefficients` had incorrect length",
+            return Err(CircuitError::InvalidInputShape(
+                "`barycentric_coefficients` had incorrect length".to_string(),
             )
             .into());
         }
 
-        transcript.commit_point(cs, &commitment)?; // C
-        transcript.commit_alloc_num(cs, eval_point)?; // input point
-        transcript.commit_alloc_num(cs, inner_prod)?; // output point
+        transcript.commit_point_with_label(cs, b"C", &commitment)?;
+        transcript.commit_alloc_num_with_label(cs, b"input point", &eval_point)?;
+        transcript.commit_alloc_num_with_label(cs, b"output point", &inner_prod)?;
 
-        let w = transcript.get_challenge();
+        let w = transcript.get_challenge(cs)?;
         let mut q = WP::alloc(
             cs,
             Some(self.ipa_conf.get_q()),
@@ -101,7 +123,7 @@ impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
             .mul(cs, &inner_prod, None, self.rns_params, &self.aux_data)?;
         commitment = commitment.add(cs, &mut qy, self.rns_params)?;
 
-        let challenges = generate_challenges::<_, _, WP, _>(
+        let challenges = generate_challenges::<_, _, WP, _, T>(
             cs,
             self.proof.clone(),
             &mut transcript,
@@ -113,7 +135,7 @@ impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
 
         // Compute expected commitment
         for (i, x) in challenges.iter().enumerate() {
-            println!("challenges_inv: {}/{}", i, challenges.len());
+            tracing::trace!(round = i, rounds = challenges.len(), "challenges_inv");
             let l = WP::alloc(cs, self.proof.l[i], self.rns_params, &self.aux_data)?;
             let r = WP::alloc(cs, self.proof.r[i], self.rns_params, &self.aux_data)?;
 
@@ -132,8 +154,6 @@ impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
             )?;
         }
 
-        println!("challenges_inv: {}/{}", challenges.len(), challenges.len());
-
         let mut current_basis = self
             .ipa_conf
             .get_srs()
@@ -141,11 +161,10 @@ impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
             .map(|v| WP::alloc(cs, Some(*v), self.rns_params, &self.aux_data))
             .collect::<Result<Vec<_>, SynthesisError>>()?;
 
-        println!("reduction starts");
-        let start = std::time::Instant::now();
+        let span = tracing::debug_span!("reduction").entered();
 
         for (i, x_inv) in challenges_inv.iter().enumerate() {
-            println!("x_inv: {}/{}", i, challenges_inv.len());
+            tracing::trace!(round = i, rounds = challenges_inv.len(), "x_inv");
             assert_eq!(
                 current_basis.len() % 2,
                 0,
@@ -170,23 +189,16 @@ impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
             )?;
         }
 
-        println!("x_inv: {}/{}", challenges_inv.len(), challenges_inv.len());
+        drop(span);
 
         if b.len() != 1 {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "`b` and `current_basis` should be 1",
+            return Err(CircuitError::InvalidInputShape(
+                "`b` and `current_basis` should be 1".to_string(),
             )
             .into());
         }
 
-        println!(
-            "reduction ends: {} s",
-            start.elapsed().as_millis() as f64 / 1000.0
-        );
-
-        println!("verification check starts");
-        let start = std::time::Instant::now();
+        let span = tracing::debug_span!("verification check").entered();
 
         // Compute `result = G[0] * a + (a * b[0]) * Q`.
         let proof_a = AllocatedNum::alloc(cs, || Ok(self.proof.a.unwrap()))?;
@@ -200,14 +212,16 @@ impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>, AD: AuxData<E>> Circuit<E>
         let result = result1.add(cs, &mut result2, self.rns_params)?; // result = result1 + result2
 
         // Ensure `commitment` is equal to `result`.
-        let is_valid = commitment.equals(cs, &result, self.rns_params)?;
+        {
+            let mut guard = enter_namespace(cs, "final equality");
+            let cs = &mut *guard;
+            let is_valid = commitment.equals(cs, &result, self.rns_params)?;
 
-        println!(
-            "verification check ends: {} s",
-            start.elapsed().as_millis() as f64 / 1000.0
-        );
+            let allocated_true = Boolean::constant(true);
+            Boolean::enforce_equal(cs, &is_valid, &allocated_true)?;
+        }
+        drop(span);
 
-        let allocated_true = Boolean::constant(true);
-        Boolean::enforce_equal(cs, &is_valid, &allocated_true)
+        Ok(())
     }
 }
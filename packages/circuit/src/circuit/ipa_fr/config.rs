@@ -4,12 +4,16 @@ use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
 use verkle_tree::ipa_fr::config::PrecomputedWeights;
 use verkle_tree::ipa_fr::utils::read_field_element_le;
 
+use crate::circuit::debug::enter_namespace;
+
 pub fn compute_barycentric_coefficients<E: Engine, CS: ConstraintSystem<E>>(
     cs: &mut CS,
     precomputed_weights: &PrecomputedWeights<E::Fr>,
     point: &AllocatedNum<E>,
 ) -> anyhow::Result<Vec<AllocatedNum<E>>> {
     let domain_size = precomputed_weights.get_domain_size();
+    let mut guard = enter_namespace(cs, "barycentric coefficients");
+    let cs = &mut *guard;
 
     // Compute A(x_i) * point - x_i
     let mut lagrange_evals: Vec<AllocatedNum<E>> = Vec::with_capacity(domain_size);
@@ -8,7 +8,7 @@ use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::aux
 use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::WrappedAffinePoint;
 use verkle_tree::ipa_fr::proof::IpaProof;
 
-use super::transcript::{Transcript, WrappedTranscript};
+use super::transcript::Transcript;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct OptionIpaProof<GA: CurveAffine> {
@@ -50,10 +50,11 @@ pub fn generate_challenges<
     CS: ConstraintSystem<E>,
     WP: WrappedAffinePoint<'a, E>,
     AD: AuxData<E>,
+    T: Transcript<E>,
 >(
     cs: &mut CS,
     ipa_proof: OptionIpaProof<E::G1Affine>,
-    transcript: &mut WrappedTranscript<E>,
+    transcript: &mut T,
     rns_params: &'a RnsParameters<E, <E::G1Affine as CurveAffine>::Base>,
     aux_data: &AD,
 ) -> Result<Vec<AllocatedNum<E>>, SynthesisError> {
@@ -61,10 +62,10 @@ pub fn generate_challenges<
     for (&l, &r) in ipa_proof.l.iter().zip(&ipa_proof.r) {
         let wrapped_l = WP::alloc(cs, l, rns_params, aux_data)?;
         let wrapped_r = WP::alloc(cs, r, rns_params, aux_data)?;
-        transcript.commit_point(cs, &wrapped_l)?;
-        transcript.commit_point(cs, &wrapped_r)?;
+        transcript.commit_point_with_label(cs, b"L", &wrapped_l)?;
+        transcript.commit_point_with_label(cs, b"R", &wrapped_r)?;
 
-        let c = transcript.get_challenge();
+        let c = transcript.get_challenge(cs)?;
         challenges.push(c);
     }
 
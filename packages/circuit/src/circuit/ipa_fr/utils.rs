@@ -2,12 +2,15 @@ use std::io::{Error, ErrorKind};
 
 use franklin_crypto::bellman::pairing::Engine;
 use franklin_crypto::bellman::plonk::better_better_cs::cs::ConstraintSystem;
-use franklin_crypto::bellman::{CurveAffine, SynthesisError};
-use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
+use franklin_crypto::bellman::{CurveAffine, Field, PrimeField, SynthesisError};
+use franklin_crypto::plonk::circuit::allocated_num::{AllocatedNum, Num};
+use franklin_crypto::plonk::circuit::linear_combination::LinearCombination;
 use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::aux_data::AuxData;
 use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::WrappedAffinePoint;
 use verkle_tree::ipa_fr::rns::BaseRnsParameters;
 
+use crate::circuit::debug::enter_namespace;
+
 // Computes c[i] = a[i] + b[i] * x
 // returns c
 // panics if len(a) != len(b)
@@ -51,6 +54,8 @@ pub fn fold_points<
         return Err(Error::new(ErrorKind::InvalidData, "slices not equal length").into());
     }
 
+    let mut guard = enter_namespace(cs, "fold_points");
+    let cs = &mut *guard;
     let result = b
         .iter()
         .enumerate()
@@ -126,3 +131,53 @@ pub fn commit<
 
     Ok(result)
 }
+
+/// Maps a commitment to the scalar opened in its parent node.
+///
+/// This is the same map as the native tree uses: the x-coordinate is reduced into its field
+/// and the bits above `E::Fr::CAPACITY` are dropped, so the result always fits in `E::Fr`.
+pub fn commitment_to_scalar<
+    'a,
+    E: Engine,
+    CS: ConstraintSystem<E>,
+    WP: WrappedAffinePoint<'a, E>,
+>(
+    cs: &mut CS,
+    commitment: &WP,
+) -> Result<AllocatedNum<E>, SynthesisError> {
+    let x = commitment
+        .get_point()
+        .get_x()
+        .force_reduce_into_field(cs)?
+        .enforce_is_normalized(cs)?;
+    let limb_widths = x.representation_params.binary_limbs_bit_widths.clone();
+    let capacity = E::Fr::CAPACITY as usize;
+
+    let two = E::Fr::from_str("2").unwrap();
+    let mut lc = LinearCombination::zero();
+    let mut shift = 0usize;
+    for (limb, width) in x.into_limbs().into_iter().zip(limb_widths) {
+        if shift >= capacity {
+            break;
+        }
+
+        let limb = limb.into_variable(); // this checks coeff and constant term internally
+        if shift + width <= capacity {
+            lc.add_assign_variable_with_coeff(&limb, two.pow(&[shift as u64]));
+        } else {
+            // Only the lower bits of the limb which crosses the capacity are kept.
+            let limb_bits = limb.into_bits_le(cs, Some(width))?;
+            for (i, bit) in limb_bits.iter().take(capacity - shift).enumerate() {
+                lc.add_assign_boolean_with_coeff(bit, two.pow(&[(shift + i) as u64]));
+            }
+        }
+        shift += width;
+    }
+
+    let result = match lc.into_num(cs)? {
+        Num::Variable(result) => result,
+        Num::Constant(value) => AllocatedNum::alloc_cnst(cs, value)?,
+    };
+
+    Ok(result)
+}
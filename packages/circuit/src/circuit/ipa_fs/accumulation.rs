@@ -0,0 +1,280 @@
+//! Accumulation of IPA openings, as in Halo.
+//!
+//! Most of the constraints of `IpaCircuit` are spent on folding the SRS into `G[0]`.
+//! `IpaAccumulationCircuit` takes `G[0]` as a witness and outputs it with the challenges,
+//! and the folding of many openings is checked at once by `verify_deferred_ipa_checks`
+//! with a single multi-scalar multiplication over the SRS.
+
+use franklin_crypto::bellman::plonk::better_better_cs::cs::{
+    Circuit, ConstraintSystem, Gate, GateInternal, Width4MainGateWithDNext,
+};
+use franklin_crypto::bellman::{Field, PrimeField, SynthesisError};
+use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
+use franklin_crypto::plonk::circuit::bigint::field::{FieldElement, RnsParameters};
+use franklin_crypto::plonk::circuit::bigint::range_constraint_gate::TwoBitDecompositionRangecheckCustomGate;
+use rand::Rng;
+use verkle_tree::ipa_fs::utils::log2_ceil;
+
+use crate::circuit::num::jubjub::{JubjubEngine, JubjubPoint};
+use crate::circuit::num::{allocate_edwards_point, compose_field_element};
+use crate::circuit::transcript::UnlabeledTranscript;
+use crate::error::CircuitError;
+
+use super::circuit::check_ipa_proof_with_deferred_basis;
+use super::config::IpaSetup;
+use super::dummy_transcript::WrappedDummyTranscript;
+use super::proof::OptionIpaProof;
+use super::transcript::Transcript;
+
+/// verkle-tree commits no labels to its transcript, so its proofs are checked with this circuit.
+pub type UnlabeledIpaAccumulationCircuit<'a, 'b, 'c, E> =
+    IpaAccumulationCircuit<'a, 'b, 'c, E, UnlabeledTranscript<WrappedDummyTranscript<E>>>;
+
+/// The public inputs are the commitment, the challenges and the folded basis `G[0]` in this order.
+#[derive(Clone)]
+pub struct IpaAccumulationCircuit<'a, 'b, 'c, E: JubjubEngine, T = WrappedDummyTranscript<E>>
+where
+    'c: 'b,
+{
+    // public inputs
+    pub transcript_params: Option<E::Fr>,
+    pub commitment: Option<E::Point>,
+
+    // private inputs
+    pub proof: OptionIpaProof<E>,
+    pub eval_point: Option<E::Fs>,
+    pub inner_prod: Option<E::Fs>,
+
+    // constant parameters
+    pub ipa_conf: &'c (dyn IpaSetup<E> + 'b),
+    pub rns_params: &'a RnsParameters<E, <E as JubjubEngine>::Fs>,
+    pub _transcript: std::marker::PhantomData<T>,
+}
+
+impl<'a, 'b, 'c, E: JubjubEngine, T> IpaAccumulationCircuit<'a, 'b, 'c, E, T>
+where
+    'c: 'b,
+{
+    pub fn initialize(
+        ipa_conf: &'c (dyn IpaSetup<E> + 'b),
+        rns_params: &'a RnsParameters<E, E::Fs>,
+    ) -> IpaAccumulationCircuit<'a, 'b, 'c, E, T> {
+        let num_rounds = log2_ceil(ipa_conf.domain_size());
+
+        IpaAccumulationCircuit {
+            transcript_params: None,
+            commitment: None,
+            proof: OptionIpaProof::with_depth(num_rounds),
+            eval_point: None,
+            inner_prod: None,
+            ipa_conf,
+            rns_params,
+            _transcript: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, 'c, E: JubjubEngine, T> Circuit<E> for IpaAccumulationCircuit<'a, 'b, 'c, E, T>
+where
+    'c: 'b,
+    T: Transcript<E, Params = AllocatedNum<E>>,
+{
+    type MainGate = Width4MainGateWithDNext;
+
+    fn declare_used_gates() -> Result<Vec<Box<dyn GateInternal<E>>>, SynthesisError> {
+        let mut gates = vec![
+            Self::MainGate::default().into_internal(),
+            TwoBitDecompositionRangecheckCustomGate::default().into_internal(),
+        ];
+        gates.extend(T::used_gates());
+
+        Ok(gates)
+    }
+
+    fn synthesize<CS: ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
+        let jubjub_params = self.ipa_conf.jubjub_params();
+
+        let allocated_transcript_params = AllocatedNum::<E>::alloc(cs, || {
+            self.transcript_params
+                .ok_or(SynthesisError::UnconstrainedVariable)
+        })?;
+
+        let commitment = allocate_edwards_point(cs, &self.commitment, jubjub_params)?;
+        commitment.inputize(cs)?;
+
+        let eval_point =
+            FieldElement::new_allocated_in_field(cs, self.eval_point, self.rns_params)?;
+        let inner_prod =
+            FieldElement::new_allocated_in_field(cs, self.inner_prod, self.rns_params)?;
+
+        let deferred = check_ipa_proof_with_deferred_basis::<E, CS, T>(
+            cs,
+            allocated_transcript_params,
+            commitment,
+            &self.proof.clone(),
+            eval_point,
+            inner_prod,
+            self.ipa_conf,
+        )?;
+
+        for x in deferred.challenges.iter() {
+            compose_field_element(cs, x)?.inputize(cs)?;
+        }
+        deferred.final_basis.inputize(cs)?;
+
+        Ok(())
+    }
+}
+
+/// The check left by `IpaAccumulationCircuit`: `final_basis` is the SRS folded with `challenges`.
+#[derive(Clone)]
+pub struct DeferredIpaClaim<E: JubjubEngine> {
+    pub challenges: Vec<E::Fs>,
+    pub final_basis: E::Point,
+}
+
+impl<E: JubjubEngine> DeferredIpaClaim<E> {
+    /// Read the claim from the public inputs of a proof of `IpaAccumulationCircuit`.
+    pub fn from_public_inputs(
+        inputs: &[E::Fr],
+        num_rounds: usize,
+        jubjub_params: &E::Params,
+    ) -> Result<Self, CircuitError> {
+        if inputs.len() != num_rounds + 4 {
+            return Err(CircuitError::InvalidInputShape(format!(
+                "the number of public inputs should be {}, but {}",
+                num_rounds + 4,
+                inputs.len()
+            )));
+        }
+
+        let challenges = inputs[2..(2 + num_rounds)]
+            .iter()
+            .map(|x| {
+                let mut raw_x = <E::Fs as PrimeField>::Repr::default();
+                for (r, &v) in raw_x.as_mut().iter_mut().zip(x.into_repr().as_ref()) {
+                    *r = v;
+                }
+
+                E::Fs::from_repr(raw_x).map_err(|_| {
+                    CircuitError::InvalidInputShape(
+                        "the challenge is not in the scalar field".to_string(),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let final_basis = E::Point::from_xy(
+            inputs[2 + num_rounds],
+            inputs[3 + num_rounds],
+            jubjub_params,
+        )
+        .ok_or_else(|| {
+            CircuitError::InvalidPoint("the folded basis is not on the curve".to_string())
+        })?;
+
+        Ok(Self {
+            challenges,
+            final_basis,
+        })
+    }
+}
+
+/// Fold `srs` in the same way as `fold_points` in `check_ipa_proof`, i.e. `G_L + x^-1 * G_R`.
+pub fn compute_final_basis<E: JubjubEngine>(
+    srs: &[E::Point],
+    challenges: &[E::Fs],
+    jubjub_params: &E::Params,
+) -> Result<E::Point, CircuitError> {
+    let coefficients = compute_basis_coefficients::<E>(challenges, srs.len())?;
+
+    Ok(multi_scalar_mul(srs, &coefficients, jubjub_params))
+}
+
+/// The coefficient of `srs[i]` in the folded basis is the product of `x_j^-1`
+/// over the rounds `j` in which `srs[i]` is in the right half.
+/// A zero challenge is rejected, since no honest proof has one.
+fn compute_basis_coefficients<E: JubjubEngine>(
+    challenges: &[E::Fs],
+    size: usize,
+) -> Result<Vec<E::Fs>, CircuitError> {
+    let mut coefficients = vec![E::Fs::one(); size];
+    let mut half = size;
+    for x in challenges.iter() {
+        let x_inv = x.inverse().ok_or_else(|| {
+            CircuitError::InvalidInputShape("the challenge should not be zero".to_string())
+        })?;
+        half /= 2;
+        for (i, coefficient) in coefficients.iter_mut().enumerate() {
+            if i & half != 0 {
+                coefficient.mul_assign(&x_inv);
+            }
+        }
+    }
+
+    Ok(coefficients)
+}
+
+pub(crate) fn multi_scalar_mul<E: JubjubEngine>(
+    points: &[E::Point],
+    scalars: &[E::Fs],
+    jubjub_params: &E::Params,
+) -> E::Point {
+    points
+        .iter()
+        .zip(scalars)
+        .fold(E::Point::zero(), |acc, (point, scalar)| {
+            acc.add(&point.mul(scalar.into_repr(), jubjub_params), jubjub_params)
+        })
+}
+
+/// Check all of `claims` with one multi-scalar multiplication over the SRS.
+/// The claims are combined with the powers of a random `r`,
+/// so a wrong `final_basis` passes the check with probability at most `claims.len() / |Fs|`.
+pub fn verify_deferred_ipa_checks<E: JubjubEngine, R: Rng>(
+    claims: &[DeferredIpaClaim<E>],
+    ipa_conf: &dyn IpaSetup<E>,
+    rng: &mut R,
+) -> Result<(), CircuitError> {
+    if claims.is_empty() {
+        return Err(CircuitError::InvalidInputShape(
+            "no claims to verify".to_string(),
+        ));
+    }
+
+    let jubjub_params = ipa_conf.jubjub_params();
+    let domain_size = ipa_conf.domain_size();
+    let num_rounds = log2_ceil(domain_size);
+
+    let r: E::Fs = rng.gen();
+    let mut power = E::Fs::one();
+    let mut scalars = vec![E::Fs::zero(); domain_size];
+    let mut expected = E::Point::zero();
+    for claim in claims.iter() {
+        if claim.challenges.len() != num_rounds {
+            return Err(CircuitError::InvalidInputShape(format!(
+                "the number of challenges should be {}, but {}",
+                num_rounds,
+                claim.challenges.len()
+            )));
+        }
+
+        let coefficients = compute_basis_coefficients::<E>(&claim.challenges, domain_size)?;
+        for (scalar, coefficient) in scalars.iter_mut().zip(coefficients) {
+            let mut term = coefficient;
+            term.mul_assign(&power);
+            scalar.add_assign(&term);
+        }
+        expected = expected.add(
+            &claim.final_basis.mul(power.into_repr(), jubjub_params),
+            jubjub_params,
+        );
+        power.mul_assign(&r);
+    }
+
+    let result = multi_scalar_mul(ipa_conf.srs(), &scalars, jubjub_params);
+    if result != expected {
+        return Err(CircuitError::InvalidProof { index: None });
+    }
+
+    Ok(())
+}
@@ -1,34 +1,41 @@
 // use std::io::{Error, ErrorKind};
 
-use franklin_crypto::babyjubjub::{edwards, JubjubEngine, Unknown};
 use franklin_crypto::bellman::plonk::better_better_cs::cs::{
     Circuit, ConstraintSystem, Gate, GateInternal, Width4MainGateWithDNext,
 };
-use franklin_crypto::bellman::{Field, PrimeField, SynthesisError};
+use franklin_crypto::bellman::{Field, SynthesisError};
 use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
 use franklin_crypto::plonk::circuit::bigint::field::{FieldElement, RnsParameters};
 use franklin_crypto::plonk::circuit::bigint::range_constraint_gate::TwoBitDecompositionRangecheckCustomGate;
-use verkle_tree::ipa_fs::config::{Committer, IpaConfig};
 use verkle_tree::ipa_fs::utils::log2_ceil;
 
-use crate::circuit::ipa_fs::config::compute_barycentric_coefficients;
+use crate::circuit::debug::enter_namespace;
+use crate::circuit::ipa_fs::config::{compute_barycentric_coefficients, IpaSetup};
 use crate::circuit::num::baby_ecc::EdwardsPoint;
+use crate::circuit::num::jubjub::JubjubEngine;
 use crate::circuit::num::{allocate_edwards_point, convert_bits_le};
+use crate::circuit::transcript::UnlabeledTranscript;
+use crate::error::CircuitError;
 
-use super::dummy_transcript::WrappedDummyTranscript as WrappedTranscript;
+use super::accumulation::compute_final_basis;
+use super::dummy_transcript::WrappedDummyTranscript;
 // use super::transcript::WrappedTranscript;
 use super::proof::{generate_challenges, OptionIpaProof};
 use super::transcript::Transcript;
 use super::utils::{fold_points, fold_scalars};
 
+/// verkle-tree commits no labels to its transcript, so its proofs are checked with this circuit.
+pub type UnlabeledIpaCircuit<'a, 'b, 'c, E> =
+    IpaCircuit<'a, 'b, 'c, E, UnlabeledTranscript<WrappedDummyTranscript<E>>>;
+
 #[derive(Clone)]
-pub struct IpaCircuit<'a, 'b, 'c, E: JubjubEngine>
+pub struct IpaCircuit<'a, 'b, 'c, E: JubjubEngine, T = WrappedDummyTranscript<E>>
 where
     'c: 'b,
 {
     // public inputs
     pub transcript_params: Option<E::Fr>,
-    pub commitment: Option<edwards::Point<E, Unknown>>,
+    pub commitment: Option<E::Point>,
 
     // private inputs
     pub proof: OptionIpaProof<E>,
@@ -36,21 +43,22 @@ where
     pub inner_prod: Option<E::Fs>,
 
     // constant parameters
-    pub ipa_conf: &'c IpaConfig<'b, E>,
+    pub ipa_conf: &'c (dyn IpaSetup<E> + 'b),
     pub rns_params: &'a RnsParameters<E, <E as JubjubEngine>::Fs>,
+    pub _transcript: std::marker::PhantomData<T>,
 }
 
-impl<'a, 'b, 'c, E: JubjubEngine> IpaCircuit<'a, 'b, 'c, E>
+impl<'a, 'b, 'c, E: JubjubEngine, T> IpaCircuit<'a, 'b, 'c, E, T>
 where
     'c: 'b,
 {
     pub fn initialize(
-        ipa_conf: &'c IpaConfig<'b, E>,
+        ipa_conf: &'c (dyn IpaSetup<E> + 'b),
         rns_params: &'a RnsParameters<E, E::Fs>,
-    ) -> IpaCircuit<'a, 'b, 'c, E> {
-        let num_rounds = log2_ceil(ipa_conf.get_domain_size());
+    ) -> IpaCircuit<'a, 'b, 'c, E, T> {
+        let num_rounds = log2_ceil(ipa_conf.domain_size());
 
-        IpaCircuit::<E> {
+        IpaCircuit {
             transcript_params: None,
             commitment: None,
             proof: OptionIpaProof::with_depth(num_rounds),
@@ -58,35 +66,37 @@ where
             inner_prod: None,
             ipa_conf,
             rns_params,
+            _transcript: std::marker::PhantomData,
         }
     }
 }
 
-impl<'a, 'b, 'c, E: JubjubEngine> Circuit<E> for IpaCircuit<'a, 'b, 'c, E>
+impl<'a, 'b, 'c, E: JubjubEngine, T> Circuit<E> for IpaCircuit<'a, 'b, 'c, E, T>
 where
     'c: 'b,
+    T: Transcript<E, Params = AllocatedNum<E>>,
 {
     type MainGate = Width4MainGateWithDNext;
 
     fn declare_used_gates() -> Result<Vec<Box<dyn GateInternal<E>>>, SynthesisError> {
-        Ok(vec![
+        let mut gates = vec![
             Self::MainGate::default().into_internal(),
             TwoBitDecompositionRangecheckCustomGate::default().into_internal(),
-        ])
+        ];
+        gates.extend(T::used_gates());
+
+        Ok(gates)
     }
 
     fn synthesize<CS: ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
-        let jubjub_params = self.ipa_conf.jubjub_params;
+        let jubjub_params = self.ipa_conf.jubjub_params();
 
-        dbg!(self.transcript_params);
         let allocated_transcript_params = AllocatedNum::<E>::alloc(cs, || {
             self.transcript_params
                 .ok_or(SynthesisError::UnconstrainedVariable)
         })?;
 
         let commitment = allocate_edwards_point(cs, &self.commitment, jubjub_params)?;
-        dbg!(commitment.get_x().get_value());
-        dbg!(commitment.get_y().get_value());
         commitment.inputize(cs)?;
 
         let eval_point =
@@ -94,7 +104,7 @@ where
         let inner_prod =
             FieldElement::new_allocated_in_field(cs, self.inner_prod, self.rns_params)?;
 
-        check_ipa_proof(
+        check_ipa_proof::<E, CS, T>(
             cs,
             allocated_transcript_params,
             commitment,
@@ -102,189 +112,343 @@ where
             eval_point,
             inner_prod,
             self.ipa_conf,
-        )
+        )?;
+
+        Ok(())
     }
 }
 
-pub fn check_ipa_proof<'a, E: JubjubEngine, CS: ConstraintSystem<E>>(
+/// Splits `values` into two halves of the same length for the next folding round.
+fn split_in_half<'v, T>(values: &'v [T], name: &str) -> Result<(&'v [T], &'v [T]), CircuitError> {
+    if values.len() < 2 || values.len() % 2 != 0 {
+        return Err(CircuitError::InvalidInputShape(format!(
+            "cannot split `{}` of length {} in half",
+            name,
+            values.len()
+        )));
+    }
+
+    Ok(values.split_at(values.len() / 2))
+}
+
+/// The IPA proof whose commitment has been folded with the challenges,
+/// which is left to be checked against the folded basis.
+struct ReducedIpaProof<'a, E: JubjubEngine> {
+    commitment: EdwardsPoint<E>,
+    qw: EdwardsPoint<E>,
+    challenges: Vec<FieldElement<'a, E, E::Fs>>,
+    challenges_inv: Vec<FieldElement<'a, E, E::Fs>>,
+    /// The barycentric coefficients folded into one.
+    b: FieldElement<'a, E, E::Fs>,
+    a: Option<E::Fs>,
+}
+
+/// Everything of `check_ipa_proof` except the folding of the SRS and the final equality.
+fn reduce_ipa_proof<'a, E: JubjubEngine, CS: ConstraintSystem<E>, T: Transcript<E>>(
     cs: &mut CS,
-    transcript_params: AllocatedNum<E>,
+    transcript_params: T::Params,
     allocated_commitment: EdwardsPoint<E>,
-    proof: &OptionIpaProof<E>, // TODO: allocate
-    eval_point: FieldElement<E, E::Fs>,
-    inner_prod: FieldElement<E, E::Fs>,
-    ipa_conf: &IpaConfig<'a, E>,
-) -> Result<(), SynthesisError> {
-    let jubjub_params = ipa_conf.jubjub_params;
+    proof: &OptionIpaProof<E>,
+    eval_point: FieldElement<'a, E, E::Fs>,
+    inner_prod: FieldElement<'a, E, E::Fs>,
+    ipa_conf: &dyn IpaSetup<E>,
+) -> Result<ReducedIpaProof<'a, E>, CircuitError> {
+    let jubjub_params = ipa_conf.jubjub_params();
     let rns_params = eval_point.representation_params;
 
-    let mut transcript = WrappedTranscript::new(cs, transcript_params);
+    let mut transcript = T::new(cs, transcript_params)?;
 
     // dbg!(proof);
-    let num_ipa_rounds = log2_ceil(ipa_conf.get_domain_size());
-    assert_eq!(
-        proof.l.len(),
-        num_ipa_rounds,
-        "The number of points for L should be equal to the number of rounds"
-    );
-    assert_eq!(
-        proof.r.len(),
-        num_ipa_rounds,
-        "The number of points for R should be equal to the number of rounds"
-    );
-
-    let mut b =
-        compute_barycentric_coefficients::<E, CS>(cs, &ipa_conf.precomputed_weights, &eval_point)?;
-    dbg!(b
-        .iter()
-        .map(|b| b.get_field_value().map(|v| v.into_repr()))
-        .collect::<Vec<_>>());
-    assert_eq!(
-        b.len(),
-        ipa_conf.srs.len(),
-        "`barycentric_coefficients` had incorrect length"
-    );
-
-    transcript.commit_point(cs, &allocated_commitment)?;
-    transcript.commit_field_element(cs, &eval_point)?;
-    transcript.commit_field_element(cs, &inner_prod)?;
-
-    let w = transcript.get_challenge(cs, rns_params)?;
-    dbg!(w.get_field_value().map(|v| v.into_repr()));
-
-    let q = allocate_edwards_point(cs, &Some(ipa_conf.q.clone()), jubjub_params)?;
-
-    let w_bits = convert_bits_le(cs, w, None)?;
-    let qw = q.mul(cs, &w_bits, jubjub_params)?;
-    dbg!(qw.get_x().get_value());
-    dbg!(qw.get_y().get_value());
-    dbg!(inner_prod.get_field_value().map(|v| v.into_repr()));
-    let inner_prod_bits = convert_bits_le(cs, inner_prod, None)?;
-    let qy = qw.mul(cs, &inner_prod_bits, jubjub_params)?;
-    dbg!(qy.get_x().get_value());
-    dbg!(qy.get_y().get_value());
-    let mut commitment = allocated_commitment.add(cs, &qy, jubjub_params)?;
-    dbg!(commitment.get_x().get_value());
-    dbg!(commitment.get_y().get_value());
-
-    let (challenges, wrapped_proof) =
-        generate_challenges(cs, &proof, &mut transcript, jubjub_params, rns_params).unwrap();
-
-    let mut challenges_inv = Vec::with_capacity(challenges.len());
-
-    // Compute expected commitment
-    for (i, x) in challenges.iter().enumerate() {
-        dbg!(x.get_field_value().map(|v| v.into_repr()));
-        println!("challenges_inv: {}/{}", i, challenges.len());
-        let l = wrapped_proof.l[i].clone();
-        let r = wrapped_proof.r[i].clone();
-
-        let x_inv = {
-            let raw_x_inv = x.get_field_value().map(|raw_x| raw_x.inverse().unwrap());
-            let x_inv = FieldElement::new_allocated_in_field(cs, raw_x_inv, rns_params)?;
-
-            x_inv
-        };
-        challenges_inv.push(x_inv.clone());
+    let num_ipa_rounds = log2_ceil(ipa_conf.domain_size());
+    if proof.l.len() != num_ipa_rounds || proof.r.len() != num_ipa_rounds {
+        return Err(CircuitError::InvalidInputShape(format!(
+            "the numbers of points for L and R should be {}, but {} and {}",
+            num_ipa_rounds,
+            proof.l.len(),
+            proof.r.len()
+        )));
+    }
 
-        let x_bits = convert_bits_le(cs, x.clone(), None)?;
-        let commitment_l = l.mul(cs, &x_bits, jubjub_params)?;
-        let x_inv_bits = convert_bits_le(cs, x_inv, None)?;
-        let commitment_r = r.mul(cs, &x_inv_bits, jubjub_params)?;
-        commitment = commitment.add(cs, &commitment_l, jubjub_params)?.add(
-            cs,
-            &commitment_r,
-            jubjub_params,
-        )?;
-        dbg!(commitment.get_x().get_value());
-        dbg!(commitment.get_y().get_value());
+    if ipa_conf.srs().len() != 1 << num_ipa_rounds {
+        return Err(CircuitError::InvalidInputShape(format!(
+            "the domain size should be a power of two, but {}",
+            ipa_conf.srs().len()
+        )));
     }
 
-    println!("challenges_inv: {}/{}", challenges.len(), challenges.len());
+    let mut b = compute_barycentric_coefficients::<E, CS>(
+        cs,
+        &ipa_conf.barycentric_weights(),
+        &eval_point,
+    )?;
+    if b.len() != ipa_conf.srs().len() {
+        return Err(CircuitError::InvalidInputShape(format!(
+            "the number of barycentric coefficients should be {}, but {}",
+            ipa_conf.srs().len(),
+            b.len()
+        )));
+    }
 
-    let mut current_basis = ipa_conf
-        .srs
-        .iter()
-        .map(|v| allocate_edwards_point(cs, &Some(v.clone()), jubjub_params))
-        .collect::<Result<Vec<_>, SynthesisError>>()?;
+    transcript.commit_point_with_label(cs, b"C", &allocated_commitment)?;
+    transcript.commit_field_element_with_label(cs, b"input point", &eval_point)?;
+    transcript.commit_field_element_with_label(cs, b"output point", &inner_prod)?;
+
+    let w: FieldElement<E, E::Fs> = transcript.get_challenge_as(cs, rns_params)?;
+
+    let (qw, mut commitment) = {
+        let mut guard = enter_namespace(cs, "commitment with Q");
+        let cs = &mut *guard;
+
+        let q = allocate_edwards_point(cs, &Some(ipa_conf.q().clone()), jubjub_params)?;
 
-    println!("reduction starts");
-    let start = std::time::Instant::now();
-
-    for (i, x_inv) in challenges_inv.iter().enumerate() {
-        println!("x_inv: {}/{}", i, challenges_inv.len());
-        assert_eq!(
-            current_basis.len() % 2,
-            0,
-            "cannot split `current_basis` in half"
-        );
-        let mut g_chunks = current_basis.chunks(current_basis.len() / 2);
-        let g_l = g_chunks.next().unwrap().to_vec();
-        let g_r = g_chunks.next().unwrap().to_vec();
-
-        let mut b_chunks = b.chunks(b.len() / 2);
-        let b_l = b_chunks.next().unwrap().to_vec();
-        let b_r = b_chunks.next().unwrap().to_vec();
-
-        dbg!(x_inv.get_field_value().map(|v| v.into_repr()));
-        b = fold_scalars(cs, &b_l, &b_r, x_inv).unwrap();
-        current_basis = fold_points::<E, CS>(cs, &g_l, &g_r, x_inv, jubjub_params)?;
+        let w_bits = convert_bits_le(cs, w, None)?;
+        let qw = q.mul(cs, &w_bits, jubjub_params)?;
+        let inner_prod_bits = convert_bits_le(cs, inner_prod, None)?;
+        let qy = qw.mul(cs, &inner_prod_bits, jubjub_params)?;
+        let commitment = allocated_commitment.add(cs, &qy, jubjub_params)?;
+
+        (qw, commitment)
+    };
+
+    let (challenges, challenges_inv, proof_a) = {
+        let _span = tracing::debug_span!("challenges").entered();
+        let mut guard = enter_namespace(cs, "challenges");
+        let cs = &mut *guard;
+        let (challenges, wrapped_proof) =
+            generate_challenges(cs, proof, &mut transcript, jubjub_params, rns_params)?;
+
+        let mut challenges_inv = Vec::with_capacity(challenges.len());
+
+        // Compute expected commitment
+        for (i, x) in challenges.iter().enumerate() {
+            tracing::trace!(round = i, rounds = challenges.len(), "challenges_inv");
+            let l = wrapped_proof.l[i].clone();
+            let r = wrapped_proof.r[i].clone();
+
+            let x_inv = {
+                let raw_x_inv = x
+                    .get_field_value()
+                    .map(|raw_x| raw_x.inverse().ok_or(SynthesisError::DivisionByZero))
+                    .transpose()?;
+                let x_inv = FieldElement::new_allocated_in_field(cs, raw_x_inv, rns_params)?;
+
+                // `x_inv` is a witness, so it is bound to `x` before folding the commitment
+                // and being exported with the challenges.
+                let (x_times_x_inv, (_, x_inv)) = x.clone().mul(cs, x_inv)?;
+                let one = FieldElement::new_constant(E::Fs::one(), rns_params);
+                FieldElement::enforce_equal(cs, x_times_x_inv, one)?;
+
+                x_inv
+            };
+            challenges_inv.push(x_inv.clone());
+
+            let x_bits = convert_bits_le(cs, x.clone(), None)?;
+            let commitment_l = l.mul(cs, &x_bits, jubjub_params)?;
+            let x_inv_bits = convert_bits_le(cs, x_inv, None)?;
+            let commitment_r = r.mul(cs, &x_inv_bits, jubjub_params)?;
+            commitment = commitment.add(cs, &commitment_l, jubjub_params)?.add(
+                cs,
+                &commitment_r,
+                jubjub_params,
+            )?;
+        }
+
+        (challenges, challenges_inv, wrapped_proof.a)
+    };
+
+    {
+        let mut guard = enter_namespace(cs, "reduction of b");
+        let cs = &mut *guard;
+        for x_inv in challenges_inv.iter() {
+            let (b_l, b_r) = split_in_half(&b, "b")?;
+            b = fold_scalars(cs, b_l, b_r, x_inv)?;
+        }
     }
 
-    println!("x_inv: {}/{}", challenges_inv.len(), challenges_inv.len());
+    if b.len() != 1 {
+        return Err(CircuitError::InvalidInputShape(format!(
+            "`b` should have one element by the reduction, but {}",
+            b.len()
+        )));
+    }
 
-    assert_eq!(
-        b.len(),
-        1,
-        "`b` and `current_basis` should have one element by the reduction."
-    );
+    Ok(ReducedIpaProof {
+        commitment,
+        qw,
+        challenges,
+        challenges_inv,
+        b: b[0].clone(),
+        a: proof_a,
+    })
+}
 
-    println!(
-        "reduction ends: {} s",
-        start.elapsed().as_millis() as f64 / 1000.0
-    );
+/// Enforce `commitment == G[0] * a + (a * b[0]) * Q` for the folded basis `G[0]`.
+fn enforce_final_equality<E: JubjubEngine, CS: ConstraintSystem<E>>(
+    cs: &mut CS,
+    reduced: ReducedIpaProof<E>,
+    final_basis: EdwardsPoint<E>,
+    jubjub_params: &E::Params,
+) -> Result<(), CircuitError> {
+    let rns_params = reduced.b.representation_params;
 
-    println!("verification check starts");
-    let start = std::time::Instant::now();
+    let _span = tracing::debug_span!("verification check").entered();
+    let result = {
+        let mut guard = enter_namespace(cs, "final result");
+        let cs = &mut *guard;
 
-    // Compute `result = G[0] * a + (a * b[0]) * Q`.
-    let proof_a = FieldElement::new_allocated_in_field(cs, wrapped_proof.a, rns_params)?;
-    let mut result1 = current_basis[0].clone(); // result1 = G[0]
-    dbg!(current_basis[0].get_x().get_value());
-    dbg!(current_basis[0].get_y().get_value());
+        // Compute `result = G[0] * a + (a * b[0]) * Q`.
+        let proof_a = FieldElement::new_allocated_in_field(cs, reduced.a, rns_params)?;
+        let mut result1 = final_basis; // result1 = G[0]
 
-    let part_2a = b[0].clone(); // part_2a = b[0]
-    dbg!(b[0].get_field_value().map(|v| v.into_repr()));
+        let part_2a = reduced.b; // part_2a = b[0]
 
-    let proof_a_bits = convert_bits_le(cs, proof_a.clone(), None)?;
-    result1 = result1.mul(cs, &proof_a_bits, jubjub_params)?; // result1 = a[0] * current_basis[0]
-    dbg!(result1.get_x().get_value());
-    dbg!(result1.get_y().get_value());
+        let proof_a_bits = convert_bits_le(cs, proof_a.clone(), None)?;
+        result1 = result1.mul(cs, &proof_a_bits, jubjub_params)?; // result1 = a[0] * current_basis[0]
 
-    let part_2a = {
-        let (part_2a, (_, _)) = part_2a.mul(cs, proof_a)?; // part_2a = a[0] * b[0]
+        let part_2a = {
+            let (part_2a, (_, _)) = part_2a.mul(cs, proof_a)?; // part_2a = a[0] * b[0]
 
-        part_2a
-    };
-    dbg!(part_2a.get_field_value().map(|v| v.into_repr()));
+            part_2a
+        };
 
-    let part_2a_bits = convert_bits_le(cs, part_2a, None)?;
-    let result2 = qw.mul(cs, &part_2a_bits, jubjub_params)?; // result2 = a[0] * b[0] * w * Q
-    dbg!(result2.get_x().get_value());
-    dbg!(result2.get_y().get_value());
+        let part_2a_bits = convert_bits_le(cs, part_2a, None)?;
+        let result2 = reduced.qw.mul(cs, &part_2a_bits, jubjub_params)?; // result2 = a[0] * b[0] * w * Q
 
-    let result = result1.add(cs, &result2, jubjub_params)?; // result = result1 + result2
-    dbg!(result.get_x().get_value());
-    dbg!(result.get_y().get_value());
+        result1.add(cs, &result2, jubjub_params)? // result = result1 + result2
+    };
 
     // Ensure `commitment` is equal to `result`.
-    commitment.get_x().enforce_equal(cs, result.get_x())?;
-    commitment.get_y().enforce_equal(cs, result.get_y())?;
+    let commitment = reduced.commitment;
+    commitment.get_x().enforce_equal(
+        &mut *enter_namespace(cs, "final equality x"),
+        result.get_x(),
+    )?;
+    commitment.get_y().enforce_equal(
+        &mut *enter_namespace(cs, "final equality y"),
+        result.get_y(),
+    )?;
 
-    println!(
-        "verification check ends: {} s",
-        start.elapsed().as_millis() as f64 / 1000.0
-    );
+    Ok(())
+}
+
+pub fn check_ipa_proof<E: JubjubEngine, CS: ConstraintSystem<E>, T: Transcript<E>>(
+    cs: &mut CS,
+    transcript_params: T::Params,
+    allocated_commitment: EdwardsPoint<E>,
+    proof: &OptionIpaProof<E>, // TODO: allocate
+    eval_point: FieldElement<E, E::Fs>,
+    inner_prod: FieldElement<E, E::Fs>,
+    ipa_conf: &dyn IpaSetup<E>,
+) -> Result<(), CircuitError> {
+    let jubjub_params = ipa_conf.jubjub_params();
+
+    let _span = tracing::info_span!(
+        "ipa_fs::check_ipa_proof",
+        domain_size = ipa_conf.domain_size()
+    )
+    .entered();
+    let mut guard = enter_namespace(cs, "ipa_fs::check_ipa_proof");
+    let cs = &mut *guard;
+
+    let reduced = reduce_ipa_proof::<E, CS, T>(
+        cs,
+        transcript_params,
+        allocated_commitment,
+        proof,
+        eval_point,
+        inner_prod,
+        ipa_conf,
+    )?;
+
+    let mut current_basis = ipa_conf
+        .srs()
+        .iter()
+        .map(|v| allocate_edwards_point(cs, &Some(v.clone()), jubjub_params))
+        .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+    {
+        let _span = tracing::debug_span!("reduction").entered();
+        let mut guard = enter_namespace(cs, "reduction");
+        let cs = &mut *guard;
+
+        for (i, x_inv) in reduced.challenges_inv.iter().enumerate() {
+            tracing::trace!(round = i, rounds = reduced.challenges_inv.len(), "x_inv");
+            let (g_l, g_r) = split_in_half(&current_basis, "current_basis")?;
+            current_basis = fold_points::<E, CS>(cs, g_l, g_r, x_inv, jubjub_params)?;
+        }
+    }
+
+    if current_basis.len() != 1 {
+        return Err(CircuitError::InvalidInputShape(format!(
+            "`current_basis` should have one element by the reduction, but {}",
+            current_basis.len()
+        )));
+    }
+
+    let final_basis = current_basis[0].clone();
+    enforce_final_equality(cs, reduced, final_basis, jubjub_params)?;
 
     Ok(())
 }
+
+/// The checks which `check_ipa_proof_with_deferred_basis` leaves to `verify_deferred_ipa_checks`.
+pub struct DeferredIpaCheck<'a, E: JubjubEngine> {
+    pub challenges: Vec<FieldElement<'a, E, E::Fs>>,
+    /// The claimed `G[0]`, which must be the SRS folded with the inverses of `challenges`.
+    pub final_basis: EdwardsPoint<E>,
+}
+
+/// The accumulation variant of `check_ipa_proof`, which takes the folded basis `G[0]` as a witness
+/// instead of folding the SRS in the circuit.
+/// The proof is valid if `final_basis` is equal to the one computed from the returned challenges,
+/// which is checked outside the circuit by `accumulation::verify_deferred_ipa_checks`.
+pub fn check_ipa_proof_with_deferred_basis<
+    'a,
+    E: JubjubEngine,
+    CS: ConstraintSystem<E>,
+    T: Transcript<E>,
+>(
+    cs: &mut CS,
+    transcript_params: T::Params,
+    allocated_commitment: EdwardsPoint<E>,
+    proof: &OptionIpaProof<E>,
+    eval_point: FieldElement<'a, E, E::Fs>,
+    inner_prod: FieldElement<'a, E, E::Fs>,
+    ipa_conf: &dyn IpaSetup<E>,
+) -> Result<DeferredIpaCheck<'a, E>, CircuitError> {
+    let jubjub_params = ipa_conf.jubjub_params();
+
+    let _span = tracing::info_span!(
+        "ipa_fs::check_ipa_proof_with_deferred_basis",
+        domain_size = ipa_conf.domain_size()
+    )
+    .entered();
+    let mut guard = enter_namespace(cs, "ipa_fs::check_ipa_proof_with_deferred_basis");
+    let cs = &mut *guard;
+
+    let reduced = reduce_ipa_proof::<E, CS, T>(
+        cs,
+        transcript_params,
+        allocated_commitment,
+        proof,
+        eval_point,
+        inner_prod,
+        ipa_conf,
+    )?;
+    let challenges = reduced.challenges.clone();
+
+    let raw_challenges = challenges
+        .iter()
+        .map(|x| x.get_field_value())
+        .collect::<Option<Vec<_>>>();
+    let raw_final_basis = raw_challenges
+        .map(|xs| compute_final_basis(ipa_conf.srs(), &xs, jubjub_params))
+        .transpose()?;
+    let final_basis = allocate_edwards_point(cs, &raw_final_basis, jubjub_params)?;
+    enforce_final_equality(cs, reduced, final_basis.clone(), jubjub_params)?;
+
+    Ok(DeferredIpaCheck {
+        challenges,
+        final_basis,
+    })
+}
@@ -1,17 +1,69 @@
-use franklin_crypto::babyjubjub::JubjubEngine;
+use franklin_crypto::bellman::pairing::bn256::Bn256;
 use franklin_crypto::bellman::plonk::better_better_cs::cs::ConstraintSystem;
 use franklin_crypto::bellman::{Field, SynthesisError};
 use franklin_crypto::plonk::circuit::bigint::field::FieldElement;
-use verkle_tree::ipa_fr::config::PrecomputedWeights;
 use verkle_tree::ipa_fr::utils::read_field_element_le;
+use verkle_tree::ipa_fs::config::IpaConfig;
 
+use crate::circuit::debug::enter_namespace;
+use crate::circuit::num::jubjub::JubjubEngine;
+
+/// The setup of the IPA which the Fs circuits are built from.
+///
+/// verkle-tree gives `IpaConfig` only over BN256, so the circuits read it through this trait,
+/// which `native::NativeIpaConfig` implements over any `JubjubEngine`.
+pub trait IpaSetup<E: JubjubEngine> {
+    /// The basis to which the polynomials are committed, whose length is the domain size.
+    fn srs(&self) -> &[E::Point];
+
+    /// The point to which the inner product is committed.
+    fn q(&self) -> &E::Point;
+
+    /// `A'(i) = prod_{j != i} (i - j)` for each `i` in the domain `0..domain_size`.
+    fn barycentric_weights(&self) -> Vec<E::Fs>;
+
+    fn jubjub_params(&self) -> &E::Params;
+
+    fn domain_size(&self) -> usize {
+        self.srs().len()
+    }
+}
+
+impl<'a> IpaSetup<Bn256> for IpaConfig<'a, Bn256> {
+    fn srs(&self) -> &[<Bn256 as JubjubEngine>::Point] {
+        &self.srs
+    }
+
+    fn q(&self) -> &<Bn256 as JubjubEngine>::Point {
+        &self.q
+    }
+
+    fn barycentric_weights(&self) -> Vec<<Bn256 as JubjubEngine>::Fs> {
+        // Only the first `domain_size` of the precomputed weights are the ones of the domain.
+        self.precomputed_weights
+            .get_barycentric_weights()
+            .iter()
+            .take(self.srs.len())
+            .copied()
+            .collect()
+    }
+
+    fn jubjub_params(&self) -> &<Bn256 as JubjubEngine>::Params {
+        self.jubjub_params
+    }
+}
+
+/// Returns the Lagrange basis polynomials of the domain evaluated at `point`,
+/// where `barycentric_weights` are the ones of `IpaSetup`.
 pub fn compute_barycentric_coefficients<'a, E: JubjubEngine, CS: ConstraintSystem<E>>(
     cs: &mut CS,
-    precomputed_weights: &PrecomputedWeights<E::Fs>,
+    barycentric_weights: &[E::Fs],
     point: &FieldElement<'a, E, E::Fs>,
 ) -> Result<Vec<FieldElement<'a, E, E::Fs>>, SynthesisError> {
-    let domain_size = precomputed_weights.get_domain_size();
+    let domain_size = barycentric_weights.len();
     let rns_params = point.representation_params;
+    let mut guard = enter_namespace(cs, "barycentric coefficients");
+    let cs = &mut *guard;
 
     // Compute A(x_i) * point - x_i
     let mut lagrange_evals = Vec::with_capacity(domain_size);
@@ -20,8 +72,7 @@ pub fn compute_barycentric_coefficients<'a, E: JubjubEngine, CS: ConstraintSyste
     let mut total_prod =
         FieldElement::<E, E::Fs>::new_allocated_in_field(cs, Some(fs_one), rns_params)?;
 
-    let barycentric_weights = precomputed_weights.get_barycentric_weights();
-    for (i, barycentric_weights_i) in barycentric_weights.iter().take(domain_size).enumerate() {
+    for (i, barycentric_weights_i) in barycentric_weights.iter().enumerate() {
         let weight = FieldElement::<E, E::Fs>::new_allocated_in_field(
             cs,
             Some(*barycentric_weights_i),
@@ -1,6 +1,8 @@
+pub mod accumulation;
 pub mod circuit;
 pub mod config;
 pub mod dummy_transcript;
+pub mod native;
 pub mod proof;
 pub mod transcript;
 pub mod utils;
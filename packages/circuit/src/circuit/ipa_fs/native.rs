@@ -0,0 +1,322 @@
+//! The native IPA over any `JubjubEngine`.
+//!
+//! verkle-tree proves only over BN256, so this is the prover of the other engines, e.g. BLS12-381.
+//! Like verkle-tree, it commits no labels to its transcript, so its proofs are checked with `UnlabeledIpaCircuit`.
+
+use franklin_crypto::bellman::pairing::bn256::Bn256;
+use franklin_crypto::bellman::{Field, PrimeField, PrimeFieldRepr};
+use sha2::{Digest, Sha256};
+use verkle_tree::ipa_fs::proof::IpaProof;
+
+use crate::circuit::num::jubjub::{JubjubEngine, JubjubPoint};
+use crate::circuit::num::truncate_field_element;
+use crate::circuit::transcript::poseidon::PoseidonNativeTranscript;
+use crate::error::CircuitError;
+
+use super::accumulation::multi_scalar_mul;
+use super::config::IpaSetup;
+use super::proof::OptionIpaProof;
+
+/// The setup of the IPA whose SRS and `Q` are hashed to the curve, so that no one knows their discrete logarithms.
+pub struct NativeIpaConfig<'a, E: JubjubEngine> {
+    pub srs: Vec<E::Point>,
+    pub q: E::Point,
+    pub barycentric_weights: Vec<E::Fs>,
+    pub jubjub_params: &'a E::Params,
+}
+
+impl<'a, E: JubjubEngine> NativeIpaConfig<'a, E> {
+    /// `domain_size` must be a power of two.
+    pub fn new(domain_size: usize, jubjub_params: &'a E::Params) -> Result<Self, CircuitError> {
+        if !domain_size.is_power_of_two() {
+            return Err(CircuitError::InvalidInputShape(format!(
+                "the domain size should be a power of two, but {}",
+                domain_size
+            )));
+        }
+
+        let srs = (0..domain_size)
+            .map(|i| hash_to_point::<E>(b"srs", i, jubjub_params))
+            .collect();
+        let q = hash_to_point::<E>(b"q", 0, jubjub_params);
+
+        // A'(i) = prod_{j != i} (i - j)
+        let domain = (0..domain_size)
+            .map(|i| E::Fs::from_str(&i.to_string()).unwrap())
+            .collect::<Vec<_>>();
+        let barycentric_weights = domain
+            .iter()
+            .enumerate()
+            .map(|(i, x_i)| {
+                let mut weight = E::Fs::one();
+                for (j, x_j) in domain.iter().enumerate() {
+                    if j != i {
+                        let mut tmp = *x_i;
+                        tmp.sub_assign(x_j);
+                        weight.mul_assign(&tmp);
+                    }
+                }
+
+                weight
+            })
+            .collect();
+
+        Ok(Self {
+            srs,
+            q,
+            barycentric_weights,
+            jubjub_params,
+        })
+    }
+
+    /// Commit to the polynomial whose evaluations over the domain are `poly`.
+    pub fn commit(&self, poly: &[E::Fs]) -> Result<E::Point, CircuitError> {
+        if poly.len() != self.srs.len() {
+            return Err(CircuitError::InvalidInputShape(format!(
+                "the number of evaluations should be {}, but {}",
+                self.srs.len(),
+                poly.len()
+            )));
+        }
+
+        Ok(multi_scalar_mul(&self.srs, poly, self.jubjub_params))
+    }
+}
+
+impl<'a, E: JubjubEngine> IpaSetup<E> for NativeIpaConfig<'a, E> {
+    fn srs(&self) -> &[E::Point] {
+        &self.srs
+    }
+
+    fn q(&self) -> &E::Point {
+        &self.q
+    }
+
+    fn barycentric_weights(&self) -> Vec<E::Fs> {
+        self.barycentric_weights.clone()
+    }
+
+    fn jubjub_params(&self) -> &E::Params {
+        self.jubjub_params
+    }
+}
+
+/// Hashes `label` and `index` with a counter until the digest is the `y`-coordinate of a point,
+/// which is mapped into the prime-order subgroup.
+fn hash_to_point<E: JubjubEngine>(
+    label: &[u8],
+    index: usize,
+    jubjub_params: &E::Params,
+) -> E::Point {
+    let mut counter = 0u64;
+    loop {
+        let mut hasher = Sha256::new();
+        hasher.update(label);
+        hasher.update((index as u64).to_le_bytes());
+        hasher.update(counter.to_le_bytes());
+        let digest = hasher.finalize();
+        counter += 1;
+
+        let mut repr = <E::Fr as PrimeField>::Repr::default();
+        repr.read_le(&digest[..]).unwrap();
+        let y = match E::Fr::from_repr(repr) {
+            Ok(y) => y,
+            Err(_) => continue,
+        };
+        if let Some(point) = E::Point::get_for_y(y, false, jubjub_params) {
+            let point = point.mul_by_cofactor(jubjub_params);
+            if point != E::Point::zero() {
+                return point;
+            }
+        }
+    }
+}
+
+/// An opening proof of the IPA in the same form as `verkle_tree::ipa_fs::proof::IpaProof`.
+#[derive(Clone)]
+pub struct NativeIpaProof<E: JubjubEngine> {
+    pub l: Vec<E::Point>,
+    pub r: Vec<E::Point>,
+    pub a: E::Fs,
+}
+
+impl From<IpaProof<Bn256>> for NativeIpaProof<Bn256> {
+    fn from(ipa_proof: IpaProof<Bn256>) -> Self {
+        Self {
+            l: ipa_proof.l,
+            r: ipa_proof.r,
+            a: ipa_proof.a,
+        }
+    }
+}
+
+impl<E: JubjubEngine> From<NativeIpaProof<E>> for OptionIpaProof<E> {
+    fn from(ipa_proof: NativeIpaProof<E>) -> Self {
+        Self {
+            l: ipa_proof.l.into_iter().map(Some).collect(),
+            r: ipa_proof.r.into_iter().map(Some).collect(),
+            a: Some(ipa_proof.a),
+        }
+    }
+}
+
+impl<E: JubjubEngine> NativeIpaProof<E> {
+    /// Prove that the polynomial whose evaluations over the domain are `poly`
+    /// is equal to the returned inner product at `eval_point`, which must be outside the domain.
+    pub fn create(
+        commitment: &E::Point,
+        poly: &[E::Fs],
+        eval_point: E::Fs,
+        transcript_params: E::Fr,
+        ipa_conf: &dyn IpaSetup<E>,
+    ) -> Result<(Self, E::Fs), CircuitError> {
+        let jubjub_params = ipa_conf.jubjub_params();
+        let domain_size = ipa_conf.domain_size();
+        if poly.len() != domain_size || !domain_size.is_power_of_two() {
+            return Err(CircuitError::InvalidInputShape(format!(
+                "the number of evaluations should be the domain size {}, but {}",
+                domain_size,
+                poly.len()
+            )));
+        }
+
+        let mut b =
+            compute_barycentric_coefficients::<E>(&ipa_conf.barycentric_weights(), eval_point)?;
+        let inner_prod = inner_product::<E>(poly, &b);
+
+        let mut transcript = PoseidonNativeTranscript::<E>::new(transcript_params);
+        commit_point::<E>(&mut transcript, commitment)?;
+        commit_scalar::<E>(&mut transcript, &eval_point)?;
+        commit_scalar::<E>(&mut transcript, &inner_prod)?;
+        let w = get_challenge::<E>(&transcript);
+        let qw = ipa_conf.q().mul(w.into_repr(), jubjub_params);
+
+        let mut a = poly.to_vec();
+        let mut g = ipa_conf.srs().to_vec();
+        let mut l = vec![];
+        let mut r = vec![];
+        while a.len() > 1 {
+            let half = a.len() / 2;
+            let (a_l, a_r) = a.split_at(half);
+            let (b_l, b_r) = b.split_at(half);
+            let (g_l, g_r) = g.split_at(half);
+
+            let l_i = multi_scalar_mul(g_l, a_r, jubjub_params).add(
+                &qw.mul(inner_product::<E>(a_r, b_l).into_repr(), jubjub_params),
+                jubjub_params,
+            );
+            let r_i = multi_scalar_mul(g_r, a_l, jubjub_params).add(
+                &qw.mul(inner_product::<E>(a_l, b_r).into_repr(), jubjub_params),
+                jubjub_params,
+            );
+            commit_point::<E>(&mut transcript, &l_i)?;
+            commit_point::<E>(&mut transcript, &r_i)?;
+
+            let x = get_challenge::<E>(&transcript);
+            let x_inv = x.inverse().ok_or_else(|| {
+                CircuitError::InvalidInputShape("the challenge should not be zero".to_string())
+            })?;
+
+            // a = a_L + x * a_R, b = b_L + x^-1 * b_R and G = G_L + x^-1 * G_R
+            let next_a = fold_scalars::<E>(a_l, a_r, &x);
+            let next_b = fold_scalars::<E>(b_l, b_r, &x_inv);
+            let next_g = g_l
+                .iter()
+                .zip(g_r)
+                .map(|(g_l_i, g_r_i)| {
+                    g_l_i.add(&g_r_i.mul(x_inv.into_repr(), jubjub_params), jubjub_params)
+                })
+                .collect();
+            a = next_a;
+            b = next_b;
+            g = next_g;
+            l.push(l_i);
+            r.push(r_i);
+        }
+
+        Ok((Self { l, r, a: a[0] }, inner_prod))
+    }
+}
+
+/// `L_i(z) = A(z) / (A'(i) * (z - i))`, which is what `config::compute_barycentric_coefficients` computes in the circuit.
+fn compute_barycentric_coefficients<E: JubjubEngine>(
+    barycentric_weights: &[E::Fs],
+    point: E::Fs,
+) -> Result<Vec<E::Fs>, CircuitError> {
+    let differences = (0..barycentric_weights.len())
+        .map(|i| {
+            let mut tmp = point;
+            tmp.sub_assign(&E::Fs::from_str(&i.to_string()).unwrap());
+
+            tmp
+        })
+        .collect::<Vec<_>>();
+    let total_prod = differences.iter().fold(E::Fs::one(), |mut acc, d| {
+        acc.mul_assign(d);
+
+        acc
+    });
+
+    differences
+        .iter()
+        .zip(barycentric_weights)
+        .map(|(d, weight)| {
+            let mut tmp = *d;
+            tmp.mul_assign(weight);
+            let mut result = tmp.inverse().ok_or_else(|| {
+                CircuitError::InvalidInputShape(
+                    "the evaluation point should be outside the domain".to_string(),
+                )
+            })?;
+            result.mul_assign(&total_prod);
+
+            Ok(result)
+        })
+        .collect()
+}
+
+fn inner_product<E: JubjubEngine>(a: &[E::Fs], b: &[E::Fs]) -> E::Fs {
+    a.iter().zip(b).fold(E::Fs::zero(), |mut acc, (a_i, b_i)| {
+        let mut tmp = *a_i;
+        tmp.mul_assign(b_i);
+        acc.add_assign(&tmp);
+
+        acc
+    })
+}
+
+fn fold_scalars<E: JubjubEngine>(a: &[E::Fs], b: &[E::Fs], x: &E::Fs) -> Vec<E::Fs> {
+    a.iter()
+        .zip(b)
+        .map(|(a_i, b_i)| {
+            let mut tmp = *b_i;
+            tmp.mul_assign(x);
+            tmp.add_assign(a_i);
+
+            tmp
+        })
+        .collect()
+}
+
+/// Commits `x` and `y` as `TranscriptPoint` does for `EdwardsPoint`.
+fn commit_point<E: JubjubEngine>(
+    transcript: &mut PoseidonNativeTranscript<E>,
+    point: &E::Point,
+) -> Result<(), CircuitError> {
+    let (x, y) = point.into_xy();
+    transcript.commit_field_element(&x)?;
+    transcript.commit_field_element(&y)
+}
+
+/// `E::Fs` fits in `E::Fr`, so a scalar is committed as a single number, as `Transcript::commit_field_element` does.
+fn commit_scalar<E: JubjubEngine>(
+    transcript: &mut PoseidonNativeTranscript<E>,
+    value: &E::Fs,
+) -> Result<(), CircuitError> {
+    transcript.commit_field_element(&truncate_field_element::<E::Fs, E::Fr>(value))
+}
+
+/// The challenge is truncated into `E::Fs` as `TranscriptChallenge` does for `FieldElement`.
+fn get_challenge<E: JubjubEngine>(transcript: &PoseidonNativeTranscript<E>) -> E::Fs {
+    truncate_field_element::<E::Fr, E::Fs>(&transcript.get_challenge())
+}
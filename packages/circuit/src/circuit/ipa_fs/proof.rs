@@ -1,4 +1,4 @@
-use franklin_crypto::babyjubjub::{edwards, JubjubEngine, Unknown};
+use franklin_crypto::bellman::pairing::bn256::Bn256;
 use franklin_crypto::bellman::plonk::better_better_cs::cs::ConstraintSystem;
 use franklin_crypto::bellman::SynthesisError;
 use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
@@ -6,13 +6,14 @@ use franklin_crypto::plonk::circuit::bigint::field::{FieldElement, RnsParameters
 use verkle_tree::ipa_fs::proof::IpaProof;
 
 use crate::circuit::num::baby_ecc::EdwardsPoint;
+use crate::circuit::num::jubjub::{JubjubEngine, JubjubPoint};
 
 use super::transcript::Transcript;
 
 #[derive(Clone)]
 pub struct OptionIpaProof<E: JubjubEngine> {
-    pub l: Vec<Option<edwards::Point<E, Unknown>>>,
-    pub r: Vec<Option<edwards::Point<E, Unknown>>>,
+    pub l: Vec<Option<E::Point>>,
+    pub r: Vec<Option<E::Point>>,
     pub a: Option<E::Fs>,
 }
 
@@ -33,8 +34,8 @@ pub struct WrappedIpaProof<E: JubjubEngine> {
     pub a: Option<E::Fs>,
 }
 
-impl<E: JubjubEngine> From<IpaProof<E>> for OptionIpaProof<E> {
-    fn from(ipa_proof: IpaProof<E>) -> Self {
+impl From<IpaProof<Bn256>> for OptionIpaProof<Bn256> {
+    fn from(ipa_proof: IpaProof<Bn256>) -> Self {
         Self {
             l: ipa_proof
                 .l
@@ -82,7 +83,7 @@ where
 
             EdwardsPoint::interpret(cs, &l_x, &l_y, jubjub_params)?
         };
-        transcript.commit_point(cs, &wrapped_l)?; // L
+        transcript.commit_point_with_label(cs, b"L", &wrapped_l)?;
 
         let wrapped_r = {
             let raw_r = if let Some(r) = r {
@@ -98,9 +99,9 @@ where
 
             EdwardsPoint::interpret(cs, &r_x, &r_y, jubjub_params)?
         };
-        transcript.commit_point(cs, &wrapped_r)?; // R
+        transcript.commit_point_with_label(cs, b"R", &wrapped_r)?;
 
-        let c = transcript.get_challenge(cs, rns_params)?;
+        let c: FieldElement<E, E::Fs> = transcript.get_challenge_as(cs, rns_params)?;
         challenges.push(c);
         wrapped_proof_l.push(wrapped_l);
         wrapped_proof_r.push(wrapped_r);
@@ -1,16 +1,13 @@
-use franklin_crypto::babyjubjub::JubjubEngine;
-use franklin_crypto::bellman::plonk::better_better_cs::cs::ConstraintSystem;
+use franklin_crypto::bellman::plonk::better_better_cs::cs::{ConstraintSystem, GateInternal};
 use franklin_crypto::bellman::SynthesisError;
 use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
-use franklin_crypto::plonk::circuit::bigint::field::{FieldElement, RnsParameters};
 use verkle_tree::ff_utils::bn256_fs::Bn256Fs;
 use verkle_tree::ipa_fr::utils::{read_field_element_le, write_field_element_le};
 use verkle_tree::ipa_fs::transcript::{from_bytes_le, to_bytes_le};
 
-use crate::circuit::num::baby_ecc::EdwardsPoint;
-use crate::circuit::poseidon_fs::calc_poseidon;
-
-use super::utils::{convert_fr_to_fs, convert_fs_to_fr};
+use crate::circuit::num::jubjub::JubjubEngine;
+use crate::circuit::poseidon::PoseidonHash;
+pub use crate::circuit::transcript::{Transcript, TranscriptHash};
 
 pub fn convert_ff_to_ff_ce<E: JubjubEngine>(value: Bn256Fs) -> anyhow::Result<E::Fs> {
     read_field_element_le::<E::Fs>(&to_bytes_le(&value))
@@ -20,107 +17,58 @@ pub fn convert_ff_ce_to_ff<E: JubjubEngine>(value: E::Fs) -> anyhow::Result<Bn25
     from_bytes_le(&write_field_element_le(&value))
 }
 
-pub trait Transcript<E: JubjubEngine>: Sized + Clone {
-    type Params;
-
-    fn new<CS: ConstraintSystem<E>>(cs: &mut CS, init_state: Self::Params) -> Self;
-    fn commit_field_element<'a, CS: ConstraintSystem<E>>(
-        &mut self,
-        cs: &mut CS,
-        element: &FieldElement<'a, E, E::Fs>,
-    ) -> Result<(), SynthesisError>;
-    fn commit_point<CS: ConstraintSystem<E>>(
-        &mut self,
-        cs: &mut CS,
-        point: &EdwardsPoint<E>,
-    ) -> Result<(), SynthesisError>;
-    fn get_challenge<'a, CS: ConstraintSystem<E>>(
-        &mut self,
-        cs: &mut CS,
-        rns_params: &'a RnsParameters<E, E::Fs>,
-    ) -> Result<FieldElement<'a, E, E::Fs>, SynthesisError>;
-    fn into_params(self) -> Self::Params;
-}
-
+/// `H` decides the hash absorbing the committed values,
+/// which is Poseidon by default and can be `Poseidon2Hash`.
 #[derive(Clone)]
-pub struct WrappedTranscript<E>
+pub struct WrappedTranscript<E, H = PoseidonHash>
 where
     E: JubjubEngine,
 {
     state: AllocatedNum<E>,
+    _hash: std::marker::PhantomData<H>,
 }
 
-impl<E> Transcript<E> for WrappedTranscript<E>
+impl<E, H> Transcript<E> for WrappedTranscript<E, H>
 where
     E: JubjubEngine,
+    H: TranscriptHash,
 {
     type Params = AllocatedNum<E>;
 
-    fn new<CS: ConstraintSystem<E>>(_cs: &mut CS, init_state: AllocatedNum<E>) -> Self {
-        Self { state: init_state }
+    fn new<CS: ConstraintSystem<E>>(
+        _cs: &mut CS,
+        init_state: AllocatedNum<E>,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            state: init_state,
+            _hash: std::marker::PhantomData,
+        })
     }
 
-    fn commit_field_element<'a, CS: ConstraintSystem<E>>(
-        &mut self,
-        cs: &mut CS,
-        element: &FieldElement<'a, E, E::Fs>,
-    ) -> Result<(), SynthesisError> {
-        let element_fr = element
-            .get_field_value()
-            .map(|e| convert_fs_to_fr::<E>(&e).unwrap());
-        let wrapped_element = AllocatedNum::<E>::alloc(cs, || {
-            element_fr.ok_or(SynthesisError::UnconstrainedVariable)
-        })?;
-        self.commit_alloc_num(cs, &wrapped_element)?;
-
-        Ok(())
-    }
-
-    /// Commit a `WP` value.
-
-    fn commit_point<CS: ConstraintSystem<E>>(
+    fn commit_alloc_num<CS: ConstraintSystem<E>>(
         &mut self,
         cs: &mut CS,
-        point: &EdwardsPoint<E>,
+        element: &AllocatedNum<E>,
     ) -> Result<(), SynthesisError> {
-        let point_x = point.get_x();
-        self.commit_alloc_num(cs, point_x)?;
-        let point_y = point.get_y();
-        self.commit_alloc_num(cs, point_y)?;
+        let inputs = vec![self.state, *element];
+        self.state = H::hash(cs, &inputs)?;
 
         Ok(())
     }
 
-    fn get_challenge<'a, CS: ConstraintSystem<E>>(
+    fn get_challenge<CS: ConstraintSystem<E>>(
         &mut self,
-        cs: &mut CS,
-        rns_params: &'a RnsParameters<E, E::Fs>,
-    ) -> Result<FieldElement<'a, E, E::Fs>, SynthesisError> {
-        let result = convert_fr_to_fs(cs, &self.state).unwrap();
-        let wrapped_result = FieldElement::new_allocated_in_field(cs, result, rns_params)?;
-
-        Ok(wrapped_result)
+        _cs: &mut CS,
+    ) -> Result<AllocatedNum<E>, SynthesisError> {
+        Ok(self.state)
     }
 
     fn into_params(self) -> AllocatedNum<E> {
         self.state
     }
-}
 
-impl<E> WrappedTranscript<E>
-where
-    E: JubjubEngine,
-{
-    /// Commit a `AllocatedNum` value.
-    fn commit_alloc_num<CS: ConstraintSystem<E>>(
-        &mut self,
-        cs: &mut CS,
-        element: &AllocatedNum<E>,
-    ) -> Result<(), SynthesisError> {
-        let inputs = vec![self.state, *element];
-        self.state = calc_poseidon::<E, CS>(cs, &inputs)?;
-
-        Ok(())
+    fn used_gates() -> Vec<Box<dyn GateInternal<E>>> {
+        H::used_gates::<E>()
     }
 }
 
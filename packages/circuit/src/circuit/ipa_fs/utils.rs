@@ -1,33 +1,24 @@
 use std::io::{Error, ErrorKind};
 
-use franklin_crypto::babyjubjub::JubjubEngine;
 use franklin_crypto::bellman::plonk::better_better_cs::cs::ConstraintSystem;
 use franklin_crypto::bellman::{PrimeField, SynthesisError};
 use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
-use franklin_crypto::plonk::circuit::bigint::field::FieldElement;
+use franklin_crypto::plonk::circuit::bigint::field::{FieldElement, RnsParameters};
 
+use crate::circuit::debug::enter_namespace;
 use crate::circuit::num::baby_ecc::EdwardsPoint;
-use crate::circuit::num::convert_bits_le;
-
-const FS_REPR_3_MASK: u64 = 0x03FFFFFFFFFFFFFF; // (250 - 192) bits
+use crate::circuit::num::jubjub::JubjubEngine;
+use crate::circuit::num::{convert_bits_le, truncate_field_element, truncate_into_field_element};
+use crate::error::CircuitError;
 
+/// Keeps the lower `E::Fs::CAPACITY` bits of `value`, which are 250 bits over BN256.
 pub fn convert_fr_to_fs<E: JubjubEngine, CS: ConstraintSystem<E>>(
     _cs: &mut CS,
     value: &AllocatedNum<E>,
 ) -> anyhow::Result<Option<E::Fs>> {
-    let result = if let Some(value) = value.get_value() {
-        let raw_value = value.into_repr();
-        let mut raw_result = <E::Fs as PrimeField>::Repr::default();
-        raw_result.as_mut()[0] = raw_value.as_ref()[0];
-        raw_result.as_mut()[1] = raw_value.as_ref()[1];
-        raw_result.as_mut()[2] = raw_value.as_ref()[2];
-        raw_result.as_mut()[3] = raw_value.as_ref()[3] & FS_REPR_3_MASK;
-        let result = E::Fs::from_repr(raw_result)?;
-
-        Some(result)
-    } else {
-        None
-    };
+    let result = value
+        .get_value()
+        .map(|value| truncate_field_element::<E::Fr, E::Fs>(&value));
 
     Ok(result)
 }
@@ -43,6 +34,19 @@ pub fn convert_fs_to_fr<E: JubjubEngine>(value: &E::Fs) -> anyhow::Result<E::Fr>
     Ok(result)
 }
 
+/// Maps a commitment to the scalar opened in its parent node.
+///
+/// This is the same map as the native tree uses: the x-coordinate is truncated
+/// to its lower `E::Fs::CAPACITY` bits (see `convert_fr_to_fs`), so the result always fits in `E::Fs`.
+/// The x-coordinate is decomposed into its canonical bits, so the scalar cannot be chosen by the prover.
+pub fn commitment_to_scalar<'a, E: JubjubEngine, CS: ConstraintSystem<E>>(
+    cs: &mut CS,
+    commitment: &EdwardsPoint<E>,
+    rns_params: &'a RnsParameters<E, E::Fs>,
+) -> Result<FieldElement<'a, E, E::Fs>, SynthesisError> {
+    truncate_into_field_element(cs, commitment.get_x(), rns_params)
+}
+
 // Computes c[i] = a[i] + b[i] * x
 // returns c
 // panics if len(a) != len(b)
@@ -70,13 +74,13 @@ pub fn fold_scalars<'a, E: JubjubEngine, CS: ConstraintSystem<E>>(
     a: &[FieldElement<'a, E, E::Fs>],
     b: &[FieldElement<'a, E, E::Fs>],
     x: &FieldElement<'a, E, E::Fs>,
-) -> anyhow::Result<Vec<FieldElement<'a, E, E::Fs>>> {
+) -> Result<Vec<FieldElement<'a, E, E::Fs>>, CircuitError> {
     if a.len() != b.len() {
-        anyhow::bail!(
+        return Err(CircuitError::InvalidInputShape(format!(
             "two vectors must have the same lengths, {} != {}",
             a.len(),
             b.len()
-        );
+        )));
     }
 
     let mut result = b.to_vec();
@@ -104,6 +108,8 @@ pub fn fold_points<'a, E: JubjubEngine, CS: ConstraintSystem<E>>(
         return Err(Error::new(ErrorKind::InvalidData, "slices not equal length").into());
     }
 
+    let mut guard = enter_namespace(cs, "fold_points");
+    let cs = &mut *guard;
     let mut result = b.to_vec();
     for i in 0..b.len() {
         let x_bits = convert_bits_le(cs, x.clone(), Some(E::Fs::NUM_BITS as usize))?;
@@ -1,5 +1,9 @@
 pub mod utils;
 
+/// This is the namespaces of gates, the satisfiability check reporting the first unsatisfied gate
+/// and the profiler counting the gates in each namespace.
+pub mod debug;
+
 pub mod num;
 
 pub mod sample;
@@ -9,7 +13,11 @@ pub mod discrete_log;
 /// This is the circuit implementation of the Poseidon hash verification.
 pub mod poseidon;
 
-pub mod poseidon_fs;
+/// This is the circuit implementation of the Poseidon2 permutation.
+pub mod poseidon2;
+
+/// This is the transcript shared by the IPA and batch proof verification.
+pub mod transcript;
 
 /// This is the circuit implementation of the IPA verification.
 pub mod ipa_fr;
@@ -1,5 +1,4 @@
 use franklin_crypto::{
-    babyjubjub::{edwards, FixedGenerators, JubjubEngine, JubjubParams},
     bellman::{
         pairing::{ff::Field, Engine},
         plonk::better_better_cs::cs::{ArithmeticTerm, ConstraintSystem, MainGateTerm},
@@ -12,6 +11,9 @@ use franklin_crypto::{
     },
 };
 
+use crate::circuit::debug::enter_namespace;
+
+use super::jubjub::{JubjubEngine, JubjubParams, JubjubPoint};
 use super::lookup::lookup3_xy;
 
 #[derive(Clone)]
@@ -22,9 +24,11 @@ pub struct EdwardsPoint<E: Engine> {
 
 /// Perform a fixed-base scalar multiplication with
 /// `by` being in little-endian bit order.
+///
+/// `generators` are the windows of the base, e.g. `params.circuit_generators(base)` of franklin-crypto.
 pub fn fixed_base_multiplication<E, CS>(
     cs: &mut CS,
-    base: FixedGenerators,
+    generators: &[Vec<(E::Fr, E::Fr)>],
     by: &[Boolean],
     params: &E::Params,
 ) -> Result<EdwardsPoint<E>, SynthesisError>
@@ -35,11 +39,7 @@ where
     // Represents the result of the multiplication
     let mut result = None;
 
-    for (_, (chunk, window)) in by
-        .chunks(3)
-        .zip(params.circuit_generators(base).iter())
-        .enumerate()
-    {
+    for (_, (chunk, window)) in by.chunks(3).zip(generators.iter()).enumerate() {
         let chunk_a = chunk
             .get(0)
             .copied()
@@ -126,9 +126,9 @@ impl<E: JubjubEngine> EdwardsPoint<E> {
 
     /// This 'witnesses' a point inside the constraint system.
     /// It guarantees the point is on the curve.
-    pub fn witness<Order, CS>(
+    pub fn witness<CS>(
         cs: &mut CS,
-        p: Option<edwards::Point<E, Order>>,
+        p: Option<E::Point>,
         params: &E::Params,
     ) -> Result<Self, SynthesisError>
     where
@@ -215,6 +215,9 @@ impl<E: JubjubEngine> EdwardsPoint<E> {
     where
         CS: ConstraintSystem<E>,
     {
+        let mut guard = enter_namespace(cs, "scalar mul");
+        let cs = &mut *guard;
+
         // Represents the current "magnitude" of the base
         // that we're operating over. Starts at self,
         // then 2*self, then 4*self, ...
@@ -261,8 +264,8 @@ impl<E: JubjubEngine> EdwardsPoint<E> {
         let x2 = x.square(cs)?;
         let y2 = y.square(cs)?;
         let x2y2 = x2.mul(cs, &y2)?;
-        let dx2y2 = x2y2.mul_constant(cs, *params.edwards_d())?;
-        let ax2 = x2.mul_constant(cs, *params.edwards_a())?;
+        let dx2y2 = x2y2.mul_constant(cs, params.edwards_d())?;
+        let ax2 = x2.mul_constant(cs, params.edwards_a())?;
 
         // cs.enforce(
         //     || "on curve check",
@@ -305,7 +308,7 @@ impl<E: JubjubEngine> EdwardsPoint<E> {
         let c = AllocatedNum::alloc(cs, || {
             let mut t0 = *a.get_value().get()?;
             t0.square();
-            t0.mul_assign(params.edwards_d());
+            t0.mul_assign(&params.edwards_d());
 
             Ok(t0)
         })?;
@@ -320,7 +323,7 @@ impl<E: JubjubEngine> EdwardsPoint<E> {
             let mut self_daa_term = ArithmeticTerm::from_variable(a.get_variable());
             let c_term = ArithmeticTerm::from_variable(c.get_variable());
             self_daa_term = self_daa_term.mul_by_variable(a.get_variable());
-            self_daa_term.scale(params.edwards_d());
+            self_daa_term.scale(&params.edwards_d());
 
             let mut term = MainGateTerm::new();
             term.add_assign(self_daa_term);
@@ -386,7 +389,7 @@ impl<E: JubjubEngine> EdwardsPoint<E> {
             let mut t0 = *u.get_value().get()?;
 
             let mut u0 = *t.get_value().get()?;
-            u0.mul_assign(params.edwards_a());
+            u0.mul_assign(&params.edwards_a());
 
             t0.sub_assign(&u0);
 
@@ -427,9 +430,9 @@ impl<E: JubjubEngine> EdwardsPoint<E> {
             let one_minus_c_times_y3_term = y3_term.mul_by_variable(one_minus_c);
 
             let mut term3 = MainGateTerm::new();
-            let a_times_t = t.mul_constant(cs, *params.edwards_a())?;
+            let a_times_t = t.mul_constant(cs, params.edwards_a())?;
             let mut a_times_t_term = ArithmeticTerm::from_variable(t.get_variable());
-            a_times_t_term.scale(params.edwards_a());
+            a_times_t_term.scale(&params.edwards_a());
             let u_term = ArithmeticTerm::from_variable(u.get_variable());
             let u_minus_at_term =
                 ArithmeticTerm::from_variable(u.sub(cs, &a_times_t)?.get_variable());
@@ -464,7 +467,7 @@ impl<E: JubjubEngine> EdwardsPoint<E> {
         let b = other.x.mul(cs, &self.y)?;
 
         // Compute T0 = -a * x1 + y1
-        let self_x_a = self.x.mul_constant(cs, *params.edwards_a())?;
+        let self_x_a = self.x.mul_constant(cs, params.edwards_a())?;
         let t0 = self.y.sub(cs, &self_x_a)?;
         // let t0 = AllocatedNum::alloc(cs, || {
         //     let mut t0 = *self.x.get_value().get()?;
@@ -514,7 +517,7 @@ impl<E: JubjubEngine> EdwardsPoint<E> {
         //     |lc| lc + b.get_variable(),
         //     |lc| lc + c.get_variable(),
         // );
-        let c = a.mul(cs, &b)?.mul_constant(cs, *params.edwards_d())?;
+        let c = a.mul(cs, &b)?.mul_constant(cs, params.edwards_d())?;
         // {
         //     let da_term =
         //         ArithmeticTerm::from_variable_and_coeff(a.get_variable(), *params.edwards_d());
@@ -613,7 +616,7 @@ impl<E: JubjubEngine> EdwardsPoint<E> {
                 return Err(SynthesisError::DivisionByZero);
             }
         }
-        let aa = a.mul_constant(cs, *params.edwards_a())?;
+        let aa = a.mul_constant(cs, params.edwards_a())?;
         // let one_minus_c_times_y3 = y3.mul(cs, &one_minus_c)?;
         // t.add(cs, &aa)?
         //     .sub(cs, &b)?
@@ -665,7 +668,7 @@ impl<E: JubjubEngine> MontgomeryPoint<E> {
         // Compute u = (scale*x) / y
         let u = AllocatedNum::alloc(cs, || {
             let mut t0 = *self.x.get_value().get()?;
-            t0.mul_assign(params.scale());
+            t0.mul_assign(&params.scale());
 
             match self.y.get_value().get()?.inverse() {
                 Some(invy) => {
@@ -687,7 +690,7 @@ impl<E: JubjubEngine> MontgomeryPoint<E> {
             let y_term = ArithmeticTerm::from_variable(self.y.get_variable().get_variable());
             let y_times_u_term = y_term.mul_by_variable(u.get_variable());
             let mut x_term = ArithmeticTerm::from_variable(self.x.get_variable().get_variable());
-            x_term.scale(params.scale());
+            x_term.scale(&params.scale());
 
             let mut term = MainGateTerm::new();
             term.add_assign(y_times_u_term);
@@ -811,7 +814,7 @@ impl<E: JubjubEngine> MontgomeryPoint<E> {
         let xprime = AllocatedNum::alloc(cs, || {
             let mut t0 = *lambda.get_value().get()?;
             t0.square();
-            t0.sub_assign(params.montgomery_a());
+            t0.sub_assign(&params.montgomery_a());
             t0.sub_assign(self.x.get_value().get()?);
             t0.sub_assign(other.x.get_value().get()?);
 
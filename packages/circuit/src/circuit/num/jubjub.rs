@@ -0,0 +1,182 @@
+use franklin_crypto::babyjubjub::{self, JubjubBn256};
+use franklin_crypto::bellman::pairing::bls12_381::Bls12;
+use franklin_crypto::bellman::pairing::bn256::Bn256;
+use franklin_crypto::bellman::pairing::Engine;
+use franklin_crypto::bellman::{Field, PrimeField, PrimeFieldRepr, SqrtField};
+use franklin_crypto::jubjub::{self, JubjubBls12};
+
+/// The engines whose scalar field `Fr` is the base field of a twisted Edwards curve
+/// `a x^2 + y^2 = 1 + d x^2 y^2`, which are Baby Jubjub over BN256 and Jubjub over BLS12-381.
+///
+/// franklin-crypto has two unrelated traits for them: `babyjubjub::JubjubEngine`, implemented only for `Bn256`,
+/// and `jubjub::JubjubEngine`, implemented only for `Bls12`.
+/// The gadgets are written against this trait, which is implemented for both.
+pub trait JubjubEngine: Engine {
+    /// The scalar field of the curve.
+    type Fs: PrimeField + SqrtField;
+    type Params: JubjubParams<Self>;
+    /// The points of the curve, which are not necessarily in the prime-order subgroup.
+    type Point: JubjubPoint<Self>;
+}
+
+/// The parameters of the curve which the gadgets use.
+pub trait JubjubParams<E: Engine>: Sized {
+    /// The coefficient `a` of the twisted Edwards form.
+    fn edwards_a(&self) -> E::Fr;
+
+    /// The coefficient `d` of the twisted Edwards form.
+    fn edwards_d(&self) -> E::Fr;
+
+    /// The coefficient `A` of the Montgomery form `y^2 = x^3 + A x^2 + x`.
+    fn montgomery_a(&self) -> E::Fr;
+
+    /// The scaling factor of the map from the Montgomery form to the twisted Edwards form.
+    fn scale(&self) -> E::Fr;
+}
+
+/// The native arithmetic of `JubjubEngine::Point`.
+pub trait JubjubPoint<E: JubjubEngine>: Sized + Clone + PartialEq {
+    /// The neutral element `(0, 1)`.
+    fn zero() -> Self;
+
+    /// Solves the curve equation for `x` and takes the root whose parity is `sign`.
+    fn get_for_y(y: E::Fr, sign: bool, params: &E::Params) -> Option<Self>;
+
+    fn into_xy(&self) -> (E::Fr, E::Fr);
+
+    fn negate(&self) -> Self;
+
+    fn add(&self, other: &Self, params: &E::Params) -> Self;
+
+    fn mul<S: Into<<E::Fs as PrimeField>::Repr>>(&self, scalar: S, params: &E::Params) -> Self;
+
+    /// Multiplies the point by the cofactor 8, which maps it into the prime-order subgroup.
+    fn mul_by_cofactor(&self, params: &E::Params) -> Self;
+
+    /// Returns the point `(x, y)` if it is on the curve.
+    fn from_xy(x: E::Fr, y: E::Fr, params: &E::Params) -> Option<Self> {
+        let point = Self::get_for_y(y, x.into_repr().is_odd(), params)?;
+
+        (point.into_xy().0 == x).then_some(point)
+    }
+}
+
+impl JubjubEngine for Bn256 {
+    type Fs = babyjubjub::fs::Fs;
+    type Params = JubjubBn256;
+    type Point = babyjubjub::edwards::Point<Bn256, babyjubjub::Unknown>;
+}
+
+impl JubjubParams<Bn256> for JubjubBn256 {
+    fn edwards_a(&self) -> <Bn256 as Engine>::Fr {
+        *babyjubjub::JubjubParams::edwards_a(self)
+    }
+
+    fn edwards_d(&self) -> <Bn256 as Engine>::Fr {
+        *babyjubjub::JubjubParams::edwards_d(self)
+    }
+
+    fn montgomery_a(&self) -> <Bn256 as Engine>::Fr {
+        *babyjubjub::JubjubParams::montgomery_a(self)
+    }
+
+    fn scale(&self) -> <Bn256 as Engine>::Fr {
+        *babyjubjub::JubjubParams::scale(self)
+    }
+}
+
+impl JubjubPoint<Bn256> for babyjubjub::edwards::Point<Bn256, babyjubjub::Unknown> {
+    fn zero() -> Self {
+        babyjubjub::edwards::Point::zero()
+    }
+
+    fn get_for_y(y: <Bn256 as Engine>::Fr, sign: bool, params: &JubjubBn256) -> Option<Self> {
+        babyjubjub::edwards::Point::get_for_y(y, sign, params)
+    }
+
+    fn into_xy(&self) -> (<Bn256 as Engine>::Fr, <Bn256 as Engine>::Fr) {
+        babyjubjub::edwards::Point::into_xy(self)
+    }
+
+    fn negate(&self) -> Self {
+        babyjubjub::edwards::Point::negate(self)
+    }
+
+    fn add(&self, other: &Self, params: &JubjubBn256) -> Self {
+        babyjubjub::edwards::Point::add(self, other, params)
+    }
+
+    fn mul<S: Into<<babyjubjub::fs::Fs as PrimeField>::Repr>>(
+        &self,
+        scalar: S,
+        params: &JubjubBn256,
+    ) -> Self {
+        babyjubjub::edwards::Point::mul(self, scalar, params)
+    }
+
+    fn mul_by_cofactor(&self, params: &JubjubBn256) -> Self {
+        babyjubjub::edwards::Point::mul_by_cofactor(self, params).into()
+    }
+}
+
+impl JubjubEngine for Bls12 {
+    type Fs = jubjub::fs::Fs;
+    type Params = JubjubBls12;
+    type Point = jubjub::edwards::Point<Bls12, jubjub::Unknown>;
+}
+
+/// `jubjub::JubjubParams` has no `edwards_a`, since it is always `-1` for Jubjub.
+impl JubjubParams<Bls12> for JubjubBls12 {
+    fn edwards_a(&self) -> <Bls12 as Engine>::Fr {
+        let mut minus_one = <Bls12 as Engine>::Fr::one();
+        minus_one.negate();
+
+        minus_one
+    }
+
+    fn edwards_d(&self) -> <Bls12 as Engine>::Fr {
+        *jubjub::JubjubParams::edwards_d(self)
+    }
+
+    fn montgomery_a(&self) -> <Bls12 as Engine>::Fr {
+        *jubjub::JubjubParams::montgomery_a(self)
+    }
+
+    fn scale(&self) -> <Bls12 as Engine>::Fr {
+        *jubjub::JubjubParams::scale(self)
+    }
+}
+
+impl JubjubPoint<Bls12> for jubjub::edwards::Point<Bls12, jubjub::Unknown> {
+    fn zero() -> Self {
+        jubjub::edwards::Point::zero()
+    }
+
+    fn get_for_y(y: <Bls12 as Engine>::Fr, sign: bool, params: &JubjubBls12) -> Option<Self> {
+        jubjub::edwards::Point::get_for_y(y, sign, params)
+    }
+
+    fn into_xy(&self) -> (<Bls12 as Engine>::Fr, <Bls12 as Engine>::Fr) {
+        jubjub::edwards::Point::into_xy(self)
+    }
+
+    fn negate(&self) -> Self {
+        jubjub::edwards::Point::negate(self)
+    }
+
+    fn add(&self, other: &Self, params: &JubjubBls12) -> Self {
+        jubjub::edwards::Point::add(self, other, params)
+    }
+
+    fn mul<S: Into<<jubjub::fs::Fs as PrimeField>::Repr>>(
+        &self,
+        scalar: S,
+        params: &JubjubBls12,
+    ) -> Self {
+        jubjub::edwards::Point::mul(self, scalar, params)
+    }
+
+    fn mul_by_cofactor(&self, params: &JubjubBls12) -> Self {
+        jubjub::edwards::Point::mul_by_cofactor(self, params).into()
+    }
+}
@@ -1,22 +1,29 @@
 pub mod baby_ecc;
+pub mod jubjub;
 pub mod lookup;
 
 use franklin_crypto::{
-    babyjubjub::{edwards, JubjubEngine},
     bellman::{
         pairing::ff::Field, plonk::better_better_cs::cs::ConstraintSystem, BitIterator, Engine,
-        PrimeField, SynthesisError,
+        PrimeField, PrimeFieldRepr, SynthesisError,
     },
     plonk::circuit::{
-        allocated_num::AllocatedNum,
-        bigint::{bigint::fe_to_biguint, field::FieldElement},
+        allocated_num::{AllocatedNum, Num},
+        bigint::{
+            bigint::fe_to_biguint,
+            field::{FieldElement, RnsParameters},
+        },
         boolean::{AllocatedBit, Boolean},
+        linear_combination::LinearCombination,
     },
 };
 use num_bigint::BigUint;
 use num_traits::Zero;
 
+use crate::circuit::debug::enter_namespace;
+
 use self::baby_ecc::EdwardsPoint;
+use self::jubjub::{JubjubEngine, JubjubPoint};
 
 pub trait SomeField<F: Field> {
     fn add(&self, other: &Self) -> Self;
@@ -105,7 +112,11 @@ where
         default_bit_length
     };
 
-    let bits = field_into_allocated_bits_le_fixed(cs, value.clone(), default_bit_length)?;
+    let bits = field_into_allocated_bits_le_fixed(
+        &mut *enter_namespace(cs, "range check"),
+        value.clone(),
+        default_bit_length,
+    )?;
     let result = bits
         .clone()
         .into_iter()
@@ -205,9 +216,9 @@ pub fn split_into_fixed_number_of_bits<F: PrimeField>(
     }
 }
 
-pub fn allocate_edwards_point<E: JubjubEngine, CS: ConstraintSystem<E>, Subgroup>(
+pub fn allocate_edwards_point<E: JubjubEngine, CS: ConstraintSystem<E>>(
     cs: &mut CS,
-    value: &Option<edwards::Point<E, Subgroup>>,
+    value: &Option<E::Point>,
     jubjub_params: &E::Params,
 ) -> Result<EdwardsPoint<E>, SynthesisError> {
     let raw = if let Some(c) = value {
@@ -223,3 +234,140 @@ pub fn allocate_edwards_point<E: JubjubEngine, CS: ConstraintSystem<E>, Subgroup
 
     Ok(result)
 }
+
+/// Keeps the lower `F::CAPACITY` bits of `value`, so the result always fits in `F`.
+pub fn truncate_field_element<V: PrimeField, F: PrimeField>(value: &V) -> F {
+    let num_bits = std::cmp::min(F::CAPACITY, V::NUM_BITS) as usize;
+    let raw_value = value.into_repr();
+    let mut raw_result = F::Repr::default();
+    for (i, (r, &v)) in raw_result
+        .as_mut()
+        .iter_mut()
+        .zip(raw_value.as_ref())
+        .enumerate()
+    {
+        let offset = 64 * i;
+        *r = if offset + 64 <= num_bits {
+            v
+        } else if offset < num_bits {
+            v & ((1u64 << (num_bits - offset)) - 1)
+        } else {
+            0
+        };
+    }
+
+    F::from_repr(raw_result).unwrap()
+}
+
+/// Decomposes `value` into `E::Fr::NUM_BITS` bits in little-endian order.
+/// `into_bits_le` also accepts the bits of `value + p` if they fit,
+/// so the bits are enforced to be the canonical ones.
+pub fn into_canonical_bits_le<E: Engine, CS: ConstraintSystem<E>>(
+    cs: &mut CS,
+    value: &AllocatedNum<E>,
+) -> Result<Vec<Boolean>, SynthesisError> {
+    let bits = value.into_bits_le(cs, Some(E::Fr::NUM_BITS as usize))?;
+    enforce_bits_in_field::<E, CS, E::Fr>(cs, &bits)?;
+
+    Ok(bits)
+}
+
+/// Enforces the `F::NUM_BITS` little-endian `bits` to be at most `F::char() - 1`.
+pub fn enforce_bits_in_field<E: Engine, CS: ConstraintSystem<E>, F: PrimeField>(
+    cs: &mut CS,
+    bits: &[Boolean],
+) -> Result<(), SynthesisError> {
+    assert_eq!(bits.len(), F::NUM_BITS as usize);
+
+    let mut char_minus_one = F::char();
+    char_minus_one.sub_noborrow(&1u64.into());
+    let num_leading_zeros = 64 * char_minus_one.as_ref().len() - F::NUM_BITS as usize;
+
+    // `is_equal` is whether the bits scanned so far equal those of `p - 1`.
+    let mut is_equal = Boolean::constant(true);
+    for (bit, char_bit) in bits
+        .iter()
+        .rev()
+        .zip(BitIterator::new(char_minus_one).skip(num_leading_zeros))
+    {
+        if char_bit {
+            is_equal = Boolean::and(cs, &is_equal, bit)?;
+        } else {
+            let is_greater = Boolean::and(cs, &is_equal, bit)?;
+            Boolean::enforce_equal(cs, &is_greater, &Boolean::constant(false))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps the lower `F::CAPACITY` bits of `value`, so the result always fits in `F`.
+pub fn truncate_into_field_element<'a, E: Engine, CS: ConstraintSystem<E>, F: PrimeField>(
+    cs: &mut CS,
+    value: &AllocatedNum<E>,
+    rns_params: &'a RnsParameters<E, F>,
+) -> Result<FieldElement<'a, E, F>, SynthesisError> {
+    let num_bits = std::cmp::min(F::CAPACITY, E::Fr::NUM_BITS) as usize;
+    let raw_result = value
+        .get_value()
+        .map(|value| truncate_field_element::<E::Fr, F>(&value));
+    let result = FieldElement::new_allocated_in_field(cs, raw_result, rns_params)?;
+
+    // Enforce SUM bits[i] * 2^i (i < num_bits) == SUM limbs[j] * 2^shift[j].
+    let two = E::Fr::from_str("2").unwrap();
+    let bits = into_canonical_bits_le(cs, value)?;
+    let mut lc = LinearCombination::zero();
+    let mut coeff = E::Fr::one();
+    for bit in bits.iter().take(num_bits) {
+        lc.add_assign_boolean_with_coeff(bit, coeff);
+        coeff.double();
+    }
+    let mut shift = 0usize;
+    for (limb, &width) in result
+        .clone()
+        .into_limbs()
+        .into_iter()
+        .zip(rns_params.binary_limbs_bit_widths.iter())
+    {
+        let mut minus_coeff = two.pow(&[shift as u64]);
+        minus_coeff.negate();
+        lc.add_assign_number_with_coeff(&limb.into_num(), minus_coeff);
+        shift += width;
+    }
+    lc.enforce_zero(cs)?;
+
+    Ok(result)
+}
+
+/// Packs a field element whose modulus is smaller than `E::Fr` into one number.
+/// The element is reduced first, so the result equals its canonical value.
+pub fn compose_field_element<'a, E: Engine, CS: ConstraintSystem<E>, F: PrimeField>(
+    cs: &mut CS,
+    value: &FieldElement<'a, E, F>,
+) -> Result<AllocatedNum<E>, SynthesisError> {
+    assert!(
+        F::NUM_BITS <= E::Fr::CAPACITY,
+        "the field element does not fit in a single number"
+    );
+
+    let value = value
+        .clone()
+        .force_reduce_into_field(cs)?
+        .enforce_is_normalized(cs)?;
+    let limb_widths = value.representation_params.binary_limbs_bit_widths.clone();
+
+    let two = E::Fr::from_str("2").unwrap();
+    let mut lc = LinearCombination::zero();
+    let mut shift = 0usize;
+    for (limb, width) in value.into_limbs().into_iter().zip(limb_widths) {
+        lc.add_assign_number_with_coeff(&limb.into_num(), two.pow(&[shift as u64]));
+        shift += width;
+    }
+
+    let result = match lc.into_num(cs)? {
+        Num::Variable(result) => result,
+        Num::Constant(value) => AllocatedNum::alloc_cnst(cs, value)?,
+    };
+
+    Ok(result)
+}
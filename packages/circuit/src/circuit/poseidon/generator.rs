@@ -0,0 +1,343 @@
+use franklin_crypto::bellman::pairing::Engine;
+use franklin_crypto::bellman::{Field, PrimeField};
+
+use crate::error::CircuitError;
+
+use super::grain::GrainLfsr;
+use super::SparseMatrix;
+
+/// The partial rounds which neptune chooses for widths 2 to 17 with `full_rounds` = 8,
+/// i.e. the output of `neptune::round_numbers` for 128-bit security.
+const NEPTUNE_PARTIAL_ROUNDS: [usize; 16] = [
+    55, 55, 56, 56, 56, 56, 57, 57, 57, 57, 57, 57, 57, 57, 59, 59,
+];
+
+/// The parameters from which `PoseidonConstants` are generated.
+///
+/// Only `alpha` = 5 is supported, since the S-box gadgets constrain `x^5`.
+/// The numbers of rounds are not derived from the security level,
+/// so they have to be chosen by the caller or taken from neptune by `for_arity`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoseidonParams {
+    pub width: usize,
+    pub full_rounds: usize,
+    pub partial_rounds: usize,
+    pub alpha: u64,
+}
+
+impl PoseidonParams {
+    /// The largest arity which `for_arity` supports.
+    pub const MAX_ARITY: usize = NEPTUNE_PARTIAL_ROUNDS.len();
+
+    /// The parameters which neptune uses for `arity` inputs, which must be between 1 and `MAX_ARITY`.
+    pub fn for_arity(arity: usize) -> Result<Self, CircuitError> {
+        if !(1..=Self::MAX_ARITY).contains(&arity) {
+            return Err(CircuitError::InvalidInputShape(format!(
+                "the arity of Poseidon should be between 1 and {}, but {}",
+                Self::MAX_ARITY,
+                arity
+            )));
+        }
+
+        Ok(Self {
+            width: arity + 1,
+            full_rounds: 8,
+            partial_rounds: NEPTUNE_PARTIAL_ROUNDS[arity - 1],
+            alpha: 5,
+        })
+    }
+}
+
+fn from_u64<F: PrimeField>(value: u64) -> F {
+    F::from_repr(F::Repr::from(value)).unwrap()
+}
+
+/// Generate `(full_rounds + partial_rounds) * width` round constants with the Grain LFSR.
+///
+/// neptune initializes the LFSR with `sbox` = 1 for `x^alpha`, so the same bits are used here.
+pub fn generate_round_constants<F: PrimeField>(params: &PoseidonParams) -> Vec<F> {
+    let mut lfsr = GrainLfsr::new(
+        1,
+        1,
+        F::NUM_BITS as u64,
+        params.width as u64,
+        params.full_rounds as u64,
+        params.partial_rounds as u64,
+    );
+    let num_constants = (params.full_rounds + params.partial_rounds) * params.width;
+
+    (0..num_constants)
+        .map(|_| lfsr.next_field_element::<F>())
+        .collect()
+}
+
+/// Generate the Cauchy matrix `m[i][j] = 1 / (x_i + y_j)` with `x_i = i` and `y_j = width + j`.
+pub fn generate_mds<F: PrimeField>(width: usize) -> Vec<Vec<F>> {
+    (0..width)
+        .map(|i| {
+            (0..width)
+                .map(|j| {
+                    from_u64::<F>((i + width + j) as u64)
+                        .inverse()
+                        .expect("x_i + y_j must not be zero")
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns `m * v`.
+pub fn apply_matrix<F: PrimeField>(m: &[Vec<F>], v: &[F]) -> Vec<F> {
+    m.iter()
+        .map(|row| {
+            row.iter().zip(v.iter()).fold(F::zero(), |mut sum, (a, b)| {
+                let mut tmp = *a;
+                tmp.mul_assign(b);
+                sum.add_assign(&tmp);
+                sum
+            })
+        })
+        .collect()
+}
+
+/// Returns `a * b`.
+pub fn mat_mul<F: PrimeField>(a: &[Vec<F>], b: &[Vec<F>]) -> Vec<Vec<F>> {
+    a.iter()
+        .map(|row| {
+            (0..b[0].len())
+                .map(|j| {
+                    row.iter()
+                        .zip(b.iter())
+                        .fold(F::zero(), |mut sum, (x, b_row)| {
+                            let mut tmp = *x;
+                            tmp.mul_assign(&b_row[j]);
+                            sum.add_assign(&tmp);
+                            sum
+                        })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the inverse of a square matrix by Gauss-Jordan elimination, or `None` if it is singular.
+pub fn invert<F: PrimeField>(m: &[Vec<F>]) -> Option<Vec<Vec<F>>> {
+    let size = m.len();
+    let mut left = m.to_vec();
+    let mut right = (0..size)
+        .map(|i| {
+            (0..size)
+                .map(|j| if i == j { F::one() } else { F::zero() })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    for column in 0..size {
+        let pivot = (column..size).find(|&row| !left[row][column].is_zero())?;
+        left.swap(column, pivot);
+        right.swap(column, pivot);
+
+        let scale = left[column][column].inverse()?;
+        for value in left[column].iter_mut().chain(right[column].iter_mut()) {
+            value.mul_assign(&scale);
+        }
+
+        let pivot_row = left[column]
+            .iter()
+            .chain(right[column].iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        for row in 0..size {
+            if row == column || left[row][column].is_zero() {
+                continue;
+            }
+
+            let factor = left[row][column];
+            let values = left[row].iter_mut().chain(right[row].iter_mut());
+            for (value, pivot) in values.zip(pivot_row.iter()) {
+                let mut tmp = *pivot;
+                tmp.mul_assign(&factor);
+                value.sub_assign(&tmp);
+            }
+        }
+    }
+
+    Some(right)
+}
+
+/// Factor `(mds_matrix)^(partial_rounds)` into the pre-sparse matrix and one sparse matrix per partial round.
+///
+/// Each step writes the current matrix as `m' * m''`, where `m'` fixes the first element
+/// and `m''` is sparse, and pushes `m'` into the matrix of the previous round.
+pub fn factor_to_sparse_matrices<E: Engine>(
+    mds_matrix: &[Vec<E::Fr>],
+    partial_rounds: usize,
+) -> (Vec<Vec<E::Fr>>, Vec<SparseMatrix<E>>) {
+    let width = mds_matrix.len();
+    let mut current = mds_matrix.to_vec();
+    let mut sparse_matrices = vec![];
+    for _ in 0..partial_rounds {
+        let m_hat = current[1..]
+            .iter()
+            .map(|row| row[1..].to_vec())
+            .collect::<Vec<_>>();
+        let m_hat_inv = invert(&m_hat).expect("the minor of the MDS matrix must be invertible");
+        let w = current[1..].iter().map(|row| row[0]).collect::<Vec<_>>();
+
+        let mut w_hat = vec![current[0][0]];
+        w_hat.extend(apply_matrix(&m_hat_inv, &w));
+        sparse_matrices.push(SparseMatrix {
+            w_hat,
+            v_rest: current[0][1..].to_vec(),
+        });
+
+        let mut m_prime = vec![vec![E::Fr::zero(); width]; width];
+        m_prime[0][0] = E::Fr::one();
+        for (m_prime_row, row) in m_prime.iter_mut().zip(current.iter()).skip(1) {
+            m_prime_row[1..].copy_from_slice(&row[1..]);
+        }
+        current = mat_mul(mds_matrix, &m_prime);
+    }
+    sparse_matrices.reverse();
+
+    (current, sparse_matrices)
+}
+
+/// Move the round constants so that they fit the schedule of `calc_permutation`.
+///
+/// The constants of a full round are added after the S-boxes of the previous round,
+/// and each partial round adds a single constant to the first element.
+pub fn compress_round_constants<F: PrimeField>(
+    params: &PoseidonParams,
+    round_constants: &[F],
+    mds_matrix_inv: &[Vec<F>],
+) -> Vec<F> {
+    let width = params.width;
+    let round_keys = |r: usize| &round_constants[(r * width)..((r + 1) * width)];
+    let half_full_rounds = params.full_rounds / 2;
+
+    // The constants of the first round are unchanged.
+    let mut result = round_keys(0).to_vec();
+    for i in 1..half_full_rounds {
+        result.extend(apply_matrix(mds_matrix_inv, round_keys(i)));
+    }
+
+    // Work backwards from the first full round after the partial rounds,
+    // keeping only the first element of each partial round.
+    let final_round = half_full_rounds + params.partial_rounds;
+    let mut partial_keys = vec![];
+    let mut acc = round_keys(final_round).to_vec();
+    for i in 0..params.partial_rounds {
+        let mut inverted = apply_matrix(mds_matrix_inv, &acc);
+        partial_keys.push(inverted[0]);
+        inverted[0] = F::zero();
+        acc = round_keys(final_round - i - 1)
+            .iter()
+            .zip(inverted.iter())
+            .map(|(a, b)| {
+                let mut tmp = *a;
+                tmp.add_assign(b);
+                tmp
+            })
+            .collect();
+    }
+    result.extend(apply_matrix(mds_matrix_inv, &acc));
+    partial_keys.reverse();
+    result.extend(partial_keys);
+
+    for i in 1..half_full_rounds {
+        result.extend(apply_matrix(mds_matrix_inv, round_keys(final_round + i)));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr};
+    use franklin_crypto::bellman::plonk::better_better_cs::cs::{
+        TrivialAssembly, Width4MainGateWithDNext,
+    };
+    use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
+    use franklin_crypto::plonk::circuit::Width4WithCustomGates;
+    use generic_array::typenum;
+    use verkle_tree::ff_utils::bn256_fr::Bn256Fr;
+    use verkle_tree::ipa_fr::utils::read_field_element_le;
+    use verkle_tree::neptune::Arity;
+
+    use crate::circuit::poseidon::{
+        calc_permutation, permute_native, MainGateSbox, PoseidonConstants,
+    };
+
+    use super::PoseidonParams;
+
+    fn check_generated_constants<A: Arity<Bn256Fr>>() {
+        let arity = A::to_usize();
+        assert_eq!(
+            PoseidonConstants::<Bn256>::new(arity).unwrap(),
+            PoseidonConstants::<Bn256>::from_neptune::<A>(),
+            "arity {} does not match neptune",
+            arity
+        );
+    }
+
+    #[test]
+    fn test_generated_poseidon_constants_match_neptune() {
+        check_generated_constants::<typenum::U1>();
+        check_generated_constants::<typenum::U2>();
+        check_generated_constants::<typenum::U4>();
+        check_generated_constants::<typenum::U8>();
+        check_generated_constants::<typenum::U11>();
+        check_generated_constants::<typenum::U16>();
+    }
+
+    #[test]
+    fn test_unsupported_poseidon_params() -> Result<(), Box<dyn std::error::Error>> {
+        assert!(PoseidonParams::for_arity(0).is_err());
+        assert!(PoseidonParams::for_arity(PoseidonParams::MAX_ARITY + 1).is_err());
+        assert!(PoseidonConstants::<Bn256>::cached(PoseidonParams::MAX_ARITY + 1).is_err());
+
+        let params = PoseidonParams {
+            alpha: 3,
+            ..PoseidonParams::for_arity(2)?
+        };
+        let domain_tag = read_field_element_le::<Fr>(&[7])?;
+        assert!(PoseidonConstants::<Bn256>::generate(&params, domain_tag).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_poseidon_circuit_with_generated_params() -> Result<(), Box<dyn std::error::Error>> {
+        // A width and rounds which neptune does not use.
+        let params = PoseidonParams {
+            width: 5,
+            full_rounds: 10,
+            partial_rounds: 60,
+            alpha: 5,
+        };
+        let domain_tag = read_field_element_le::<Fr>(&[7])?;
+        let constants = PoseidonConstants::<Bn256>::generate(&params, domain_tag)?;
+        assert_eq!(constants.round_constants.len(), 70 * 5);
+        assert_eq!(constants.sparse_matrices.len(), 60);
+
+        let mut elements = (0..params.width)
+            .map(|i| read_field_element_le::<Fr>(&[i as u8, 2]))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        let inputs = elements
+            .iter()
+            .map(|&x| AllocatedNum::alloc(&mut cs, || Ok(x)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let outputs = calc_permutation::<_, _, MainGateSbox>(&mut cs, &inputs, &constants)?;
+        permute_native(&mut elements, &constants);
+        assert_eq!(
+            outputs.iter().map(|x| x.get_value()).collect::<Vec<_>>(),
+            elements.into_iter().map(Some).collect::<Vec<_>>()
+        );
+        assert!(cs.is_satisfied());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use franklin_crypto::bellman::{PrimeField, PrimeFieldRepr};
+
+const STATE_SIZE: usize = 80;
+
+/// The Grain LFSR which generates the round constants of Poseidon and Poseidon2,
+/// as specified in Appendix F of the Poseidon paper.
+#[derive(Clone, Debug)]
+pub struct GrainLfsr {
+    state: [bool; STATE_SIZE],
+    head: usize,
+}
+
+impl GrainLfsr {
+    /// Initialize the LFSR with the parameters of a permutation.
+    ///
+    /// * `field` is 1 for a prime field.
+    /// * `sbox` is 0 for `x^alpha` and 1 for `x^(-1)`.
+    /// * `field_size` is the number of bits of the field modulus.
+    /// * `width` is the number of field elements in the state.
+    pub fn new(
+        field: u64,
+        sbox: u64,
+        field_size: u64,
+        width: u64,
+        full_rounds: u64,
+        partial_rounds: u64,
+    ) -> Self {
+        let mut state = [true; STATE_SIZE];
+        let mut position = 0;
+        for (value, num_bits) in [
+            (field, 2),
+            (sbox, 4),
+            (field_size, 12),
+            (width, 12),
+            (full_rounds, 10),
+            (partial_rounds, 10),
+        ] {
+            assert!(value < (1 << num_bits), "too large parameter: {}", value);
+            for i in (0..num_bits).rev() {
+                state[position] = (value >> i) & 1 == 1;
+                position += 1;
+            }
+        }
+        // The remaining 30 bits are set to one.
+
+        let mut lfsr = Self { state, head: 0 };
+        // Discard the first 160 bits.
+        for _ in 0..160 {
+            lfsr.update();
+        }
+
+        lfsr
+    }
+
+    fn update(&mut self) -> bool {
+        let bit = |i: usize| self.state[(self.head + i) % STATE_SIZE];
+        let new_bit = bit(62) ^ bit(51) ^ bit(38) ^ bit(23) ^ bit(13) ^ bit(0);
+        self.state[self.head] = new_bit;
+        self.head = (self.head + 1) % STATE_SIZE;
+
+        new_bit
+    }
+
+    /// Returns the next output bit.
+    ///
+    /// The bits are generated in pairs, and the second bit is output only if the first bit is one.
+    pub fn next_bit(&mut self) -> bool {
+        loop {
+            let first = self.update();
+            let second = self.update();
+            if first {
+                return second;
+            }
+        }
+    }
+
+    /// Returns the next element of `F`.
+    ///
+    /// `F::NUM_BITS` bits are read in big-endian order, and they are discarded if they are not less than the modulus.
+    pub fn next_field_element<F: PrimeField>(&mut self) -> F {
+        loop {
+            let mut repr = F::Repr::default();
+            for _ in 0..F::NUM_BITS {
+                repr.shl(1);
+                if self.next_bit() {
+                    repr.as_mut()[0] |= 1;
+                }
+            }
+
+            if let Ok(value) = F::from_repr(repr) {
+                return value;
+            }
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::sync::Arc;
+
+use franklin_crypto::bellman::pairing::Engine;
+use franklin_crypto::bellman::plonk::better_better_cs::cs::ConstraintSystem;
+use franklin_crypto::bellman::{Field, SynthesisError};
+use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
+
+use crate::error::CircuitError;
+
+use super::generator::PoseidonParams;
+use super::{calc_permutation, permute_native, MainGateSbox, PoseidonConstants, Sbox};
+
+/// The parameters of a Poseidon sponge.
+///
+/// The width of the permutation is `rate + capacity`, which must be between 2 and 17,
+/// the widths for which `PoseidonParams::for_arity` has parameters.
+/// The first `capacity` elements of the state are never absorbed into nor squeezed out,
+/// and the first of them is initialized with `domain_tag`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpongeParams<E: Engine> {
+    pub rate: usize,
+    pub capacity: usize,
+    pub domain_tag: E::Fr,
+}
+
+impl<E: Engine> SpongeParams<E> {
+    pub fn new(rate: usize, capacity: usize, domain_tag: E::Fr) -> Result<Self, SynthesisError> {
+        if rate == 0 || capacity == 0 || rate + capacity - 1 > PoseidonParams::MAX_ARITY {
+            return Err(CircuitError::InvalidInputShape(format!(
+                "rate and capacity should be positive and their sum should be at most {}, \
+                but rate = {} and capacity = {}",
+                PoseidonParams::MAX_ARITY + 1,
+                rate,
+                capacity
+            ))
+            .into());
+        }
+
+        Ok(Self {
+            rate,
+            capacity,
+            domain_tag,
+        })
+    }
+
+    fn constants(&self) -> Result<Arc<PoseidonConstants<E>>, SynthesisError> {
+        let constants = PoseidonConstants::<E>::cached(self.rate + self.capacity - 1)?;
+
+        Ok(constants)
+    }
+
+    fn initial_state(&self) -> Vec<E::Fr> {
+        let mut state = vec![E::Fr::zero(); self.rate + self.capacity];
+        state[0] = self.domain_tag;
+
+        state
+    }
+}
+
+/// This is the circuit implementation of the Poseidon sponge.
+///
+/// Absorbed elements are added into the rate part of the state, and the permutation is applied
+/// whenever `rate` elements are pending. The first squeeze pads the absorbed elements
+/// with a single one followed by zeros, so that inputs of different lengths give different outputs.
+/// No element can be absorbed after squeezing, and `absorb` returns an error then.
+#[derive(Clone)]
+pub struct PoseidonSponge<E: Engine, S = MainGateSbox> {
+    params: SpongeParams<E>,
+    constants: Arc<PoseidonConstants<E>>,
+    state: Vec<AllocatedNum<E>>,
+    pending: Vec<AllocatedNum<E>>,
+    squeezed: Option<usize>,
+    _sbox: std::marker::PhantomData<S>,
+}
+
+impl<E: Engine, S: Sbox> PoseidonSponge<E, S> {
+    pub fn new<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        params: SpongeParams<E>,
+    ) -> Result<Self, SynthesisError> {
+        let constants = params.constants()?;
+        let state = params
+            .initial_state()
+            .into_iter()
+            .map(|value| AllocatedNum::alloc_cnst(cs, value))
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        Ok(Self {
+            params,
+            constants,
+            state,
+            pending: vec![],
+            squeezed: None,
+            _sbox: std::marker::PhantomData,
+        })
+    }
+
+    pub fn absorb<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+        element: &AllocatedNum<E>,
+    ) -> Result<(), SynthesisError> {
+        if self.squeezed.is_some() {
+            return Err(absorb_after_squeeze());
+        }
+
+        self.pending.push(*element);
+        if self.pending.len() == self.params.rate {
+            self.absorb_pending(cs)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn absorb_all<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+        elements: &[AllocatedNum<E>],
+    ) -> Result<(), SynthesisError> {
+        for element in elements {
+            self.absorb(cs, element)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn squeeze<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+    ) -> Result<AllocatedNum<E>, SynthesisError> {
+        let index = match self.squeezed {
+            None => {
+                // Pad with a single one followed by zeros, which need not be added.
+                let position = self.params.capacity + self.pending.len();
+                self.state[position] = self.state[position].add_constant(cs, E::Fr::one())?;
+                self.absorb_pending(cs)?;
+
+                0
+            }
+            Some(index) if index == self.params.rate => {
+                self.state = calc_permutation::<E, CS, S>(cs, &self.state, &self.constants)?;
+
+                0
+            }
+            Some(index) => index,
+        };
+        self.squeezed = Some(index + 1);
+
+        Ok(self.state[self.params.capacity + index])
+    }
+
+    fn absorb_pending<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
+        let capacity = self.params.capacity;
+        for (i, element) in self.pending.drain(..).enumerate() {
+            self.state[capacity + i] = self.state[capacity + i].add(cs, &element)?;
+        }
+        self.state = calc_permutation::<E, CS, S>(cs, &self.state, &self.constants)?;
+
+        Ok(())
+    }
+}
+
+/// The native counterpart of `PoseidonSponge`.
+#[derive(Clone, Debug)]
+pub struct PoseidonSpongeNative<E: Engine> {
+    params: SpongeParams<E>,
+    constants: Arc<PoseidonConstants<E>>,
+    state: Vec<E::Fr>,
+    pending: Vec<E::Fr>,
+    squeezed: Option<usize>,
+}
+
+impl<E: Engine> PoseidonSpongeNative<E> {
+    pub fn new(params: SpongeParams<E>) -> Result<Self, SynthesisError> {
+        let constants = params.constants()?;
+        let state = params.initial_state();
+
+        Ok(Self {
+            params,
+            constants,
+            state,
+            pending: vec![],
+            squeezed: None,
+        })
+    }
+
+    pub fn absorb(&mut self, element: &E::Fr) -> Result<(), SynthesisError> {
+        if self.squeezed.is_some() {
+            return Err(absorb_after_squeeze());
+        }
+
+        self.pending.push(*element);
+        if self.pending.len() == self.params.rate {
+            self.absorb_pending();
+        }
+
+        Ok(())
+    }
+
+    pub fn absorb_all(&mut self, elements: &[E::Fr]) -> Result<(), SynthesisError> {
+        for element in elements {
+            self.absorb(element)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn squeeze(&mut self) -> E::Fr {
+        let index = match self.squeezed {
+            None => {
+                // Pad with a single one followed by zeros, which need not be added.
+                let position = self.params.capacity + self.pending.len();
+                self.state[position].add_assign(&E::Fr::one());
+                self.absorb_pending();
+
+                0
+            }
+            Some(index) if index == self.params.rate => {
+                permute_native(&mut self.state, &self.constants);
+
+                0
+            }
+            Some(index) => index,
+        };
+        self.squeezed = Some(index + 1);
+
+        self.state[self.params.capacity + index]
+    }
+
+    fn absorb_pending(&mut self) {
+        let capacity = self.params.capacity;
+        for (i, element) in self.pending.drain(..).enumerate() {
+            self.state[capacity + i].add_assign(&element);
+        }
+        permute_native(&mut self.state, &self.constants);
+    }
+}
+
+fn absorb_after_squeeze() -> SynthesisError {
+    CircuitError::InvalidInputShape("cannot absorb after squeezing".to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr};
+    use franklin_crypto::bellman::plonk::better_better_cs::cs::{
+        TrivialAssembly, Width4MainGateWithDNext,
+    };
+    use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
+    use franklin_crypto::plonk::circuit::Width4WithCustomGates;
+    use verkle_tree::ipa_fr::utils::read_field_element_le;
+
+    use super::{PoseidonSponge, PoseidonSpongeNative, SpongeParams};
+
+    #[test]
+    fn test_fr_poseidon_sponge_matches_native() -> Result<(), Box<dyn std::error::Error>> {
+        let domain_tag = read_field_element_le::<Fr>(&[7])?;
+        for &(rate, capacity) in [(2, 1), (4, 1), (3, 2)].iter() {
+            for length in 0..6 {
+                let elements = (0..length)
+                    .map(|i| read_field_element_le::<Fr>(&[i as u8, 1]))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                let params = SpongeParams::<Bn256>::new(rate, capacity, domain_tag)?;
+                let mut native_sponge = PoseidonSpongeNative::new(params.clone())?;
+                native_sponge.absorb_all(&elements)?;
+
+                let mut cs =
+                    TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+                let allocated_elements = elements
+                    .iter()
+                    .map(|&x| AllocatedNum::alloc(&mut cs, || Ok(x)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut sponge = PoseidonSponge::<Bn256>::new(&mut cs, params)?;
+                sponge.absorb_all(&mut cs, &allocated_elements)?;
+
+                // Squeeze more than `rate` elements to go through another permutation.
+                for _ in 0..(rate + 1) {
+                    assert_eq!(
+                        sponge.squeeze(&mut cs)?.get_value(),
+                        Some(native_sponge.squeeze())
+                    );
+                }
+                assert!(cs.is_satisfied());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fr_poseidon_sponge_rejects_invalid_use() -> Result<(), Box<dyn std::error::Error>> {
+        let domain_tag = read_field_element_le::<Fr>(&[7])?;
+        assert!(SpongeParams::<Bn256>::new(0, 1, domain_tag).is_err());
+        assert!(SpongeParams::<Bn256>::new(16, 2, domain_tag).is_err());
+
+        let params = SpongeParams::<Bn256>::new(2, 1, domain_tag)?;
+        let mut native_sponge = PoseidonSpongeNative::new(params.clone())?;
+        native_sponge.squeeze();
+        assert!(native_sponge.absorb(&domain_tag).is_err());
+
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        let element = AllocatedNum::alloc(&mut cs, || Ok(domain_tag))?;
+        let mut sponge = PoseidonSponge::<Bn256>::new(&mut cs, params)?;
+        sponge.squeeze(&mut cs)?;
+        assert!(sponge.absorb(&mut cs, &element).is_err());
+
+        Ok(())
+    }
+}
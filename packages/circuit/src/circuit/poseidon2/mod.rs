@@ -0,0 +1,443 @@
+use std::sync::Arc;
+
+use franklin_crypto::bellman::pairing::Engine;
+use franklin_crypto::bellman::plonk::better_better_cs::cs::{ConstraintSystem, GateInternal};
+use franklin_crypto::bellman::{Field, PrimeField, SynthesisError};
+use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
+
+use super::poseidon::grain::GrainLfsr;
+use super::poseidon::{
+    add_round_constant, get_or_insert_cached, linear_combination, MainGateSbox, Sbox,
+};
+use super::transcript::TranscriptHash;
+
+/// The width supported by `Poseidon2Constants`.
+pub const WIDTH: usize = 3;
+
+/// Generate constraints for the Poseidon2 hash of two elements.
+///
+/// The state is `[0, inputs[0], inputs[1]]` and the second element of the permuted state is returned.
+pub fn calc_poseidon2<E, CS>(
+    cs: &mut CS,
+    inputs: &[AllocatedNum<E>],
+) -> Result<AllocatedNum<E>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    calc_poseidon2_with_sbox::<E, CS, MainGateSbox>(cs, inputs)
+}
+
+/// Generate constraints for the Poseidon2 hash of two elements whose S-boxes are constrained by `S`.
+pub fn calc_poseidon2_with_sbox<E, CS, S>(
+    cs: &mut CS,
+    inputs: &[AllocatedNum<E>],
+) -> Result<AllocatedNum<E>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+    S: Sbox,
+{
+    assert_eq!(inputs.len(), WIDTH - 1, "invalid inputs length");
+
+    let constants = Poseidon2Constants::<E>::cached();
+    let capacity = AllocatedNum::zero(cs);
+    let mut elements = vec![capacity];
+    elements.append(&mut inputs.to_vec());
+    let elements = calc_poseidon2_permutation::<E, CS, S>(cs, &elements, &constants)?;
+
+    Ok(elements[1])
+}
+
+/// Compute the Poseidon2 hash of two elements.
+///
+/// This is the native counterpart of `calc_poseidon2`.
+pub fn poseidon2_native<E: Engine>(inputs: &[E::Fr]) -> E::Fr {
+    assert_eq!(inputs.len(), WIDTH - 1, "invalid inputs length");
+
+    let constants = Poseidon2Constants::<E>::cached();
+    let mut elements = vec![E::Fr::zero()];
+    elements.extend_from_slice(inputs);
+    permute_native(&mut elements, &constants);
+
+    elements[1]
+}
+
+/// Generate constraints that
+/// `outputs` = `M_E` * `inputs`, where `M_E` is the circulant matrix `circ(2, 1, 1)`.
+pub fn product_external_matrix<E, CS>(
+    cs: &mut CS,
+    inputs: &[AllocatedNum<E>],
+) -> Result<Vec<AllocatedNum<E>>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    let mut two = E::Fr::one();
+    two.double();
+
+    let mut outputs = vec![];
+    for i in 0..inputs.len() {
+        let terms = inputs
+            .iter()
+            .enumerate()
+            .map(|(j, input)| (if i == j { two } else { E::Fr::one() }, *input))
+            .collect::<Vec<_>>();
+        outputs.push(linear_combination(cs, &terms)?);
+    }
+
+    Ok(outputs)
+}
+
+/// Generate constraints that
+/// `outputs` = `M_I` * `inputs`, where `M_I` is the all-ones matrix plus `diag(internal_diagonal)`.
+pub fn product_internal_matrix<E, CS>(
+    cs: &mut CS,
+    inputs: &[AllocatedNum<E>],
+    internal_diagonal: &[E::Fr],
+) -> Result<Vec<AllocatedNum<E>>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    let mut outputs = vec![];
+    for i in 0..inputs.len() {
+        let terms = inputs
+            .iter()
+            .enumerate()
+            .map(|(j, input)| {
+                let mut coeff = E::Fr::one();
+                if i == j {
+                    coeff.add_assign(&internal_diagonal[i]);
+                }
+
+                (coeff, *input)
+            })
+            .collect::<Vec<_>>();
+        outputs.push(linear_combination(cs, &terms)?);
+    }
+
+    Ok(outputs)
+}
+
+/// Generate constraints for the Poseidon2 permutation of `inputs`,
+/// whose length must be `WIDTH`.
+pub fn calc_poseidon2_permutation<E, CS, S>(
+    cs: &mut CS,
+    inputs: &[AllocatedNum<E>],
+    constants: &Poseidon2Constants<E>,
+) -> Result<Vec<AllocatedNum<E>>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+    S: Sbox,
+{
+    assert_eq!(inputs.len(), WIDTH, "invalid inputs length");
+
+    let full_round = |cs: &mut CS,
+                      elements: Vec<AllocatedNum<E>>,
+                      round_constants: &[E::Fr]|
+     -> Result<Vec<AllocatedNum<E>>, SynthesisError> {
+        let mut outputs = vec![];
+        for (e, c) in elements.into_iter().zip(round_constants.iter()) {
+            let tmp = add_round_constant(cs, e, *c)?;
+            outputs.push(S::apply(cs, tmp)?);
+        }
+
+        product_external_matrix(cs, &outputs)
+    };
+
+    // The external linear layer is applied before the first round.
+    let mut elements = product_external_matrix(cs, inputs)?;
+
+    let half_full_rounds = constants.full_rounds / 2;
+    for round_constants in constants.external_round_constants[..half_full_rounds].iter() {
+        elements = full_round(cs, elements, round_constants)?;
+    }
+
+    for c in constants.internal_round_constants.iter() {
+        let tmp = add_round_constant(cs, elements[0], *c)?;
+        elements[0] = S::apply(cs, tmp)?;
+        elements = product_internal_matrix(cs, &elements, &constants.internal_diagonal)?;
+    }
+
+    for round_constants in constants.external_round_constants[half_full_rounds..].iter() {
+        elements = full_round(cs, elements, round_constants)?;
+    }
+
+    Ok(elements)
+}
+
+/// Compute the Poseidon2 permutation of `elements` in place.
+///
+/// This is the native counterpart of `calc_poseidon2_permutation`.
+pub fn permute_native<E: Engine>(elements: &mut [E::Fr], constants: &Poseidon2Constants<E>) {
+    assert_eq!(elements.len(), WIDTH, "invalid elements length");
+
+    let sigma = |x: &mut E::Fr| {
+        let input = *x;
+        x.square();
+        x.square();
+        x.mul_assign(&input);
+    };
+
+    let sum = |elements: &[E::Fr]| {
+        elements.iter().fold(E::Fr::zero(), |mut sum, e| {
+            sum.add_assign(e);
+            sum
+        })
+    };
+
+    // M_E * x = x + sum(x) for the width 3.
+    let external_layer = |elements: &mut [E::Fr]| {
+        let s = sum(elements);
+        for e in elements.iter_mut() {
+            e.add_assign(&s);
+        }
+    };
+
+    // M_I * x = diag(internal_diagonal) * x + sum(x).
+    let internal_layer = |elements: &mut [E::Fr]| {
+        let s = sum(elements);
+        for (e, d) in elements.iter_mut().zip(constants.internal_diagonal.iter()) {
+            e.mul_assign(d);
+            e.add_assign(&s);
+        }
+    };
+
+    let full_round = |elements: &mut [E::Fr], round_constants: &[E::Fr]| {
+        for (e, c) in elements.iter_mut().zip(round_constants.iter()) {
+            e.add_assign(c);
+            sigma(e);
+        }
+        external_layer(elements);
+    };
+
+    external_layer(elements);
+
+    let half_full_rounds = constants.full_rounds / 2;
+    for round_constants in constants.external_round_constants[..half_full_rounds].iter() {
+        full_round(elements, round_constants);
+    }
+
+    for c in constants.internal_round_constants.iter() {
+        elements[0].add_assign(c);
+        sigma(&mut elements[0]);
+        internal_layer(elements);
+    }
+
+    for round_constants in constants.external_round_constants[half_full_rounds..].iter() {
+        full_round(elements, round_constants);
+    }
+}
+
+/// The constants of the Poseidon2 permutation with width 3 and `alpha` = 5.
+///
+/// They follow the reference parameters of Poseidon2 over the scalar field of BN256,
+/// whose round constants are generated by the Grain LFSR.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Poseidon2Constants<E: Engine> {
+    /// The round constants of the full rounds, `WIDTH` for each round.
+    pub external_round_constants: Vec<Vec<E::Fr>>,
+    /// The round constants of the partial rounds, which are added to the first element only.
+    pub internal_round_constants: Vec<E::Fr>,
+    /// The diagonal of `M_I` minus the all-ones matrix.
+    pub internal_diagonal: Vec<E::Fr>,
+    pub full_rounds: usize,
+    pub partial_rounds: usize,
+}
+
+impl<E: Engine> Default for Poseidon2Constants<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Engine> Poseidon2Constants<E> {
+    pub fn new() -> Self {
+        let full_rounds = 8;
+        let partial_rounds = 56;
+
+        let mut lfsr = GrainLfsr::new(
+            1,
+            0,
+            E::Fr::NUM_BITS as u64,
+            WIDTH as u64,
+            full_rounds as u64,
+            partial_rounds as u64,
+        );
+        let next_full_round = |lfsr: &mut GrainLfsr| {
+            (0..WIDTH)
+                .map(|_| lfsr.next_field_element::<E::Fr>())
+                .collect::<Vec<_>>()
+        };
+
+        // The constants are generated in the order of rounds.
+        let mut external_round_constants = vec![];
+        for _ in 0..(full_rounds / 2) {
+            external_round_constants.push(next_full_round(&mut lfsr));
+        }
+        let internal_round_constants = (0..partial_rounds)
+            .map(|_| lfsr.next_field_element::<E::Fr>())
+            .collect::<Vec<_>>();
+        for _ in 0..(full_rounds / 2) {
+            external_round_constants.push(next_full_round(&mut lfsr));
+        }
+
+        let mut two = E::Fr::one();
+        two.double();
+        let internal_diagonal = vec![E::Fr::one(), E::Fr::one(), two];
+
+        Self {
+            external_round_constants,
+            internal_round_constants,
+            internal_diagonal,
+            full_rounds,
+            partial_rounds,
+        }
+    }
+
+    /// Returns the constants, which are generated only once.
+    pub fn cached() -> Arc<Self> {
+        get_or_insert_cached(WIDTH, Self::new)
+    }
+}
+
+/// The transcript hash which absorbs numbers with `calc_poseidon2_with_sbox`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Poseidon2Hash<S = MainGateSbox> {
+    _sbox: std::marker::PhantomData<S>,
+}
+
+impl<S: Sbox> TranscriptHash for Poseidon2Hash<S> {
+    fn used_gates<E: Engine>() -> Vec<Box<dyn GateInternal<E>>> {
+        S::used_gates()
+    }
+
+    fn hash<E: Engine, CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        inputs: &[AllocatedNum<E>],
+    ) -> Result<AllocatedNum<E>, SynthesisError> {
+        calc_poseidon2_with_sbox::<E, CS, S>(cs, inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use franklin_crypto::bellman::pairing::bn256::{Bn256, Fr};
+    use franklin_crypto::bellman::plonk::better_better_cs::cs::{
+        TrivialAssembly, Width4MainGateWithDNext,
+    };
+    use franklin_crypto::plonk::circuit::allocated_num::AllocatedNum;
+    use franklin_crypto::plonk::circuit::Width4WithCustomGates;
+    use verkle_tree::ipa_fr::utils::read_field_element_le;
+
+    use crate::circuit::ipa_fr::transcript::{Transcript, WrappedTranscript};
+    use crate::circuit::poseidon::{CustomGateSbox, MainGateSbox, Sbox};
+
+    use super::{
+        calc_poseidon2_permutation, permute_native, poseidon2_native, Poseidon2Constants,
+        Poseidon2Hash,
+    };
+
+    /// Read a `0x`-prefixed big-endian hex string as in the reference parameters.
+    fn read_hex_be(s: &str) -> anyhow::Result<Fr> {
+        let mut bytes = hex::decode(&s[2..])?;
+        bytes.reverse();
+
+        read_field_element_le::<Fr>(&bytes)
+    }
+
+    #[test]
+    fn test_poseidon2_native_known_answer() -> Result<(), Box<dyn std::error::Error>> {
+        let constants = Poseidon2Constants::<Bn256>::new();
+        assert_eq!(
+            constants.external_round_constants[0][0],
+            read_hex_be("0x1d066a255517b7fd8bddd3a93f7804ef7f8fcde48bb4c37a59a09a1a97052816")?
+        );
+        assert_eq!(
+            constants.internal_round_constants[0],
+            read_hex_be("0x1a1d063e54b1e764b63e1855bff015b8cedd192f47308731499573f23597d4b5")?
+        );
+
+        let mut elements = vec![
+            read_field_element_le::<Fr>(&[0])?,
+            read_field_element_le::<Fr>(&[1])?,
+            read_field_element_le::<Fr>(&[2])?,
+        ];
+        permute_native(&mut elements, &constants);
+        let expected = [
+            "0x0bb61d24daca55eebcb1929a82650f328134334da98ea4f847f760054f4a3033",
+            "0x303b6f7c86d043bfcbcc80214f26a30277a15d3f74ca654992defe7ff8d03570",
+            "0x1ed25194542b12eef8617361c3ba7c52e660b145994427cc86296242cf766ec8",
+        ]
+        .iter()
+        .map(|x| read_hex_be(x))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(elements, expected);
+
+        Ok(())
+    }
+
+    fn check_poseidon2_circuit<S: Sbox>() -> Result<(), Box<dyn std::error::Error>> {
+        let constants = Poseidon2Constants::<Bn256>::cached();
+        let mut elements = vec![
+            read_field_element_le::<Fr>(&[0])?,
+            read_field_element_le::<Fr>(&[1])?,
+            read_field_element_le::<Fr>(&[2])?,
+        ];
+
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        let inputs = elements
+            .iter()
+            .map(|&x| AllocatedNum::alloc(&mut cs, || Ok(x)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let outputs = calc_poseidon2_permutation::<_, _, S>(&mut cs, &inputs, &constants)?;
+        permute_native(&mut elements, &constants);
+        assert_eq!(
+            outputs.iter().map(|x| x.get_value()).collect::<Vec<_>>(),
+            elements.into_iter().map(Some).collect::<Vec<_>>()
+        );
+        assert!(cs.is_satisfied());
+        println!("the number of gates: {}", cs.n());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_poseidon2_circuit_matches_native() -> Result<(), Box<dyn std::error::Error>> {
+        check_poseidon2_circuit::<MainGateSbox>()?;
+        check_poseidon2_circuit::<CustomGateSbox>()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_poseidon2_transcript() -> Result<(), Box<dyn std::error::Error>> {
+        let init_state = read_field_element_le::<Fr>(&[3])?;
+        let elements = [
+            read_field_element_le::<Fr>(&[1])?,
+            read_field_element_le::<Fr>(&[2, 3])?,
+        ];
+
+        let mut cs =
+            TrivialAssembly::<Bn256, Width4WithCustomGates, Width4MainGateWithDNext>::new();
+        let allocated_init_state = AllocatedNum::alloc(&mut cs, || Ok(init_state))?;
+        let mut transcript =
+            WrappedTranscript::<Bn256, Poseidon2Hash>::new(&mut cs, allocated_init_state)?;
+        let mut expected = init_state;
+        for element in elements.iter() {
+            expected = poseidon2_native::<Bn256>(&[expected, *element]);
+            let allocated_element = AllocatedNum::alloc(&mut cs, || Ok(*element))?;
+            transcript.commit_alloc_num(&mut cs, &allocated_element)?;
+            assert_eq!(
+                transcript.get_challenge(&mut cs)?.get_value(),
+                Some(expected)
+            );
+        }
+        assert!(cs.is_satisfied());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+pub mod poseidon;
+pub mod sha256;
+
+use franklin_crypto::bellman::pairing::Engine;
+use franklin_crypto::bellman::plonk::better_better_cs::cs::{ConstraintSystem, GateInternal};
+use franklin_crypto::bellman::{PrimeField, SynthesisError};
+use franklin_crypto::plonk::circuit::allocated_num::{AllocatedNum, Num};
+use franklin_crypto::plonk::circuit::bigint::field::{FieldElement, RnsParameters};
+use franklin_crypto::plonk::circuit::verifier_circuit::affine_point_wrapper::WrappedAffinePoint;
+
+use crate::error::CircuitError;
+
+use super::num::baby_ecc::EdwardsPoint;
+use super::num::{compose_field_element, truncate_into_field_element};
+
+/// The trait of transcript objects.
+///
+/// An implementation only decides how its state absorbs a number and how a challenge is
+/// squeezed out of it. Point gadgets are committed through `TranscriptPoint` and challenges
+/// are converted into any `TranscriptChallenge`, so the same transcript works in the Fr and Fs circuits.
+pub trait Transcript<E: Engine>: Sized + Clone {
+    type Params;
+
+    /// Create new transcript object with init parameter.
+    fn new<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        init_state: Self::Params,
+    ) -> Result<Self, SynthesisError>;
+
+    /// Commit a `AllocatedNum` value.
+    fn commit_alloc_num<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+        element: &AllocatedNum<E>,
+    ) -> Result<(), SynthesisError>;
+
+    /// Generate a pseudo-random `AllocatedNum` value.
+    fn get_challenge<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+    ) -> Result<AllocatedNum<E>, SynthesisError>;
+
+    fn into_params(self) -> Self::Params;
+
+    /// Custom gates which have to be declared by a circuit using this transcript.
+    fn used_gates() -> Vec<Box<dyn GateInternal<E>>> {
+        vec![]
+    }
+
+    /// Commit a label which separates the values committed after it.
+    ///
+    /// The label is absorbed as a constant by `commit_alloc_num`. Use `UnlabeledTranscript`
+    /// to check the proofs of a native prover which does not commit labels.
+    fn commit_label<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+        label: &[u8],
+    ) -> Result<(), SynthesisError> {
+        let label = AllocatedNum::alloc_cnst(cs, label_to_field_element(label)?)?;
+
+        self.commit_alloc_num(cs, &label)
+    }
+
+    fn commit_alloc_num_with_label<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+        label: &[u8],
+        element: &AllocatedNum<E>,
+    ) -> Result<(), SynthesisError> {
+        self.commit_label(cs, label)?;
+        self.commit_alloc_num(cs, element)
+    }
+
+    fn commit_field_element_with_label<'a, CS: ConstraintSystem<E>, F: PrimeField>(
+        &mut self,
+        cs: &mut CS,
+        label: &[u8],
+        element: &FieldElement<'a, E, F>,
+    ) -> Result<(), SynthesisError> {
+        self.commit_label(cs, label)?;
+        self.commit_field_element(cs, element)
+    }
+
+    fn commit_point_with_label<CS: ConstraintSystem<E>, P: TranscriptPoint<E>>(
+        &mut self,
+        cs: &mut CS,
+        label: &[u8],
+        point: &P,
+    ) -> Result<(), SynthesisError> {
+        self.commit_label(cs, label)?;
+        self.commit_point(cs, point)
+    }
+
+    /// Commit a `FieldElement` value.
+    ///
+    /// An element which fits in `E::Fr` is committed as a single number,
+    /// otherwise each of its limbs is committed.
+    fn commit_field_element<'a, CS: ConstraintSystem<E>, F: PrimeField>(
+        &mut self,
+        cs: &mut CS,
+        element: &FieldElement<'a, E, F>,
+    ) -> Result<(), SynthesisError> {
+        if F::NUM_BITS <= E::Fr::CAPACITY {
+            let element = compose_field_element(cs, element)?;
+            return self.commit_alloc_num(cs, &element);
+        }
+
+        let value = element.get_field_value();
+        for term in element.clone().into_limbs().iter() {
+            let v = if value.is_some() {
+                match term.into_num() {
+                    Num::Constant(c) => AllocatedNum::alloc(cs, || Ok(c))?,
+                    Num::Variable(v) => v,
+                }
+            } else {
+                AllocatedNum::alloc(cs, || Err(SynthesisError::UnconstrainedVariable))?
+            };
+
+            self.commit_alloc_num(cs, &v)?;
+        }
+
+        Ok(())
+    }
+
+    /// Commit a point gadget.
+    fn commit_point<CS: ConstraintSystem<E>, P: TranscriptPoint<E>>(
+        &mut self,
+        cs: &mut CS,
+        point: &P,
+    ) -> Result<(), SynthesisError> {
+        point.commit_into(cs, self)
+    }
+
+    /// Generate a challenge in the representation `C`.
+    fn get_challenge_as<'a, CS: ConstraintSystem<E>, C: TranscriptChallenge<'a, E>>(
+        &mut self,
+        cs: &mut CS,
+        params: &'a C::Params,
+    ) -> Result<C, SynthesisError> {
+        let challenge = self.get_challenge(cs)?;
+
+        C::from_challenge(cs, &challenge, params)
+    }
+}
+
+/// The hash with which a transcript absorbs a number into its state.
+///
+/// It is called with `[state, element]` and returns the new state.
+pub trait TranscriptHash: Clone {
+    /// Custom gates which have to be declared by a circuit using this hash.
+    fn used_gates<E: Engine>() -> Vec<Box<dyn GateInternal<E>>>;
+
+    fn hash<E: Engine, CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        inputs: &[AllocatedNum<E>],
+    ) -> Result<AllocatedNum<E>, SynthesisError>;
+}
+
+/// Maps a label to the field element which is committed in its place.
+///
+/// The length of the label and the label are read as a little-endian integer,
+/// so that labels which differ only in trailing zeros are mapped to different elements.
+pub fn label_to_field_element<F: PrimeField>(label: &[u8]) -> Result<F, CircuitError> {
+    let max_len = (F::CAPACITY / 8) as usize - 1;
+    if label.len() > max_len {
+        return Err(CircuitError::InvalidInputShape(format!(
+            "the label should be at most {} bytes, but {}",
+            max_len,
+            label.len()
+        )));
+    }
+
+    let mut bytes = vec![label.len() as u8];
+    bytes.extend_from_slice(label);
+    let mut raw_value = F::Repr::default();
+    for (i, limb) in raw_value.as_mut().iter_mut().enumerate() {
+        for (j, &byte) in bytes.iter().skip(8 * i).take(8).enumerate() {
+            *limb |= (byte as u64) << (8 * j);
+        }
+    }
+
+    F::from_repr(raw_value)
+        .map_err(|_| CircuitError::InvalidInputShape("the label is not in the field".to_string()))
+}
+
+/// The transcript object which ignores labels,
+/// for the proofs of a native prover which does not commit them, e.g. the ones of verkle-tree.
+#[derive(Clone)]
+pub struct UnlabeledTranscript<T> {
+    inner: T,
+}
+
+impl<E: Engine, T: Transcript<E>> Transcript<E> for UnlabeledTranscript<T> {
+    type Params = T::Params;
+
+    fn new<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        init_state: Self::Params,
+    ) -> Result<Self, SynthesisError> {
+        let inner = T::new(cs, init_state)?;
+
+        Ok(Self { inner })
+    }
+
+    fn commit_alloc_num<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+        element: &AllocatedNum<E>,
+    ) -> Result<(), SynthesisError> {
+        self.inner.commit_alloc_num(cs, element)
+    }
+
+    fn get_challenge<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+    ) -> Result<AllocatedNum<E>, SynthesisError> {
+        self.inner.get_challenge(cs)
+    }
+
+    fn into_params(self) -> Self::Params {
+        self.inner.into_params()
+    }
+
+    fn used_gates() -> Vec<Box<dyn GateInternal<E>>> {
+        T::used_gates()
+    }
+
+    fn commit_label<CS: ConstraintSystem<E>>(
+        &mut self,
+        _cs: &mut CS,
+        _label: &[u8],
+    ) -> Result<(), SynthesisError> {
+        Ok(())
+    }
+}
+
+/// Point gadgets which can be committed to a transcript.
+pub trait TranscriptPoint<E: Engine> {
+    fn commit_into<CS: ConstraintSystem<E>, T: Transcript<E>>(
+        &self,
+        cs: &mut CS,
+        transcript: &mut T,
+    ) -> Result<(), SynthesisError>;
+}
+
+impl<E: Engine> TranscriptPoint<E> for EdwardsPoint<E> {
+    fn commit_into<CS: ConstraintSystem<E>, T: Transcript<E>>(
+        &self,
+        cs: &mut CS,
+        transcript: &mut T,
+    ) -> Result<(), SynthesisError> {
+        transcript.commit_alloc_num(cs, self.get_x())?;
+        transcript.commit_alloc_num(cs, self.get_y())?;
+
+        Ok(())
+    }
+}
+
+impl<'a, E: Engine, WP: WrappedAffinePoint<'a, E>> TranscriptPoint<E> for WP {
+    fn commit_into<CS: ConstraintSystem<E>, T: Transcript<E>>(
+        &self,
+        cs: &mut CS,
+        transcript: &mut T,
+    ) -> Result<(), SynthesisError> {
+        let unwrapped_point = self.get_point();
+        transcript.commit_field_element(cs, &unwrapped_point.get_x())?;
+        transcript.commit_field_element(cs, &unwrapped_point.get_y())?;
+
+        Ok(())
+    }
+}
+
+/// Representations which a transcript challenge can be converted into.
+pub trait TranscriptChallenge<'a, E: Engine>: Sized {
+    type Params: 'a;
+
+    fn from_challenge<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        challenge: &AllocatedNum<E>,
+        params: &'a Self::Params,
+    ) -> Result<Self, SynthesisError>;
+}
+
+impl<'a, E: Engine> TranscriptChallenge<'a, E> for AllocatedNum<E> {
+    type Params = ();
+
+    fn from_challenge<CS: ConstraintSystem<E>>(
+        _cs: &mut CS,
+        challenge: &AllocatedNum<E>,
+        _params: &'a (),
+    ) -> Result<Self, SynthesisError> {
+        Ok(*challenge)
+    }
+}
+
+/// The challenge is decomposed into its canonical bits before it is truncated,
+/// so the prover cannot choose the bits of `challenge + p` instead.
+impl<'a, E: Engine, F: PrimeField> TranscriptChallenge<'a, E> for FieldElement<'a, E, F> {
+    type Params = RnsParameters<E, F>;
+
+    fn from_challenge<CS: ConstraintSystem<E>>(
+        cs: &mut CS,
+        challenge: &AllocatedNum<E>,
+        params: &'a RnsParameters<E, F>,
+    ) -> Result<Self, SynthesisError> {
+        truncate_into_field_element(cs, challenge, params)
+    }
+}
@@ -0,0 +1,51 @@
+use franklin_crypto::bellman::pairing::Engine;
+
+use crate::circuit::poseidon::{permute_native, PoseidonConstants};
+use crate::error::CircuitError;
+
+use super::label_to_field_element;
+
+/// The native counterpart of the transcripts hashing with `PoseidonHash`,
+/// e.g. `ipa_fr::transcript::WrappedTranscript<E>`, which commits labels in the same way.
+///
+/// Committing `element` sets `state = poseidon(state, element)`.
+#[derive(Clone, Debug)]
+pub struct PoseidonNativeTranscript<E: Engine> {
+    state: E::Fr,
+}
+
+impl<E: Engine> PoseidonNativeTranscript<E> {
+    pub fn new(init_state: E::Fr) -> Self {
+        Self { state: init_state }
+    }
+
+    pub fn commit_field_element(&mut self, element: &E::Fr) -> Result<(), CircuitError> {
+        let constants = PoseidonConstants::<E>::cached(2)?;
+        let mut elements = vec![constants.domain_tag, self.state, *element];
+        permute_native(&mut elements, &constants);
+        self.state = elements[1];
+
+        Ok(())
+    }
+
+    pub fn commit_label(&mut self, label: &[u8]) -> Result<(), CircuitError> {
+        self.commit_field_element(&label_to_field_element(label)?)
+    }
+
+    pub fn commit_field_element_with_label(
+        &mut self,
+        label: &[u8],
+        element: &E::Fr,
+    ) -> Result<(), CircuitError> {
+        self.commit_label(label)?;
+        self.commit_field_element(element)
+    }
+
+    pub fn get_challenge(&self) -> E::Fr {
+        self.state
+    }
+
+    pub fn into_params(self) -> E::Fr {
+        self.state
+    }
+}
@@ -0,0 +1,131 @@
+use franklin_crypto::bellman::pairing::Engine;
+use franklin_crypto::bellman::plonk::better_better_cs::cs::ConstraintSystem;
+use franklin_crypto::bellman::{Field, PrimeField, SynthesisError};
+use franklin_crypto::plonk::circuit::allocated_num::{AllocatedNum, Num};
+use franklin_crypto::plonk::circuit::linear_combination::LinearCombination;
+use franklin_crypto::plonk::circuit::sha256::sha256;
+use sha2::{Digest, Sha256};
+
+use crate::circuit::discrete_log::utils::{allocated_num_to_alligned_big_endian, bytes_to_keep};
+use crate::circuit::utils::{read_field_element_be_from, write_field_element_be_into};
+
+use super::{label_to_field_element, Transcript};
+
+/// The transcript object whose state is updated by SHA-256,
+/// so that challenges can be derived in the same way on EVM.
+///
+/// Committing `element` sets `state = sha256(state || element)`, where both values are
+/// serialized as 32-byte big-endian integers and the first byte of the digest is dropped.
+#[derive(Clone)]
+pub struct Sha256Transcript<E>
+where
+    E: Engine,
+{
+    state: AllocatedNum<E>,
+}
+
+impl<E: Engine> Transcript<E> for Sha256Transcript<E> {
+    type Params = AllocatedNum<E>;
+
+    fn new<CS: ConstraintSystem<E>>(
+        _cs: &mut CS,
+        init_state: AllocatedNum<E>,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self { state: init_state })
+    }
+
+    fn commit_alloc_num<CS: ConstraintSystem<E>>(
+        &mut self,
+        cs: &mut CS,
+        element: &AllocatedNum<E>,
+    ) -> Result<(), SynthesisError> {
+        let mut preimage = allocated_num_to_alligned_big_endian(cs, &self.state)?;
+        preimage.extend(allocated_num_to_alligned_big_endian(cs, element)?);
+        let digest = sha256(cs, &preimage)?;
+
+        // The digest is big-endian, so keep its last `bytes_to_keep` bytes.
+        let keep = bytes_to_keep::<E>();
+        let mut lc = LinearCombination::zero();
+        let mut coeff = E::Fr::one();
+        for bit in digest[(32 - keep) * 8..].iter().rev() {
+            lc.add_assign_boolean_with_coeff(bit, coeff);
+            coeff.double();
+        }
+
+        self.state = match lc.into_num(cs)? {
+            Num::Variable(state) => state,
+            Num::Constant(value) => AllocatedNum::alloc_cnst(cs, value)?,
+        };
+
+        Ok(())
+    }
+
+    fn get_challenge<CS: ConstraintSystem<E>>(
+        &mut self,
+        _cs: &mut CS,
+    ) -> Result<AllocatedNum<E>, SynthesisError> {
+        Ok(self.state)
+    }
+
+    fn into_params(self) -> AllocatedNum<E> {
+        self.state
+    }
+}
+
+/// The native counterpart of `Sha256Transcript<E>`.
+#[derive(Clone, Debug)]
+pub struct Sha256NativeTranscript<F: PrimeField> {
+    state: F,
+}
+
+impl<F: PrimeField> Sha256NativeTranscript<F> {
+    pub fn new(init_state: F) -> Self {
+        Self { state: init_state }
+    }
+
+    /// Create new transcript object whose state is derived from `bytes`.
+    pub fn with_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let state = digest_into_field_element(bytes)?;
+
+        Ok(Self { state })
+    }
+
+    pub fn commit_field_element(&mut self, element: &F) -> anyhow::Result<()> {
+        let mut preimage = vec![];
+        write_field_element_be_into(self.state, &mut preimage)?;
+        write_field_element_be_into(*element, &mut preimage)?;
+        self.state = digest_into_field_element(&preimage)?;
+
+        Ok(())
+    }
+
+    pub fn commit_label(&mut self, label: &[u8]) -> anyhow::Result<()> {
+        self.commit_field_element(&label_to_field_element(label)?)
+    }
+
+    pub fn commit_field_element_with_label(
+        &mut self,
+        label: &[u8],
+        element: &F,
+    ) -> anyhow::Result<()> {
+        self.commit_label(label)?;
+        self.commit_field_element(element)
+    }
+
+    pub fn get_challenge(&self) -> F {
+        self.state
+    }
+
+    pub fn into_params(self) -> F {
+        self.state
+    }
+}
+
+fn digest_into_field_element<F: PrimeField>(preimage: &[u8]) -> anyhow::Result<F> {
+    let digest = Sha256::digest(preimage);
+    let keep = (F::CAPACITY / 8) as usize;
+    let mut bytes = [0u8; 32];
+    bytes[(32 - keep)..].copy_from_slice(&digest[(32 - keep)..]);
+
+    read_field_element_be_from(&mut &bytes[..])
+}
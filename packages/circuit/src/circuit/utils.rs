@@ -35,3 +35,26 @@ pub fn write_field_element_be_into<F: PrimeField, W: Write>(
 
     Ok(())
 }
+
+/// Encode a field element as a `0x`-prefixed big-endian hex string.
+pub fn field_element_to_hex<F: PrimeField>(value: F) -> String {
+    let mut writer = vec![];
+    write_field_element_be_into(value, &mut writer).unwrap();
+
+    "0x".to_string() + &hex::encode(writer)
+}
+
+/// Decode a `0x`-prefixed big-endian hex string into a field element.
+pub fn field_element_from_hex<F: PrimeField>(value: &str) -> anyhow::Result<F> {
+    let raw = value
+        .strip_prefix("0x")
+        .ok_or_else(|| anyhow::anyhow!("hex string must start with 0x: {}", value))?;
+    let bytes = hex::decode(raw)?;
+    anyhow::ensure!(
+        bytes.len() == F::Repr::default().as_ref().len() * 8,
+        "invalid length of hex string: {}",
+        value
+    );
+
+    read_field_element_be_from(&mut bytes.as_slice())
+}
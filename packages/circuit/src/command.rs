@@ -1,11 +1,28 @@
+use std::fs::File;
+use std::io::BufReader;
 use std::path::PathBuf;
 
+use franklin_crypto::bellman::pairing::bn256::Bn256;
+use franklin_crypto::bellman::plonk::better_better_cs::proof::Proof;
+use franklin_crypto::bellman::plonk::better_better_cs::setup::VerificationKey;
+
 use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
 
+use crate::api::format::CircuitKind;
+use crate::api::solidity::{
+    encode_calldata, render_verifier, AnyCircuit, PLONK_CORE_FILE_NAME, PLONK_CORE_SOURCE,
+};
+use crate::api::stats::profile_circuit;
 use crate::crs::create_crs;
 
 #[derive(Debug, StructOpt)]
 struct Cli {
+    /// Emit the logs and the span timings as JSON lines, e.g. for proving-time dashboards.
+    /// The levels are filtered by `RUST_LOG`, which defaults to `info`.
+    #[structopt(long, global = true)]
+    pub json_logs: bool,
     #[structopt(subcommand)]
     pub subcommand: SubCommand,
 }
@@ -14,6 +31,10 @@ struct Cli {
 enum SubCommand {
     #[structopt(name = "crs")]
     Crs(CrsCommand),
+    #[structopt(name = "circuit")]
+    Circuit(CircuitCommand),
+    #[structopt(name = "verifier")]
+    Verifier(VerifierCommand),
     // #[structopt(name = "setup")]
     // Setup {
     //     #[structopt(parse(from_os_str))]
@@ -54,13 +75,110 @@ enum CrsCommand {
     },
 }
 
+#[derive(Debug, StructOpt)]
+enum CircuitCommand {
+    /// Print the number of gates in each namespace of a circuit.
+    #[structopt(name = "stats")]
+    Stats {
+        /// poseidon, ipa-fr, ipa-fs, batch-proof-fr, batch-proof-fs or discrete-log
+        #[structopt(long)]
+        circuit: CircuitKind,
+        /// The arity for poseidon, which is ignored for discrete-log
+        #[structopt(long)]
+        domain_size: usize,
+    },
+}
+
+/// Write the events and the closed spans with their durations into stderr.
+fn init_tracing(json_logs: bool) {
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr);
+    if json_logs {
+        builder.json().init();
+    } else {
+        builder.init();
+    }
+}
+
+#[derive(Debug, StructOpt)]
+enum VerifierCommand {
+    /// Render a Solidity verifier for a verification key written by `VerificationKey::write`.
+    ///
+    /// The verifier supports only the main gate with the range check gate,
+    /// i.e. the keys of 2 gate selectors without lookup tables, and the other keys are rejected.
+    /// A key does not record its gates, so the one of another pair of gates,
+    /// e.g. the main gate with the Rescue5 S-box gate, is not detected and its verifier rejects every proof.
+    #[structopt(name = "export-solidity")]
+    ExportSolidity {
+        /// poseidon, ipa-fr, ipa-fs, batch-proof-fr, batch-proof-fs or discrete-log,
+        /// which is named in the error if the key is not supported
+        #[structopt(long)]
+        circuit: CircuitKind,
+        #[structopt(long, parse(from_os_str))]
+        vk: PathBuf,
+        /// Print the contract if omitted. Otherwise, `PlonkCore.sol` is written next to it.
+        #[structopt(short, long, parse(from_os_str))]
+        output: Option<PathBuf>,
+        #[structopt(long, default_value = "Verifier")]
+        contract_name: String,
+    },
+    /// Print the calldata of `verify_serialized_proof` for a proof written by `Proof::write`.
+    #[structopt(name = "calldata")]
+    Calldata {
+        #[structopt(long, parse(from_os_str))]
+        proof: PathBuf,
+    },
+}
+
 pub fn invoke_command() -> anyhow::Result<()> {
-    match Cli::from_args().subcommand {
+    let cli = Cli::from_args();
+    init_tracing(cli.json_logs);
+
+    match cli.subcommand {
         SubCommand::Crs(crs_command) => match crs_command {
             CrsCommand::Create { log2_size, path } => {
                 create_crs(log2_size, &path);
             }
         },
+        SubCommand::Circuit(circuit_command) => match circuit_command {
+            CircuitCommand::Stats {
+                circuit,
+                domain_size,
+            } => {
+                let profile = profile_circuit(circuit, domain_size)?;
+                println!("{} (domain size {})", circuit.name(), domain_size);
+                println!("{}", profile);
+            }
+        },
+        SubCommand::Verifier(verifier_command) => match verifier_command {
+            VerifierCommand::ExportSolidity {
+                circuit,
+                vk,
+                output,
+                contract_name,
+            } => {
+                let vk =
+                    VerificationKey::<Bn256, AnyCircuit>::read(BufReader::new(File::open(vk)?))?;
+                let contract = render_verifier(&vk, circuit.name(), &contract_name)?;
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, contract)?;
+                        let core_path = path.with_file_name(PLONK_CORE_FILE_NAME);
+                        std::fs::write(core_path, PLONK_CORE_SOURCE)?;
+                    }
+                    None => println!("{}", contract),
+                }
+            }
+            VerifierCommand::Calldata { proof } => {
+                let proof = Proof::<Bn256, AnyCircuit>::read(BufReader::new(File::open(proof)?))?;
+                let calldata = encode_calldata(&proof)?;
+                println!("0x{}", hex::encode(calldata));
+            }
+        },
         // SubCommand::Setup { pk_path, vk_path } => {
         //     // crate::api::ipa::setup::generate_random_parameters_with_file(&pk_path, &vk_path)?;
         //     generate_random_parameters_with_file(&pk_path, &vk_path)?;
@@ -0,0 +1,130 @@
+use std::fmt;
+
+use franklin_crypto::bellman::SynthesisError;
+
+use crate::api::format::FormatError;
+use crate::circuit::debug::UnsatisfiedGate;
+
+/// The error which is returned from the `api` functions and the gadgets checking proofs.
+///
+/// `InvalidInputShape`, `InvalidPoint` and `CrsTooSmall` are caused by the inputs of the caller,
+/// while `Synthesis` usually means a bug in a circuit.
+#[derive(Debug)]
+pub enum CircuitError {
+    /// The lengths of the inputs do not fit the circuit, e.g. the number of IPA rounds.
+    InvalidInputShape(String),
+    /// A point in the inputs is not on the curve.
+    InvalidPoint(String),
+    /// The CRS has fewer bases than the domain of the circuit.
+    CrsTooSmall {
+        required: usize,
+        available: usize,
+    },
+    /// The witness does not satisfy the constraints.
+    Unsatisfied,
+    /// The witness does not satisfy the gate, which is the first unsatisfied one.
+    UnsatisfiedGate(UnsatisfiedGate),
+    /// The verifier of the proofs of `circuit` cannot be made, e.g. the Solidity one for its custom gates.
+    UnsupportedCircuit {
+        circuit: String,
+        reason: String,
+    },
+    /// The proof at `index` is rejected, or the accumulated check of many proofs fails if `index` is `None`.
+    InvalidProof {
+        index: Option<usize>,
+    },
+    /// The inputs, the proof or the verification key cannot be serialized or deserialized.
+    Serialization(FormatError),
+    Io(std::io::Error),
+    Synthesis(SynthesisError),
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidInputShape(message) => write!(f, "invalid input shape: {}", message),
+            Self::InvalidPoint(message) => write!(f, "invalid point: {}", message),
+            Self::CrsTooSmall {
+                required,
+                available,
+            } => write!(
+                f,
+                "CRS is too small: {} bases are required, but {} are available",
+                required, available
+            ),
+            Self::Unsatisfied => write!(f, "the witness does not satisfy the constraints"),
+            Self::UnsatisfiedGate(gate) => {
+                write!(f, "the witness does not satisfy the constraints: {}", gate)
+            }
+            Self::UnsupportedCircuit { circuit, reason } => {
+                write!(f, "the circuit {} is not supported: {}", circuit, reason)
+            }
+            Self::InvalidProof { index: Some(index) } => write!(f, "proof {} is invalid", index),
+            Self::InvalidProof { index: None } => {
+                write!(f, "the accumulated check failed")
+            }
+            Self::Serialization(err) => write!(f, "serialization failed: {}", err),
+            Self::Io(err) => write!(f, "{}", err),
+            Self::Synthesis(err) => write!(f, "synthesis failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Serialization(err) => Some(err),
+            Self::Io(err) => Some(err),
+            Self::Synthesis(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<FormatError> for CircuitError {
+    fn from(err: FormatError) -> Self {
+        Self::Serialization(err)
+    }
+}
+
+impl From<std::io::Error> for CircuitError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<SynthesisError> for CircuitError {
+    /// A `CircuitError` which has been wrapped by `From<CircuitError> for SynthesisError` is unwrapped.
+    fn from(err: SynthesisError) -> Self {
+        match err {
+            SynthesisError::IoError(err) => {
+                let is_circuit_error =
+                    matches!(err.get_ref(), Some(inner) if inner.is::<CircuitError>());
+                if is_circuit_error {
+                    *err.into_inner()
+                        .unwrap()
+                        .downcast::<CircuitError>()
+                        .unwrap()
+                } else {
+                    Self::Synthesis(SynthesisError::IoError(err))
+                }
+            }
+            SynthesisError::Unsatisfiable => Self::Unsatisfied,
+            err => Self::Synthesis(err),
+        }
+    }
+}
+
+impl From<CircuitError> for SynthesisError {
+    /// `Circuit::synthesize` must return `SynthesisError`,
+    /// so any other error is wrapped in `SynthesisError::IoError`.
+    fn from(err: CircuitError) -> Self {
+        match err {
+            CircuitError::Synthesis(err) => err,
+            CircuitError::Unsatisfied => SynthesisError::Unsatisfiable,
+            err => {
+                SynthesisError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            }
+        }
+    }
+}
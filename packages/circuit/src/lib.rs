@@ -2,3 +2,4 @@ pub mod api;
 pub mod circuit;
 pub mod command;
 pub mod crs;
+pub mod error;
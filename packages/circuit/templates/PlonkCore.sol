@@ -0,0 +1,871 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+pragma solidity >=0.8.0;
+
+// The PlonK verifier core for `better_better_cs` proofs of `Width4MainGateWithDNext`
+// with `TwoBitDecompositionRangecheckCustomGate`, following matter-labs' PlonkCore.sol.
+// The gates must be declared in this order, and no lookup tables may be used.
+
+library PairingsBn254 {
+    uint256 constant q_mod = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+    uint256 constant r_mod = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+    uint256 constant bn254_b_coeff = 3;
+
+    struct G1Point {
+        uint256 X;
+        uint256 Y;
+    }
+
+    struct Fr {
+        uint256 value;
+    }
+
+    function new_fr(uint256 fr) internal pure returns (Fr memory) {
+        require(fr < r_mod, "not a field element");
+        return Fr({value: fr});
+    }
+
+    function copy(Fr memory self) internal pure returns (Fr memory n) {
+        n.value = self.value;
+    }
+
+    function assign(Fr memory self, Fr memory other) internal pure {
+        self.value = other.value;
+    }
+
+    function inverse(Fr memory fr) internal view returns (Fr memory) {
+        require(fr.value != 0, "cannot invert zero");
+        return pow(fr, r_mod - 2);
+    }
+
+    function add_assign(Fr memory self, Fr memory other) internal pure {
+        self.value = addmod(self.value, other.value, r_mod);
+    }
+
+    function sub_assign(Fr memory self, Fr memory other) internal pure {
+        self.value = addmod(self.value, r_mod - other.value, r_mod);
+    }
+
+    function mul_assign(Fr memory self, Fr memory other) internal pure {
+        self.value = mulmod(self.value, other.value, r_mod);
+    }
+
+    function pow(Fr memory self, uint256 power) internal view returns (Fr memory) {
+        uint256[6] memory input = [32, 32, 32, self.value, power, r_mod];
+        uint256[1] memory result;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x05, input, 0xc0, result, 0x20)
+        }
+        require(success, "modexp failed");
+        return Fr({value: result[0]});
+    }
+
+    // The coefficients of `Fq2` are encoded as `X[0] * u + X[1]`, which the pairing precompile expects.
+    struct G2Point {
+        uint256[2] X;
+        uint256[2] Y;
+    }
+
+    function P1() internal pure returns (G1Point memory) {
+        return G1Point(1, 2);
+    }
+
+    function new_g1(uint256 x, uint256 y) internal pure returns (G1Point memory) {
+        return G1Point(x, y);
+    }
+
+    // The point at infinity is encoded as `(0, 0)`.
+    function new_g1_checked(uint256 x, uint256 y) internal pure returns (G1Point memory) {
+        if (x == 0 && y == 0) {
+            return G1Point(x, y);
+        }
+
+        require(x < q_mod, "x is not in the base field");
+        require(y < q_mod, "y is not in the base field");
+        uint256 lhs = mulmod(y, y, q_mod); // y^2
+        uint256 rhs = mulmod(x, x, q_mod); // x^2
+        rhs = mulmod(rhs, x, q_mod); // x^3
+        rhs = addmod(rhs, bn254_b_coeff, q_mod); // x^3 + b
+        require(lhs == rhs, "the point is not on the curve");
+
+        return G1Point(x, y);
+    }
+
+    function new_g2(uint256[2] memory x, uint256[2] memory y) internal pure returns (G2Point memory) {
+        return G2Point(x, y);
+    }
+
+    function copy_g1(G1Point memory self) internal pure returns (G1Point memory result) {
+        result.X = self.X;
+        result.Y = self.Y;
+    }
+
+    function P2() internal pure returns (G2Point memory) {
+        return
+            G2Point(
+                [
+                    0x198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2,
+                    0x1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed
+                ],
+                [
+                    0x090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b,
+                    0x12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa
+                ]
+            );
+    }
+
+    function negate(G1Point memory self) internal pure {
+        if (self.Y == 0) {
+            require(self.X == 0, "invalid point");
+            return;
+        }
+
+        self.Y = q_mod - self.Y;
+    }
+
+    function point_add(G1Point memory p1, G1Point memory p2) internal view returns (G1Point memory r) {
+        point_add_into_dest(p1, p2, r);
+        return r;
+    }
+
+    function point_add_assign(G1Point memory p1, G1Point memory p2) internal view {
+        point_add_into_dest(p1, p2, p1);
+    }
+
+    function point_add_into_dest(
+        G1Point memory p1,
+        G1Point memory p2,
+        G1Point memory dest
+    ) internal view {
+        if (p2.X == 0 && p2.Y == 0) {
+            dest.X = p1.X;
+            dest.Y = p1.Y;
+            return;
+        } else if (p1.X == 0 && p1.Y == 0) {
+            dest.X = p2.X;
+            dest.Y = p2.Y;
+            return;
+        } else {
+            uint256[4] memory input;
+            input[0] = p1.X;
+            input[1] = p1.Y;
+            input[2] = p2.X;
+            input[3] = p2.Y;
+            bool success;
+            assembly {
+                success := staticcall(gas(), 6, input, 0x80, dest, 0x40)
+            }
+            require(success, "ecAdd failed");
+        }
+    }
+
+    function point_sub_assign(G1Point memory p1, G1Point memory p2) internal view {
+        point_sub_into_dest(p1, p2, p1);
+    }
+
+    function point_sub_into_dest(
+        G1Point memory p1,
+        G1Point memory p2,
+        G1Point memory dest
+    ) internal view {
+        if (p2.X == 0 && p2.Y == 0) {
+            dest.X = p1.X;
+            dest.Y = p1.Y;
+            return;
+        } else if (p1.X == 0 && p1.Y == 0) {
+            dest.X = p2.X;
+            dest.Y = q_mod - p2.Y;
+            return;
+        } else {
+            uint256[4] memory input;
+            input[0] = p1.X;
+            input[1] = p1.Y;
+            input[2] = p2.X;
+            input[3] = q_mod - p2.Y;
+            bool success;
+            assembly {
+                success := staticcall(gas(), 6, input, 0x80, dest, 0x40)
+            }
+            require(success, "ecAdd failed");
+        }
+    }
+
+    function point_mul(G1Point memory p, Fr memory s) internal view returns (G1Point memory r) {
+        point_mul_into_dest(p, s, r);
+        return r;
+    }
+
+    function point_mul_assign(G1Point memory p, Fr memory s) internal view {
+        point_mul_into_dest(p, s, p);
+    }
+
+    function point_mul_into_dest(
+        G1Point memory p,
+        Fr memory s,
+        G1Point memory dest
+    ) internal view {
+        uint256[3] memory input;
+        input[0] = p.X;
+        input[1] = p.Y;
+        input[2] = s.value;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 7, input, 0x60, dest, 0x40)
+        }
+        require(success, "ecMul failed");
+    }
+
+    function pairing(G1Point[] memory p1, G2Point[] memory p2) internal view returns (bool) {
+        require(p1.length == p2.length, "mismatched lengths");
+        uint256 elements = p1.length;
+        uint256 inputSize = elements * 6;
+        uint256[] memory input = new uint256[](inputSize);
+        for (uint256 i = 0; i < elements; i++) {
+            input[i * 6 + 0] = p1[i].X;
+            input[i * 6 + 1] = p1[i].Y;
+            input[i * 6 + 2] = p2[i].X[0];
+            input[i * 6 + 3] = p2[i].X[1];
+            input[i * 6 + 4] = p2[i].Y[0];
+            input[i * 6 + 5] = p2[i].Y[1];
+        }
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 8, add(input, 0x20), mul(inputSize, 0x20), out, 0x20)
+        }
+        require(success, "ecPairing failed");
+        return out[0] != 0;
+    }
+
+    /// Convenience method for a pairing check for two pairs.
+    function pairingProd2(
+        G1Point memory a1,
+        G2Point memory a2,
+        G1Point memory b1,
+        G2Point memory b2
+    ) internal view returns (bool) {
+        G1Point[] memory p1 = new G1Point[](2);
+        G2Point[] memory p2 = new G2Point[](2);
+        p1[0] = a1;
+        p1[1] = b1;
+        p2[0] = a2;
+        p2[1] = b2;
+        return pairing(p1, p2);
+    }
+}
+
+// The counterpart of `RollingKeccakTranscript` of bellman.
+library TranscriptLibrary {
+    // The challenges are the lower 253 bits of the hash, so that they are less than `r_mod`.
+    uint256 constant FR_MASK = 0x1fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff;
+
+    uint32 constant DST_0 = 0;
+    uint32 constant DST_1 = 1;
+    uint32 constant DST_CHALLENGE = 2;
+
+    struct Transcript {
+        bytes32 state_0;
+        bytes32 state_1;
+        uint32 challenge_counter;
+    }
+
+    function new_transcript() internal pure returns (Transcript memory t) {
+        t.state_0 = bytes32(0);
+        t.state_1 = bytes32(0);
+        t.challenge_counter = 0;
+    }
+
+    function update_with_u256(Transcript memory self, uint256 value) internal pure {
+        bytes32 old_state_0 = self.state_0;
+        self.state_0 = keccak256(abi.encodePacked(DST_0, old_state_0, self.state_1, value));
+        self.state_1 = keccak256(abi.encodePacked(DST_1, old_state_0, self.state_1, value));
+    }
+
+    function update_with_fr(Transcript memory self, PairingsBn254.Fr memory value) internal pure {
+        update_with_u256(self, value.value);
+    }
+
+    function update_with_g1(Transcript memory self, PairingsBn254.G1Point memory p) internal pure {
+        update_with_u256(self, p.X);
+        update_with_u256(self, p.Y);
+    }
+
+    function get_challenge(Transcript memory self) internal pure returns (PairingsBn254.Fr memory challenge) {
+        bytes32 query = keccak256(abi.encodePacked(DST_CHALLENGE, self.state_0, self.state_1, self.challenge_counter));
+        self.challenge_counter += 1;
+        challenge = PairingsBn254.Fr({value: uint256(query) & FR_MASK});
+    }
+}
+
+contract Plonk4VerifierWithAccessToDNext {
+    using PairingsBn254 for PairingsBn254.G1Point;
+    using PairingsBn254 for PairingsBn254.G2Point;
+    using PairingsBn254 for PairingsBn254.Fr;
+
+    using TranscriptLibrary for TranscriptLibrary.Transcript;
+
+    uint256 constant ZERO = 0;
+    uint256 constant ONE = 1;
+    uint256 constant TWO = 2;
+    uint256 constant THREE = 3;
+    uint256 constant FOUR = 4;
+
+    uint256 constant STATE_WIDTH = 4;
+    uint256 constant NUM_DIFFERENT_GATES = 2;
+    uint256 constant NUM_SETUP_POLYS_FOR_MAIN_GATE = 7;
+    uint256 constant NUM_SETUP_POLYS_RANGE_CHECK_GATE = 0;
+    uint256 constant ACCESSIBLE_STATE_POLYS_ON_NEXT_STEP = 1;
+    uint256 constant NUM_GATE_SELECTORS_OPENED_EXPLICITLY = 1;
+
+    // The number of words of `serialized_proof`, which `serialize_proof` of the crate writes.
+    uint256 constant SERIALIZED_PROOF_LENGTH = 34;
+
+    struct VerificationKey {
+        uint256 domain_size;
+        uint256 num_inputs;
+        PairingsBn254.Fr omega;
+        PairingsBn254.G1Point[NUM_SETUP_POLYS_FOR_MAIN_GATE + NUM_SETUP_POLYS_RANGE_CHECK_GATE] gate_setup_commitments;
+        PairingsBn254.G1Point[NUM_DIFFERENT_GATES] gate_selector_commitments;
+        PairingsBn254.G1Point[STATE_WIDTH] copy_permutation_commitments;
+        PairingsBn254.Fr[STATE_WIDTH - 1] copy_permutation_non_residues;
+        PairingsBn254.G2Point g2_x;
+    }
+
+    struct Proof {
+        uint256[] input_values;
+        PairingsBn254.G1Point[STATE_WIDTH] wire_commitments;
+        PairingsBn254.G1Point copy_permutation_grand_product_commitment;
+        PairingsBn254.G1Point[STATE_WIDTH] quotient_poly_commitments;
+        PairingsBn254.Fr[STATE_WIDTH] wire_values_at_z;
+        PairingsBn254.Fr[ACCESSIBLE_STATE_POLYS_ON_NEXT_STEP] wire_values_at_z_omega;
+        PairingsBn254.Fr[NUM_GATE_SELECTORS_OPENED_EXPLICITLY] gate_selector_values_at_z;
+        PairingsBn254.Fr copy_grand_product_at_z_omega;
+        PairingsBn254.Fr quotient_polynomial_at_z;
+        PairingsBn254.Fr linearization_polynomial_at_z;
+        PairingsBn254.Fr[STATE_WIDTH - 1] permutation_polynomials_at_z;
+        PairingsBn254.G1Point opening_at_z_proof;
+        PairingsBn254.G1Point opening_at_z_omega_proof;
+    }
+
+    struct PartialVerifierState {
+        PairingsBn254.Fr alpha;
+        PairingsBn254.Fr beta;
+        PairingsBn254.Fr gamma;
+        PairingsBn254.Fr v;
+        PairingsBn254.Fr u;
+        PairingsBn254.Fr z;
+        PairingsBn254.Fr[] cached_lagrange_evals;
+    }
+
+    function evaluate_lagrange_poly_out_of_domain(
+        uint256 poly_num,
+        uint256 domain_size,
+        PairingsBn254.Fr memory omega,
+        PairingsBn254.Fr memory at
+    ) internal view returns (PairingsBn254.Fr memory res) {
+        require(poly_num < domain_size, "the index is out of the domain");
+        PairingsBn254.Fr memory one = PairingsBn254.new_fr(1);
+        PairingsBn254.Fr memory omega_power = omega.pow(poly_num);
+        res = at.pow(domain_size);
+        res.sub_assign(one);
+        require(res.value != 0, "the point is in the domain"); // the vanishing polynomial cannot be zero at `at`
+        res.mul_assign(omega_power);
+
+        PairingsBn254.Fr memory den = PairingsBn254.copy(at);
+        den.sub_assign(omega_power);
+        den.mul_assign(PairingsBn254.new_fr(domain_size));
+
+        den = den.inverse();
+
+        res.mul_assign(den);
+    }
+
+    function evaluate_vanishing(uint256 domain_size, PairingsBn254.Fr memory at)
+        internal
+        view
+        returns (PairingsBn254.Fr memory res)
+    {
+        res = at.pow(domain_size);
+        res.sub_assign(PairingsBn254.new_fr(1));
+    }
+
+    function verify_at_z(
+        PartialVerifierState memory state,
+        Proof memory proof,
+        VerificationKey memory vk
+    ) internal view returns (bool) {
+        PairingsBn254.Fr memory lhs = evaluate_vanishing(vk.domain_size, state.z);
+        require(lhs.value != 0, "the point is in the domain"); // the relation cannot be checked in the domain
+        lhs.mul_assign(proof.quotient_polynomial_at_z);
+
+        PairingsBn254.Fr memory quotient_challenge = PairingsBn254.new_fr(1);
+        PairingsBn254.Fr memory rhs = PairingsBn254.copy(proof.linearization_polynomial_at_z);
+
+        // public inputs
+        PairingsBn254.Fr memory tmp = PairingsBn254.new_fr(0);
+        PairingsBn254.Fr memory inputs_term = PairingsBn254.new_fr(0);
+        for (uint256 i = 0; i < proof.input_values.length; i++) {
+            tmp.assign(state.cached_lagrange_evals[i]);
+            tmp.mul_assign(PairingsBn254.new_fr(proof.input_values[i]));
+            inputs_term.add_assign(tmp);
+        }
+
+        inputs_term.mul_assign(proof.gate_selector_values_at_z[0]);
+        rhs.add_assign(inputs_term);
+
+        // The main gate takes `alpha^0` and the range check gate takes `alpha^1` to `alpha^4`,
+        // so the copy permutation starts from `alpha^5`.
+        quotient_challenge.mul_assign(state.alpha);
+        quotient_challenge.mul_assign(state.alpha);
+        quotient_challenge.mul_assign(state.alpha);
+        quotient_challenge.mul_assign(state.alpha);
+        quotient_challenge.mul_assign(state.alpha);
+
+        PairingsBn254.Fr memory z_part = PairingsBn254.copy(proof.copy_grand_product_at_z_omega);
+        for (uint256 i = 0; i < proof.permutation_polynomials_at_z.length; i++) {
+            tmp.assign(proof.permutation_polynomials_at_z[i]);
+            tmp.mul_assign(state.beta);
+            tmp.add_assign(state.gamma);
+            tmp.add_assign(proof.wire_values_at_z[i]);
+
+            z_part.mul_assign(tmp);
+        }
+
+        tmp.assign(state.gamma);
+        // the value of the last wire, whose permutation polynomial is linearized
+        tmp.add_assign(proof.wire_values_at_z[STATE_WIDTH - 1]);
+
+        z_part.mul_assign(tmp);
+        z_part.mul_assign(quotient_challenge);
+
+        rhs.sub_assign(z_part);
+
+        quotient_challenge.mul_assign(state.alpha);
+
+        tmp.assign(state.cached_lagrange_evals[0]);
+        tmp.mul_assign(quotient_challenge);
+
+        rhs.sub_assign(tmp);
+
+        return lhs.value == rhs.value;
+    }
+
+    // The scalar of the selector of the range check gate, whose constraints are
+    // `(c - 4d)`, `(b - 4c)`, `(a - 4b)` and `(d_next - 4a)` in `{0, 1, 2, 3}`.
+    function add_contribution_from_range_constraint_gates(
+        PartialVerifierState memory state,
+        Proof memory proof,
+        PairingsBn254.Fr memory current_alpha
+    ) internal pure returns (PairingsBn254.Fr memory res) {
+        res = PairingsBn254.new_fr(0);
+        PairingsBn254.Fr memory t1;
+        for (uint256 i = 0; i < 3; i++) {
+            current_alpha.mul_assign(state.alpha);
+
+            // high - 4 * low
+            PairingsBn254.Fr memory t0 = PairingsBn254.copy(proof.wire_values_at_z[3 - i]);
+            t0.mul_assign(PairingsBn254.new_fr(FOUR));
+            t1 = PairingsBn254.copy(proof.wire_values_at_z[2 - i]);
+            t1.sub_assign(t0);
+
+            res.add_assign(range_check_term(t1, current_alpha));
+        }
+
+        current_alpha.mul_assign(state.alpha);
+
+        // d_next - 4 * a
+        PairingsBn254.Fr memory t2 = PairingsBn254.copy(proof.wire_values_at_z[0]);
+        t2.mul_assign(PairingsBn254.new_fr(FOUR));
+        t1 = PairingsBn254.copy(proof.wire_values_at_z_omega[0]);
+        t1.sub_assign(t2);
+
+        res.add_assign(range_check_term(t1, current_alpha));
+    }
+
+    // `alpha * t * (t - 1) * (t - 2) * (t - 3)`
+    function range_check_term(PairingsBn254.Fr memory t, PairingsBn254.Fr memory alpha)
+        internal
+        pure
+        returns (PairingsBn254.Fr memory res)
+    {
+        res = PairingsBn254.copy(t);
+        PairingsBn254.Fr memory tmp;
+        for (uint256 j = 1; j < 4; j++) {
+            tmp = PairingsBn254.copy(t);
+            tmp.sub_assign(PairingsBn254.new_fr(j));
+            res.mul_assign(tmp);
+        }
+        res.mul_assign(alpha);
+    }
+
+    function reconstruct_linearization_commitment(
+        PartialVerifierState memory state,
+        Proof memory proof,
+        VerificationKey memory vk
+    ) internal view returns (PairingsBn254.G1Point memory res) {
+        // The main gate is linearized over its setup polynomials,
+        // and multiplied by the value of its selector.
+        res = PairingsBn254.copy_g1(vk.gate_setup_commitments[STATE_WIDTH + 1]); // q_const
+
+        PairingsBn254.G1Point memory tmp_g1 = PairingsBn254.P1();
+        PairingsBn254.Fr memory tmp_fr = PairingsBn254.new_fr(0);
+
+        // addition
+        for (uint256 i = 0; i < STATE_WIDTH; i++) {
+            tmp_g1 = vk.gate_setup_commitments[i].point_mul(proof.wire_values_at_z[i]);
+            res.point_add_assign(tmp_g1);
+        }
+
+        // multiplication
+        tmp_fr.assign(proof.wire_values_at_z[0]);
+        tmp_fr.mul_assign(proof.wire_values_at_z[1]);
+        tmp_g1 = vk.gate_setup_commitments[STATE_WIDTH].point_mul(tmp_fr);
+        res.point_add_assign(tmp_g1);
+
+        // d_next
+        tmp_g1 = vk.gate_setup_commitments[STATE_WIDTH + 2].point_mul(proof.wire_values_at_z_omega[0]);
+        res.point_add_assign(tmp_g1);
+
+        res.point_mul_assign(proof.gate_selector_values_at_z[0]);
+
+        // The range check gate is linearized over its selector.
+        PairingsBn254.Fr memory current_alpha = PairingsBn254.new_fr(ONE);
+        tmp_fr = add_contribution_from_range_constraint_gates(state, proof, current_alpha);
+        tmp_g1 = vk.gate_selector_commitments[1].point_mul(tmp_fr);
+        res.point_add_assign(tmp_g1);
+
+        // the copy permutation from `alpha^5`
+        current_alpha.mul_assign(state.alpha);
+
+        PairingsBn254.Fr memory alpha_for_grand_product = PairingsBn254.copy(current_alpha);
+
+        // (a + beta * z + gamma) * (b + beta * k_1 * z + gamma) * ...
+        PairingsBn254.Fr memory grand_product_part_at_z = PairingsBn254.copy(state.z);
+        grand_product_part_at_z.mul_assign(state.beta);
+        grand_product_part_at_z.add_assign(proof.wire_values_at_z[0]);
+        grand_product_part_at_z.add_assign(state.gamma);
+        for (uint256 i = 0; i < vk.copy_permutation_non_residues.length; i++) {
+            tmp_fr.assign(state.z);
+            tmp_fr.mul_assign(vk.copy_permutation_non_residues[i]);
+            tmp_fr.mul_assign(state.beta);
+            tmp_fr.add_assign(state.gamma);
+            tmp_fr.add_assign(proof.wire_values_at_z[i + 1]);
+
+            grand_product_part_at_z.mul_assign(tmp_fr);
+        }
+
+        grand_product_part_at_z.mul_assign(alpha_for_grand_product);
+
+        // L_0(z) * alpha^6
+        current_alpha.mul_assign(state.alpha);
+
+        tmp_fr.assign(state.cached_lagrange_evals[0]);
+        tmp_fr.mul_assign(current_alpha);
+
+        grand_product_part_at_z.add_assign(tmp_fr);
+
+        // (a + beta * perm_a + gamma) * ... * z(z * omega) * beta, which is multiplied by perm_d(x)
+        PairingsBn254.Fr memory last_permutation_part_at_z = PairingsBn254.new_fr(1);
+        for (uint256 i = 0; i < proof.permutation_polynomials_at_z.length; i++) {
+            tmp_fr.assign(state.beta);
+            tmp_fr.mul_assign(proof.permutation_polynomials_at_z[i]);
+            tmp_fr.add_assign(state.gamma);
+            tmp_fr.add_assign(proof.wire_values_at_z[i]);
+
+            last_permutation_part_at_z.mul_assign(tmp_fr);
+        }
+
+        last_permutation_part_at_z.mul_assign(state.beta);
+        last_permutation_part_at_z.mul_assign(proof.copy_grand_product_at_z_omega);
+        last_permutation_part_at_z.mul_assign(alpha_for_grand_product);
+
+        tmp_g1 = proof.copy_permutation_grand_product_commitment.point_mul(grand_product_part_at_z);
+        tmp_g1.point_sub_assign(vk.copy_permutation_commitments[STATE_WIDTH - 1].point_mul(last_permutation_part_at_z));
+
+        res.point_add_assign(tmp_g1);
+        // The linearization is opened with `v^1`.
+        res.point_mul_assign(state.v);
+    }
+
+    function aggregate_commitments(
+        PartialVerifierState memory state,
+        Proof memory proof,
+        VerificationKey memory vk
+    ) internal view returns (PairingsBn254.G1Point[2] memory res) {
+        PairingsBn254.Fr memory d = state.z.pow(vk.domain_size);
+
+        PairingsBn254.Fr memory tmp_fr = PairingsBn254.new_fr(1);
+
+        // t_0(x) + z^n * t_1(x) + z^{2n} * t_2(x) + z^{3n} * t_3(x)
+        PairingsBn254.G1Point memory commitment_aggregation = PairingsBn254.copy_g1(proof.quotient_poly_commitments[0]);
+        for (uint256 i = 1; i < proof.quotient_poly_commitments.length; i++) {
+            tmp_fr.mul_assign(d);
+            commitment_aggregation.point_add_assign(proof.quotient_poly_commitments[i].point_mul(tmp_fr));
+        }
+
+        PairingsBn254.Fr memory aggregation_challenge = PairingsBn254.new_fr(1);
+        aggregation_challenge.mul_assign(state.v);
+        commitment_aggregation.point_add_assign(reconstruct_linearization_commitment(state, proof, vk));
+
+        PairingsBn254.G1Point memory tmp_g1 = PairingsBn254.P1();
+
+        // the openings at `z`
+        for (uint256 i = 0; i < proof.wire_commitments.length; i++) {
+            aggregation_challenge.mul_assign(state.v);
+            tmp_g1 = proof.wire_commitments[i].point_mul(aggregation_challenge);
+            commitment_aggregation.point_add_assign(tmp_g1);
+        }
+
+        for (uint256 i = 0; i < NUM_GATE_SELECTORS_OPENED_EXPLICITLY; i++) {
+            aggregation_challenge.mul_assign(state.v);
+            tmp_g1 = vk.gate_selector_commitments[i].point_mul(aggregation_challenge);
+            commitment_aggregation.point_add_assign(tmp_g1);
+        }
+
+        for (uint256 i = 0; i < vk.copy_permutation_commitments.length - 1; i++) {
+            aggregation_challenge.mul_assign(state.v);
+            tmp_g1 = vk.copy_permutation_commitments[i].point_mul(aggregation_challenge);
+            commitment_aggregation.point_add_assign(tmp_g1);
+        }
+
+        // the openings at `z * omega`, which are multiplied by `u`
+        aggregation_challenge.mul_assign(state.v);
+        tmp_fr.assign(aggregation_challenge);
+        tmp_fr.mul_assign(state.u);
+        commitment_aggregation.point_add_assign(proof.copy_permutation_grand_product_commitment.point_mul(tmp_fr));
+
+        aggregation_challenge.mul_assign(state.v);
+        tmp_fr.assign(aggregation_challenge);
+        tmp_fr.mul_assign(state.u);
+        tmp_g1 = proof.wire_commitments[STATE_WIDTH - 1].point_mul(tmp_fr);
+        commitment_aggregation.point_add_assign(tmp_g1);
+
+        // the opened values in the same order
+        aggregation_challenge = PairingsBn254.new_fr(1);
+
+        PairingsBn254.Fr memory aggregated_value = PairingsBn254.copy(proof.quotient_polynomial_at_z);
+
+        aggregation_challenge.mul_assign(state.v);
+
+        tmp_fr.assign(proof.linearization_polynomial_at_z);
+        tmp_fr.mul_assign(aggregation_challenge);
+        aggregated_value.add_assign(tmp_fr);
+
+        for (uint256 i = 0; i < proof.wire_values_at_z.length; i++) {
+            aggregation_challenge.mul_assign(state.v);
+
+            tmp_fr.assign(proof.wire_values_at_z[i]);
+            tmp_fr.mul_assign(aggregation_challenge);
+            aggregated_value.add_assign(tmp_fr);
+        }
+
+        for (uint256 i = 0; i < proof.gate_selector_values_at_z.length; i++) {
+            aggregation_challenge.mul_assign(state.v);
+
+            tmp_fr.assign(proof.gate_selector_values_at_z[i]);
+            tmp_fr.mul_assign(aggregation_challenge);
+            aggregated_value.add_assign(tmp_fr);
+        }
+
+        for (uint256 i = 0; i < proof.permutation_polynomials_at_z.length; i++) {
+            aggregation_challenge.mul_assign(state.v);
+
+            tmp_fr.assign(proof.permutation_polynomials_at_z[i]);
+            tmp_fr.mul_assign(aggregation_challenge);
+            aggregated_value.add_assign(tmp_fr);
+        }
+
+        aggregation_challenge.mul_assign(state.v);
+
+        tmp_fr.assign(proof.copy_grand_product_at_z_omega);
+        tmp_fr.mul_assign(aggregation_challenge);
+        tmp_fr.mul_assign(state.u);
+        aggregated_value.add_assign(tmp_fr);
+
+        aggregation_challenge.mul_assign(state.v);
+
+        tmp_fr.assign(proof.wire_values_at_z_omega[0]);
+        tmp_fr.mul_assign(aggregation_challenge);
+        tmp_fr.mul_assign(state.u);
+        aggregated_value.add_assign(tmp_fr);
+
+        commitment_aggregation.point_sub_assign(PairingsBn254.P1().point_mul(aggregated_value));
+
+        // e(F + z * W + u * z * omega * W', [1]) == e(W + u * W', [x])
+        PairingsBn254.G1Point memory pair_with_generator = commitment_aggregation;
+        pair_with_generator.point_add_assign(proof.opening_at_z_proof.point_mul(state.z));
+
+        tmp_fr.assign(state.z);
+        tmp_fr.mul_assign(vk.omega);
+        tmp_fr.mul_assign(state.u);
+        pair_with_generator.point_add_assign(proof.opening_at_z_omega_proof.point_mul(tmp_fr));
+
+        PairingsBn254.G1Point memory pair_with_x = proof.opening_at_z_omega_proof.point_mul(state.u);
+        pair_with_x.point_add_assign(proof.opening_at_z_proof);
+        pair_with_x.negate();
+
+        res[0] = pair_with_generator;
+        res[1] = pair_with_x;
+
+        return res;
+    }
+
+    function verify_initial(
+        PartialVerifierState memory state,
+        Proof memory proof,
+        VerificationKey memory vk
+    ) internal view returns (bool) {
+        require(proof.input_values.length == vk.num_inputs, "invalid number of public inputs");
+        require(vk.num_inputs >= 1, "no public inputs");
+        TranscriptLibrary.Transcript memory transcript = TranscriptLibrary.new_transcript();
+        for (uint256 i = 0; i < vk.num_inputs; i++) {
+            transcript.update_with_u256(proof.input_values[i]);
+        }
+
+        for (uint256 i = 0; i < proof.wire_commitments.length; i++) {
+            transcript.update_with_g1(proof.wire_commitments[i]);
+        }
+
+        state.beta = transcript.get_challenge();
+        state.gamma = transcript.get_challenge();
+
+        transcript.update_with_g1(proof.copy_permutation_grand_product_commitment);
+        state.alpha = transcript.get_challenge();
+
+        for (uint256 i = 0; i < proof.quotient_poly_commitments.length; i++) {
+            transcript.update_with_g1(proof.quotient_poly_commitments[i]);
+        }
+
+        state.z = transcript.get_challenge();
+
+        state.cached_lagrange_evals = new PairingsBn254.Fr[](vk.num_inputs);
+        for (uint256 i = 0; i < vk.num_inputs; i++) {
+            state.cached_lagrange_evals[i] = evaluate_lagrange_poly_out_of_domain(i, vk.domain_size, vk.omega, state.z);
+        }
+
+        bool valid = verify_at_z(state, proof, vk);
+
+        if (valid == false) {
+            return false;
+        }
+
+        transcript.update_with_fr(proof.quotient_polynomial_at_z);
+
+        for (uint256 i = 0; i < proof.wire_values_at_z.length; i++) {
+            transcript.update_with_fr(proof.wire_values_at_z[i]);
+        }
+
+        for (uint256 i = 0; i < proof.wire_values_at_z_omega.length; i++) {
+            transcript.update_with_fr(proof.wire_values_at_z_omega[i]);
+        }
+
+        for (uint256 i = 0; i < proof.gate_selector_values_at_z.length; i++) {
+            transcript.update_with_fr(proof.gate_selector_values_at_z[i]);
+        }
+
+        for (uint256 i = 0; i < proof.permutation_polynomials_at_z.length; i++) {
+            transcript.update_with_fr(proof.permutation_polynomials_at_z[i]);
+        }
+
+        transcript.update_with_fr(proof.copy_grand_product_at_z_omega);
+        transcript.update_with_fr(proof.linearization_polynomial_at_z);
+
+        state.v = transcript.get_challenge();
+        transcript.update_with_g1(proof.opening_at_z_proof);
+        transcript.update_with_g1(proof.opening_at_z_omega_proof);
+        state.u = transcript.get_challenge();
+
+        return true;
+    }
+
+    function verify(Proof memory proof, VerificationKey memory vk) internal view returns (bool) {
+        PartialVerifierState memory state;
+
+        bool valid = verify_initial(state, proof, vk);
+
+        if (valid == false) {
+            return false;
+        }
+
+        PairingsBn254.G1Point[2] memory aggregated = aggregate_commitments(state, proof, vk);
+
+        return PairingsBn254.pairingProd2(aggregated[0], PairingsBn254.P2(), aggregated[1], vk.g2_x);
+    }
+
+    // Read the words in the order in which `serialize_proof` of the crate writes them.
+    function deserialize_proof(uint256[] memory public_inputs, uint256[] memory serialized_proof)
+        internal
+        pure
+        returns (Proof memory proof)
+    {
+        require(serialized_proof.length == SERIALIZED_PROOF_LENGTH, "invalid proof length");
+        proof.input_values = new uint256[](public_inputs.length);
+        for (uint256 i = 0; i < public_inputs.length; i++) {
+            proof.input_values[i] = public_inputs[i];
+        }
+
+        uint256 j = 0;
+        for (uint256 i = 0; i < STATE_WIDTH; i++) {
+            proof.wire_commitments[i] = PairingsBn254.new_g1_checked(serialized_proof[j], serialized_proof[j + 1]);
+
+            j += 2;
+        }
+
+        proof.copy_permutation_grand_product_commitment = PairingsBn254.new_g1_checked(
+            serialized_proof[j],
+            serialized_proof[j + 1]
+        );
+        j += 2;
+
+        for (uint256 i = 0; i < STATE_WIDTH; i++) {
+            proof.quotient_poly_commitments[i] = PairingsBn254.new_g1_checked(
+                serialized_proof[j],
+                serialized_proof[j + 1]
+            );
+
+            j += 2;
+        }
+
+        for (uint256 i = 0; i < STATE_WIDTH; i++) {
+            proof.wire_values_at_z[i] = PairingsBn254.new_fr(serialized_proof[j]);
+
+            j += 1;
+        }
+
+        for (uint256 i = 0; i < proof.wire_values_at_z_omega.length; i++) {
+            proof.wire_values_at_z_omega[i] = PairingsBn254.new_fr(serialized_proof[j]);
+
+            j += 1;
+        }
+
+        for (uint256 i = 0; i < proof.gate_selector_values_at_z.length; i++) {
+            proof.gate_selector_values_at_z[i] = PairingsBn254.new_fr(serialized_proof[j]);
+
+            j += 1;
+        }
+
+        for (uint256 i = 0; i < proof.permutation_polynomials_at_z.length; i++) {
+            proof.permutation_polynomials_at_z[i] = PairingsBn254.new_fr(serialized_proof[j]);
+
+            j += 1;
+        }
+
+        proof.copy_grand_product_at_z_omega = PairingsBn254.new_fr(serialized_proof[j]);
+
+        j += 1;
+
+        proof.quotient_polynomial_at_z = PairingsBn254.new_fr(serialized_proof[j]);
+
+        j += 1;
+
+        proof.linearization_polynomial_at_z = PairingsBn254.new_fr(serialized_proof[j]);
+
+        j += 1;
+
+        proof.opening_at_z_proof = PairingsBn254.new_g1_checked(serialized_proof[j], serialized_proof[j + 1]);
+        j += 2;
+
+        proof.opening_at_z_omega_proof = PairingsBn254.new_g1_checked(serialized_proof[j], serialized_proof[j + 1]);
+    }
+}
@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// Generated by `verifier export-solidity`. Do not edit by hand.
+
+pragma solidity >=0.8.0;
+
+// The PlonK verifier core for `Width4MainGateWithDNext` with the range check custom gate,
+// as in matter-labs' solidity_plonk_verifier.
+import "./PlonkCore.sol";
+
+contract {{contract_name}} is Plonk4VerifierWithAccessToDNext {
+    uint256 constant NUM_INPUTS = {{num_inputs}};
+
+    function verify_serialized_proof(
+        uint256[] memory public_inputs,
+        uint256[] memory serialized_proof
+    ) public view returns (bool) {
+        require(public_inputs.length == NUM_INPUTS, "invalid number of public inputs");
+        VerificationKey memory vk = get_verification_key();
+        Proof memory proof = deserialize_proof(public_inputs, serialized_proof);
+
+        return verify(proof, vk);
+    }
+
+    function get_verification_key() internal pure returns (VerificationKey memory vk) {
+{{verification_key}}
+    }
+}